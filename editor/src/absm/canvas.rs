@@ -75,6 +75,11 @@ pub(super) enum AbsmCanvasMessage {
     CommitDrag {
         entries: Vec<Entry>,
     },
+    // Occurs when user drags an input socket of a node onto another input socket of the same node.
+    CommitSocketReorder {
+        source_socket: Handle<UiNode>,
+        dest_socket: Handle<UiNode>,
+    },
     SelectionChanged(Vec<Handle<UiNode>>),
     ForceSyncDependentObjects,
 }
@@ -84,6 +89,7 @@ impl AbsmCanvasMessage {
     define_constructor!(AbsmCanvasMessage:CommitTransition => fn commit_transition(source_node: Handle<UiNode>, dest_node: Handle<UiNode>), layout: false);
     define_constructor!(AbsmCanvasMessage:CommitConnection => fn commit_connection(source_socket: Handle<UiNode>, dest_socket: Handle<UiNode>), layout: false);
     define_constructor!(AbsmCanvasMessage:CommitDrag => fn commit_drag(entries: Vec<Entry>), layout: false);
+    define_constructor!(AbsmCanvasMessage:CommitSocketReorder => fn commit_socket_reorder(source_socket: Handle<UiNode>, dest_socket: Handle<UiNode>), layout: false);
     define_constructor!(AbsmCanvasMessage:SelectionChanged => fn selection_changed(Vec<Handle<UiNode>>), layout: false);
     define_constructor!(AbsmCanvasMessage:ForceSyncDependentObjects => fn force_sync_dependent_objects(), layout: true);
 }
@@ -523,6 +529,19 @@ impl Control for AbsmCanvas {
                                     child,
                                     parent,
                                 ));
+                            } else if dest_socket_ref.parent_node == source_socket_ref.parent_node
+                                && dest_socket_ref.direction == SocketDirection::Input
+                                && source_socket_ref.direction == SocketDirection::Input
+                                && dest_socket_handle != source
+                            {
+                                // Dragging an input socket onto another input socket of the same
+                                // node reorders the inputs.
+                                ui.send_message(AbsmCanvasMessage::commit_socket_reorder(
+                                    self.handle(),
+                                    MessageDirection::FromWidget,
+                                    source,
+                                    dest_socket_handle,
+                                ));
                             }
                         }
 
@@ -5,6 +5,7 @@ use crate::{
 use fyrox::{
     animation::machine::node::{
         blend::{BlendPose, IndexedBlendInput},
+        blendspace::BlendSpacePoint,
         PoseNode,
     },
     core::pool::Handle,
@@ -27,6 +28,14 @@ define_push_element_to_collection_command!(AddPoseSourceCommand<Handle<PoseNode>
     }
 });
 
+define_push_element_to_collection_command!(AddBlendSpacePointCommand<Handle<PoseNode>, BlendSpacePoint>(self, context) {
+    let machine = fetch_machine(context, self.node_handle);
+    match &mut machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+        PoseNode::BlendSpace(definition) => &mut definition.points,
+        _ => unreachable!(),
+    }
+});
+
 define_set_collection_element_command!(
     SetBlendAnimationByIndexInputPoseSourceCommand<Handle<PoseNode>, Handle<PoseNode>>(self, context) {
         let machine = fetch_machine(context, self.node_handle);
@@ -50,3 +59,91 @@ define_set_collection_element_command!(
         }
     }
 );
+
+define_set_collection_element_command!(
+    SetBlendSpacePointPoseSourceCommand<Handle<PoseNode>, Handle<PoseNode>>(self, context) {
+        let machine = fetch_machine(context, self.node_handle);
+        match machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+            PoseNode::BlendSpace(ref mut definition) => {
+                &mut definition.points[self.index].pose_source
+            }
+            _ => unreachable!(),
+        }
+    }
+);
+
+// Moves an element of the collection from one position to another, preserving the order of the
+// rest of the elements.
+fn move_element<T>(collection: &mut Vec<T>, from: usize, to: usize) {
+    if from < collection.len() && to < collection.len() && from != to {
+        let element = collection.remove(from);
+        collection.insert(to, element);
+    }
+}
+
+#[derive(Debug)]
+pub struct MovePoseSourceCommand {
+    pub node_handle: Handle<Node>,
+    pub layer_index: usize,
+    pub handle: Handle<PoseNode>,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl MovePoseSourceCommand {
+    fn pose_sources<'a>(&self, context: &'a mut SceneContext) -> &'a mut Vec<BlendPose> {
+        let machine = fetch_machine(context, self.node_handle);
+        match machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+            PoseNode::BlendAnimations(ref mut definition) => &mut definition.pose_sources,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Command for MovePoseSourceCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Move Pose Source".to_string()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        let (from, to) = (self.from, self.to);
+        move_element(self.pose_sources(context), from, to);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        let (from, to) = (self.from, self.to);
+        move_element(self.pose_sources(context), to, from);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::absm::command::blend::move_element;
+
+    #[test]
+    fn test_move_element() {
+        let original = vec!["a", "b", "c"];
+
+        for (from, to, expected) in [
+            (0, 2, ["b", "c", "a"]),
+            (2, 0, ["c", "a", "b"]),
+            (1, 2, ["a", "c", "b"]),
+            (1, 1, ["a", "b", "c"]),
+        ] {
+            let mut collection = original.clone();
+
+            // Execute.
+            move_element(&mut collection, from, to);
+            assert_eq!(collection, expected);
+
+            // Revert.
+            move_element(&mut collection, to, from);
+            assert_eq!(collection, original);
+        }
+
+        // Out-of-bounds indices must leave the collection untouched.
+        let mut collection = original.clone();
+        move_element(&mut collection, 0, 3);
+        assert_eq!(collection, original);
+    }
+}
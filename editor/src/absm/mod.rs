@@ -1,6 +1,6 @@
 use crate::{
     absm::{
-        command::blend::{AddInputCommand, AddPoseSourceCommand},
+        command::blend::{AddBlendSpacePointCommand, AddInputCommand, AddPoseSourceCommand},
         node::{AbsmNode, AbsmNodeMessage},
         parameter::ParameterPanel,
         selection::AbsmSelection,
@@ -13,7 +13,9 @@ use crate::{
 };
 use fyrox::fxhash::FxHashSet;
 use fyrox::{
-    animation::machine::{BlendPose, Event, IndexedBlendInput, Machine, PoseNode, State},
+    animation::machine::{
+        BlendPose, BlendSpacePoint, Event, IndexedBlendInput, Machine, PoseNode, State,
+    },
     core::{color::Color, pool::Handle},
     engine::Engine,
     gui::{
@@ -52,6 +54,8 @@ const SELECTED_BACKGROUND: Color = Color::opaque(80, 80, 80);
 const BORDER_COLOR: Color = Color::opaque(70, 70, 70);
 const NORMAL_ROOT_COLOR: Color = Color::opaque(40, 80, 0);
 const SELECTED_ROOT_COLOR: Color = Color::opaque(60, 100, 0);
+const NORMAL_WARNING_COLOR: Color = Color::opaque(110, 70, 0);
+const SELECTED_WARNING_COLOR: Color = Color::opaque(130, 90, 0);
 
 struct PreviewModeData {
     machine: Machine,
@@ -510,6 +514,18 @@ impl AbsmEditor {
                                             )))
                                             .unwrap();
                                     }
+                                    PoseNode::BlendSpace(_) => {
+                                        sender
+                                            .send(Message::do_scene_command(
+                                                AddBlendSpacePointCommand::new(
+                                                    selection.absm_node_handle,
+                                                    node.model_handle,
+                                                    layer_index,
+                                                    BlendSpacePoint::default(),
+                                                ),
+                                            ))
+                                            .unwrap();
+                                    }
                                 }
                             }
                         }
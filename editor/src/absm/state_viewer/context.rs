@@ -2,7 +2,8 @@ use crate::{
     absm::{
         command::{
             blend::{
-                SetBlendAnimationByIndexInputPoseSourceCommand, SetBlendAnimationsPoseSourceCommand,
+                SetBlendAnimationByIndexInputPoseSourceCommand,
+                SetBlendAnimationsPoseSourceCommand, SetBlendSpacePointPoseSourceCommand,
             },
             AddPoseNodeCommand, DeletePoseNodeCommand, SetStateRootPoseCommand,
         },
@@ -15,30 +16,35 @@ use crate::{
         commands::{ChangeSelectionCommand, CommandGroup, SceneCommand},
         EditorScene, Selection,
     },
-    Message,
+    utils, Message,
 };
 use fyrox::{
     animation::machine::{
-        node::BasePoseNode, BlendAnimations, BlendAnimationsByIndex, MachineLayer, PlayAnimation,
-        PoseNode, State,
+        node::BasePoseNode, BlendAnimations, BlendAnimationsByIndex, BlendSpace, MachineLayer,
+        PlayAnimation, PoseNode, State,
     },
-    core::pool::Handle,
+    core::{algebra::Vector2, pool::Handle},
     gui::{
         menu::MenuItemMessage,
-        message::UiMessage,
+        message::{MessageDirection, UiMessage},
         popup::{Placement, PopupBuilder, PopupMessage},
         stack_panel::StackPanelBuilder,
+        text::TextMessage,
+        text_box::{TextBoxBuilder, TextCommitMode},
         widget::WidgetBuilder,
-        BuildContext, UiNode, UserInterface,
+        BuildContext, Thickness, UiNode, UserInterface,
     },
     scene::node::Node,
 };
 use std::sync::mpsc::Sender;
 
 pub struct CanvasContextMenu {
+    search_text: Handle<UiNode>,
+    items_panel: Handle<UiNode>,
     create_play_animation: Handle<UiNode>,
     create_blend_animations: Handle<UiNode>,
     create_blend_by_index: Handle<UiNode>,
+    create_blend_space: Handle<UiNode>,
     pub menu: Handle<UiNode>,
     pub canvas: Handle<UiNode>,
     pub node_context_menu: Handle<UiNode>,
@@ -46,9 +52,12 @@ pub struct CanvasContextMenu {
 
 impl CanvasContextMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
+        let search_text;
+        let items_panel;
         let create_play_animation;
         let create_blend_animations;
         let create_blend_by_index;
+        let create_blend_space;
         let menu = PopupBuilder::new(
             WidgetBuilder::new()
                 .with_enabled(false) // Disabled by default.
@@ -58,16 +67,41 @@ impl CanvasContextMenu {
             StackPanelBuilder::new(
                 WidgetBuilder::new()
                     .with_child({
-                        create_play_animation = create_menu_item("Play Animation", vec![], ctx);
-                        create_play_animation
+                        search_text = TextBoxBuilder::new(
+                            WidgetBuilder::new()
+                                .with_min_size(Vector2::new(120.0, 22.0))
+                                .with_margin(Thickness::uniform(1.0)),
+                        )
+                        .with_text_commit_mode(TextCommitMode::Immediate)
+                        .build(ctx);
+                        search_text
                     })
                     .with_child({
-                        create_blend_animations = create_menu_item("Blend Animations", vec![], ctx);
-                        create_blend_animations
-                    })
-                    .with_child({
-                        create_blend_by_index = create_menu_item("Blend By Index", vec![], ctx);
-                        create_blend_by_index
+                        items_panel = StackPanelBuilder::new(
+                            WidgetBuilder::new()
+                                .with_child({
+                                    create_play_animation =
+                                        create_menu_item("Play Animation", vec![], ctx);
+                                    create_play_animation
+                                })
+                                .with_child({
+                                    create_blend_animations =
+                                        create_menu_item("Blend Animations", vec![], ctx);
+                                    create_blend_animations
+                                })
+                                .with_child({
+                                    create_blend_by_index =
+                                        create_menu_item("Blend By Index", vec![], ctx);
+                                    create_blend_by_index
+                                })
+                                .with_child({
+                                    create_blend_space =
+                                        create_menu_item("Blend Space", vec![], ctx);
+                                    create_blend_space
+                                }),
+                        )
+                        .build(ctx);
+                        items_panel
                     }),
             )
             .build(ctx),
@@ -75,15 +109,27 @@ impl CanvasContextMenu {
         .build(ctx);
 
         Self {
+            search_text,
+            items_panel,
             create_play_animation,
             create_blend_animations,
             create_blend_by_index,
+            create_blend_space,
             menu,
             canvas: Default::default(),
             node_context_menu: Default::default(),
         }
     }
 
+    fn item_names(&self) -> [(Handle<UiNode>, &'static str); 4] {
+        [
+            (self.create_play_animation, "Play Animation"),
+            (self.create_blend_animations, "Blend Animations"),
+            (self.create_blend_by_index, "Blend By Index"),
+            (self.create_blend_space, "Blend Space"),
+        ]
+    }
+
     pub fn handle_ui_message(
         &mut self,
         sender: &Sender<Message>,
@@ -93,7 +139,29 @@ impl CanvasContextMenu {
         absm_node_handle: Handle<Node>,
         layer_index: usize,
     ) {
-        if let Some(MenuItemMessage::Click) = message.data() {
+        if let Some(TextMessage::Text(text)) = message.data() {
+            if message.destination() == self.search_text
+                && message.direction() == MessageDirection::FromWidget
+            {
+                let filter_text = text.to_lowercase();
+                let item_names = self.item_names();
+                utils::apply_visibility_filter(self.items_panel, ui, |node| {
+                    item_names
+                        .iter()
+                        .find(|(handle, _)| *handle == node.handle())
+                        .map(|(_, name)| name.to_lowercase().contains(&filter_text))
+                });
+            }
+        } else if let Some(PopupMessage::Open) = message.data() {
+            if message.destination() == self.menu {
+                // Reset the filter, so every item will be visible next time the menu is opened.
+                ui.send_message(TextMessage::text(
+                    self.search_text,
+                    MessageDirection::ToWidget,
+                    Default::default(),
+                ));
+            }
+        } else if let Some(MenuItemMessage::Click) = message.data() {
             let position = ui
                 .node(self.canvas)
                 .screen_to_local(ui.node(self.menu).screen_position());
@@ -128,6 +196,17 @@ impl CanvasContextMenu {
                     blend_time: Default::default(),
                     output_pose: Default::default(),
                 }))
+            } else if message.destination() == self.create_blend_space {
+                Some(PoseNode::BlendSpace(BlendSpace {
+                    base: BasePoseNode {
+                        position,
+                        parent_state: current_state,
+                    },
+                    sampling_parameter: "".to_string(),
+                    points: Default::default(),
+                    triangles: Default::default(),
+                    output_pose: Default::default(),
+                }))
             } else {
                 None
             };
@@ -148,6 +227,8 @@ impl CanvasContextMenu {
 pub struct NodeContextMenu {
     remove: Handle<UiNode>,
     set_as_root: Handle<UiNode>,
+    duplicate: Handle<UiNode>,
+    disconnect_all: Handle<UiNode>,
     pub menu: Handle<UiNode>,
     pub canvas: Handle<UiNode>,
     placement_target: Handle<UiNode>,
@@ -157,6 +238,8 @@ impl NodeContextMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let remove;
         let set_as_root;
+        let duplicate;
+        let disconnect_all;
         let menu = PopupBuilder::new(WidgetBuilder::new().with_visibility(false))
             .with_content(
                 StackPanelBuilder::new(
@@ -165,6 +248,14 @@ impl NodeContextMenu {
                             set_as_root = create_menu_item("Set As Root", vec![], ctx);
                             set_as_root
                         })
+                        .with_child({
+                            duplicate = create_menu_item("Duplicate", vec![], ctx);
+                            duplicate
+                        })
+                        .with_child({
+                            disconnect_all = create_menu_item("Disconnect All", vec![], ctx);
+                            disconnect_all
+                        })
                         .with_child({
                             remove = create_menu_item("Remove", vec![], ctx);
                             remove
@@ -177,6 +268,8 @@ impl NodeContextMenu {
         Self {
             remove,
             set_as_root,
+            duplicate,
+            disconnect_all,
             menu,
             canvas: Default::default(),
             placement_target: Default::default(),
@@ -204,16 +297,40 @@ impl NodeContextMenu {
                         editor_scene.selection.clone(),
                     ))];
 
-                    group.extend(selection.entities.iter().filter_map(|entry| {
-                        if let SelectedEntity::PoseNode(pose_node) = entry {
-                            Some(SceneCommand::new(DeletePoseNodeCommand::new(
-                                absm_node_handle,
-                                layer_index,
-                                *pose_node,
-                            )))
-                        } else {
-                            None
-                        }
+                    let pose_nodes = selection
+                        .entities
+                        .iter()
+                        .filter_map(|entry| {
+                            if let SelectedEntity::PoseNode(pose_node) = entry {
+                                Some(*pose_node)
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    // Reset inputs of other nodes first, otherwise they will reference deleted
+                    // nodes.
+                    group.extend(
+                        find_pose_node_references(machine_layer, &pose_nodes)
+                            .into_iter()
+                            .filter_map(|(handle, index)| {
+                                make_reset_pose_source_command(
+                                    machine_layer,
+                                    absm_node_handle,
+                                    layer_index,
+                                    handle,
+                                    index,
+                                )
+                            }),
+                    );
+
+                    group.extend(pose_nodes.into_iter().map(|pose_node| {
+                        SceneCommand::new(DeletePoseNodeCommand::new(
+                            absm_node_handle,
+                            layer_index,
+                            pose_node,
+                        ))
                     }));
 
                     sender
@@ -235,6 +352,66 @@ impl NodeContextMenu {
                         value: root,
                     }))
                     .unwrap();
+            } else if message.destination() == self.duplicate {
+                let original = ui
+                    .node(self.placement_target)
+                    .query_component::<AbsmNode<PoseNode>>()
+                    .unwrap()
+                    .model_handle;
+
+                let mut copy = machine_layer.node(original).clone();
+                copy.position += Vector2::new(20.0, 20.0);
+
+                // Connections are not duplicated, only the sockets are.
+                match copy {
+                    PoseNode::PlayAnimation(_) => {}
+                    PoseNode::BlendAnimations(ref mut blend_animations) => {
+                        for pose_source in blend_animations.pose_sources.iter_mut() {
+                            pose_source.pose_source = Handle::NONE;
+                        }
+                    }
+                    PoseNode::BlendAnimationsByIndex(ref mut blend_animations) => {
+                        for input in blend_animations.inputs.iter_mut() {
+                            input.pose_source = Handle::NONE;
+                        }
+                    }
+                    PoseNode::BlendSpace(ref mut blend_space) => {
+                        for point in blend_space.points.iter_mut() {
+                            point.pose_source = Handle::NONE;
+                        }
+                    }
+                }
+
+                sender
+                    .send(Message::do_scene_command(AddPoseNodeCommand::new(
+                        absm_node_handle,
+                        layer_index,
+                        copy,
+                    )))
+                    .unwrap();
+            } else if message.destination() == self.disconnect_all {
+                let node_ref = ui
+                    .node(self.placement_target)
+                    .query_component::<AbsmNode<PoseNode>>()
+                    .unwrap();
+
+                let group = (0..node_ref.base.input_sockets.len())
+                    .filter_map(|index| {
+                        make_reset_pose_source_command(
+                            machine_layer,
+                            absm_node_handle,
+                            layer_index,
+                            node_ref.model_handle,
+                            index,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                if !group.is_empty() {
+                    sender
+                        .send(Message::do_scene_command(CommandGroup::from(group)))
+                        .unwrap();
+                }
             }
         } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data() {
             if message.destination() == self.menu {
@@ -298,33 +475,14 @@ impl ConnectionContextMenu {
                     .position(|s| *s == connection_ref.segment.dest)
                     .unwrap();
 
-                let model_handle = dest_node_ref.model_handle;
-                match machine_layer.node(model_handle) {
-                    PoseNode::PlayAnimation(_) => {
-                        // No connections
-                    }
-                    PoseNode::BlendAnimations(_) => sender
-                        .send(Message::do_scene_command(
-                            SetBlendAnimationsPoseSourceCommand {
-                                node_handle: absm_node_handle,
-                                layer_index,
-                                handle: model_handle,
-                                index,
-                                value: Default::default(),
-                            },
-                        ))
-                        .unwrap(),
-                    PoseNode::BlendAnimationsByIndex(_) => sender
-                        .send(Message::do_scene_command(
-                            SetBlendAnimationByIndexInputPoseSourceCommand {
-                                node_handle: absm_node_handle,
-                                layer_index,
-                                handle: model_handle,
-                                index,
-                                value: Default::default(),
-                            },
-                        ))
-                        .unwrap(),
+                if let Some(command) = make_reset_pose_source_command(
+                    machine_layer,
+                    absm_node_handle,
+                    layer_index,
+                    dest_node_ref.model_handle,
+                    index,
+                ) {
+                    sender.send(Message::DoSceneCommand(command)).unwrap();
                 }
             }
         } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data() {
@@ -334,3 +492,92 @@ impl ConnectionContextMenu {
         }
     }
 }
+
+// Returns pairs `(node, input_index)` of every input of the nodes (except the given ones) that is
+// connected to any of the given nodes.
+fn find_pose_node_references(
+    machine_layer: &MachineLayer,
+    pose_nodes: &[Handle<PoseNode>],
+) -> Vec<(Handle<PoseNode>, usize)> {
+    let mut references = Vec::new();
+    for (handle, node) in machine_layer.nodes().pair_iter() {
+        if pose_nodes.contains(&handle) {
+            continue;
+        }
+        for (index, child) in node.children().into_iter().enumerate() {
+            if pose_nodes.contains(&child) {
+                references.push((handle, index));
+            }
+        }
+    }
+    references
+}
+
+// Creates a command that resets pose source of an input with the given index to default (removes
+// the connection).
+fn make_reset_pose_source_command(
+    machine_layer: &MachineLayer,
+    absm_node_handle: Handle<Node>,
+    layer_index: usize,
+    handle: Handle<PoseNode>,
+    index: usize,
+) -> Option<SceneCommand> {
+    match machine_layer.node(handle) {
+        PoseNode::PlayAnimation(_) => {
+            // No connections
+            None
+        }
+        PoseNode::BlendAnimations(_) => {
+            Some(SceneCommand::new(SetBlendAnimationsPoseSourceCommand {
+                node_handle: absm_node_handle,
+                layer_index,
+                handle,
+                index,
+                value: Default::default(),
+            }))
+        }
+        PoseNode::BlendAnimationsByIndex(_) => Some(SceneCommand::new(
+            SetBlendAnimationByIndexInputPoseSourceCommand {
+                node_handle: absm_node_handle,
+                layer_index,
+                handle,
+                index,
+                value: Default::default(),
+            },
+        )),
+        PoseNode::BlendSpace(_) => Some(SceneCommand::new(SetBlendSpacePointPoseSourceCommand {
+            node_handle: absm_node_handle,
+            layer_index,
+            handle,
+            index,
+            value: Default::default(),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::absm::state_viewer::context::find_pose_node_references;
+    use fyrox::{
+        animation::machine::{BlendAnimations, BlendPose, MachineLayer, PlayAnimation, PoseNode},
+        core::pool::Handle,
+    };
+
+    #[test]
+    fn test_find_pose_node_references() {
+        let mut layer = MachineLayer::new();
+        let a = layer.add_node(PoseNode::PlayAnimation(PlayAnimation::new(Handle::NONE)));
+        let c = layer.add_node(PoseNode::PlayAnimation(PlayAnimation::new(Handle::NONE)));
+        // B blends C and A.
+        let b = layer.add_node(PoseNode::BlendAnimations(BlendAnimations::new(vec![
+            BlendPose::with_constant_weight(0.5, c),
+            BlendPose::with_constant_weight(0.5, a),
+        ])));
+
+        // Deleting A must reset the second input of B only.
+        assert_eq!(find_pose_node_references(&layer, &[a]), vec![(b, 1)]);
+
+        // Inputs of deleted nodes are not reset, they're deleted anyway.
+        assert_eq!(find_pose_node_references(&layer, &[a, b]), vec![]);
+    }
+}
@@ -3,7 +3,8 @@ use crate::{
         canvas::{AbsmCanvasBuilder, AbsmCanvasMessage},
         command::{
             blend::{
-                SetBlendAnimationByIndexInputPoseSourceCommand, SetBlendAnimationsPoseSourceCommand,
+                MovePoseSourceCommand, SetBlendAnimationByIndexInputPoseSourceCommand,
+                SetBlendAnimationsPoseSourceCommand, SetBlendSpacePointPoseSourceCommand,
             },
             MovePoseNodeCommand,
         },
@@ -13,7 +14,8 @@ use crate::{
         selection::{AbsmSelection, SelectedEntity},
         socket::{Socket, SocketBuilder, SocketDirection},
         state_viewer::context::{CanvasContextMenu, ConnectionContextMenu, NodeContextMenu},
-        NORMAL_BACKGROUND, NORMAL_ROOT_COLOR, SELECTED_BACKGROUND, SELECTED_ROOT_COLOR,
+        NORMAL_BACKGROUND, NORMAL_ROOT_COLOR, NORMAL_WARNING_COLOR, SELECTED_BACKGROUND,
+        SELECTED_ROOT_COLOR, SELECTED_WARNING_COLOR,
     },
     scene::{
         commands::{ChangeSelectionCommand, CommandGroup, SceneCommand},
@@ -23,7 +25,7 @@ use crate::{
 };
 use fyrox::{
     animation::{
-        machine::{MachineLayer, PoseNode, State},
+        machine::{MachineLayer, ParameterContainer, PoseNode, State},
         Animation,
     },
     core::pool::Handle,
@@ -39,6 +41,7 @@ use fyrox::{
         graph::Graph,
         node::Node,
     },
+    utils::log::Log,
 };
 use std::{cmp::Ordering, sync::mpsc::Sender};
 
@@ -52,6 +55,7 @@ pub struct StateViewer {
     node_context_menu: NodeContextMenu,
     connection_context_menu: ConnectionContextMenu,
     last_selection: AbsmSelection,
+    invalid_pose_nodes: Vec<Handle<PoseNode>>,
 }
 
 fn create_socket(
@@ -126,7 +130,27 @@ fn make_pose_node_name(
             "Blend {} Animations By Index",
             blend_animations_by_index.inputs.len()
         ),
+        PoseNode::BlendSpace(blend_space) => {
+            format!("Blend Space {} Points", blend_space.points.len())
+        }
+    }
+}
+
+// Checks whether the pose node is able to produce a pose, returns a description of the problem
+// if it is not.
+fn validate_pose_node(model_ref: &PoseNode, parameters: &ParameterContainer) -> Result<(), String> {
+    if let PoseNode::BlendAnimationsByIndex(blend_animations_by_index) = model_ref {
+        let index_parameter = &blend_animations_by_index.index_parameter;
+        if index_parameter.is_empty() {
+            return Err("index parameter is not set".to_string());
+        } else if parameters.get(index_parameter).is_none() {
+            return Err(format!(
+                "there is no parameter named {} in the machine",
+                index_parameter
+            ));
+        }
     }
+    Ok(())
 }
 
 impl StateViewer {
@@ -165,6 +189,7 @@ impl StateViewer {
             node_context_menu,
             connection_context_menu,
             last_selection: AbsmSelection::default(),
+            invalid_pose_nodes: Default::default(),
         }
     }
 
@@ -210,6 +235,7 @@ impl StateViewer {
 
     pub fn clear(&mut self, ui: &UserInterface) {
         self.state = Handle::NONE;
+        self.invalid_pose_nodes.clear();
 
         for &child in ui.node(self.canvas).children() {
             ui.send_message(WidgetMessage::remove(child, MessageDirection::ToWidget));
@@ -338,6 +364,48 @@ impl StateViewer {
                                         ))
                                         .unwrap();
                                 }
+                                PoseNode::BlendSpace(_) => {
+                                    sender
+                                        .send(Message::do_scene_command(
+                                            SetBlendSpacePointPoseSourceCommand {
+                                                node_handle: absm_node_handle,
+                                                layer_index,
+                                                handle: dest_node,
+                                                index: dest_socket_ref.index,
+                                                value: source_node,
+                                            },
+                                        ))
+                                        .unwrap();
+                                }
+                            }
+                        }
+                        AbsmCanvasMessage::CommitSocketReorder {
+                            source_socket,
+                            dest_socket,
+                        } => {
+                            let node = fetch_socket_pose_node_model_handle(*dest_socket, ui);
+                            let from = ui
+                                .node(*source_socket)
+                                .query_component::<Socket>()
+                                .unwrap()
+                                .index;
+                            let to = ui
+                                .node(*dest_socket)
+                                .query_component::<Socket>()
+                                .unwrap()
+                                .index;
+
+                            // Only blend animations node has ordered inputs for now.
+                            if let PoseNode::BlendAnimations(_) = layer.nodes()[node] {
+                                sender
+                                    .send(Message::do_scene_command(MovePoseSourceCommand {
+                                        node_handle: absm_node_handle,
+                                        layer_index,
+                                        handle: node,
+                                        from,
+                                        to,
+                                    }))
+                                    .unwrap();
                             }
                         }
                         _ => (),
@@ -462,6 +530,9 @@ impl StateViewer {
                                     "Blend Animations By Index",
                                     true,
                                 ),
+                                PoseNode::BlendSpace(blend_space) => {
+                                    (blend_space.points.len(), "Blend Space", true)
+                                }
                             };
 
                             let node_view = AbsmNodeBuilder::new(
@@ -585,13 +656,36 @@ impl StateViewer {
                     );
                 }
 
+                let is_valid = match validate_pose_node(model_ref, absm_node.machine().parameters())
+                {
+                    Ok(()) => {
+                        self.invalid_pose_nodes.retain(|h| *h != model_handle);
+                        true
+                    }
+                    Err(reason) => {
+                        // Warn only once, when a node becomes invalid.
+                        if !self.invalid_pose_nodes.contains(&model_handle) {
+                            Log::warn(format!(
+                                "{} ({}) will not produce any pose: {}!",
+                                make_pose_node_name(model_ref, graph, absm_node),
+                                model_handle,
+                                reason
+                            ));
+                            self.invalid_pose_nodes.push(model_handle);
+                        }
+                        false
+                    }
+                };
+
                 if model_ref.parent_state == self.state {
                     send_sync_message(
                         ui,
                         AbsmNodeMessage::normal_color(
                             view,
                             MessageDirection::ToWidget,
-                            if model_handle == parent_state_ref.root {
+                            if !is_valid {
+                                NORMAL_WARNING_COLOR
+                            } else if model_handle == parent_state_ref.root {
                                 NORMAL_ROOT_COLOR
                             } else {
                                 NORMAL_BACKGROUND
@@ -603,7 +697,9 @@ impl StateViewer {
                         AbsmNodeMessage::selected_color(
                             view,
                             MessageDirection::ToWidget,
-                            if model_handle == parent_state_ref.root {
+                            if !is_valid {
+                                SELECTED_WARNING_COLOR
+                            } else if model_handle == parent_state_ref.root {
                                 SELECTED_ROOT_COLOR
                             } else {
                                 SELECTED_BACKGROUND
@@ -718,3 +814,26 @@ impl StateViewer {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::absm::state_viewer::validate_pose_node;
+    use fyrox::animation::machine::{
+        BlendAnimationsByIndex, Parameter, ParameterContainer, PoseNode,
+    };
+
+    #[test]
+    fn test_validate_blend_by_index_parameter() {
+        let mut parameters = ParameterContainer::default();
+        parameters.add("Weapon", Parameter::Index(0));
+
+        let node = |name: &str| {
+            PoseNode::BlendAnimationsByIndex(BlendAnimationsByIndex::new(name.to_string(), vec![]))
+        };
+
+        assert!(validate_pose_node(&node(""), &parameters).is_err());
+        assert!(validate_pose_node(&node("Unknown"), &parameters).is_err());
+        assert!(validate_pose_node(&node("Weapon"), &parameters).is_ok());
+        assert!(validate_pose_node(&PoseNode::default(), &parameters).is_ok());
+    }
+}
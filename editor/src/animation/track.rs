@@ -46,7 +46,9 @@ use fyrox::{
         stack_panel::StackPanelBuilder,
         text::{Text, TextBuilder, TextMessage},
         text_box::{TextBoxBuilder, TextCommitMode},
-        tree::{Tree, TreeBuilder, TreeMessage, TreeRootBuilder, TreeRootMessage},
+        tree::{
+            Tree, TreeBuilder, TreeExpansionStrategy, TreeMessage, TreeRootBuilder, TreeRootMessage,
+        },
         utils::{make_cross, make_simple_tooltip},
         widget::{Widget, WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowMessage, WindowTitle},
@@ -507,7 +509,7 @@ impl TrackList {
                 && message.direction() == MessageDirection::FromWidget
             {
                 let filter_text = text.to_lowercase();
-                utils::apply_visibility_filter(self.tree_root, ui, |node| {
+                let filter = |node: &UiNode| {
                     if let Some(tree) = node.query_component::<Tree>() {
                         if let Some(tree_text) = ui.node(tree.content).query_component::<Text>() {
                             return Some(tree_text.text().to_lowercase().contains(&filter_text));
@@ -515,7 +517,21 @@ impl TrackList {
                     }
 
                     None
-                });
+                };
+                utils::apply_visibility_filter(self.tree_root, ui, &filter);
+
+                if !filter_text.is_empty() {
+                    // Expand every item on the way to the matches, otherwise they could be hidden
+                    // inside collapsed parent items.
+                    for tree in utils::collect_visibility_matches(self.tree_root, ui, &filter) {
+                        ui.send_message(TreeMessage::expand(
+                            tree,
+                            MessageDirection::ToWidget,
+                            true,
+                            TreeExpansionStrategy::Direct,
+                        ));
+                    }
+                }
             }
         } else if let Some(WindowMessage::Close) = message.data() {
             if message.destination() == self.node_selector
@@ -0,0 +1,101 @@
+//! Allows to hear sound effects without running the game, it is used to tune parameters of the
+//! effects.
+
+use fyrox::scene::{
+    sound::{effect::Effect, DataSource, SoundBufferResource, SoundError, SAMPLE_RATE},
+    Scene,
+};
+
+/// Duration of a preview (in seconds), it should be long enough to hear reverberation tails.
+const PREVIEW_DURATION: f32 = 3.0;
+
+/// Creates a buffer with a short percussive tone followed by silence, it makes echoes and
+/// reverberation easy to hear when played in a loop.
+pub fn make_test_buffer() -> SoundBufferResource {
+    let samples = (0..SAMPLE_RATE)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            (t * 440.0 * std::f32::consts::TAU).sin() * (-t * 30.0).exp()
+        })
+        .collect();
+
+    SoundBufferResource::new_generic(DataSource::Raw {
+        sample_rate: SAMPLE_RATE as usize,
+        channel_count: 1,
+        samples,
+    })
+    .unwrap()
+}
+
+/// Plays the test buffer in a loop through a copy of the effect in an isolated preview context and
+/// returns rendered stereo samples. Neither the scene, nor the effect are modified.
+pub fn preview_effect(
+    effect: &Effect,
+    test_buffer: SoundBufferResource,
+) -> Result<Vec<(f32, f32)>, SoundError> {
+    effect.render_preview(
+        test_buffer,
+        (PREVIEW_DURATION * SAMPLE_RATE as f32) as usize,
+    )
+}
+
+/// Renders the preview of the effect and plays it in the given scene as a non-spatial sound.
+pub fn play_effect_preview(effect: &Effect, scene: &mut Scene) -> Result<(), SoundError> {
+    let samples = preview_effect(effect, make_test_buffer())?;
+
+    let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+        sample_rate: SAMPLE_RATE as usize,
+        channel_count: 2,
+        samples: samples
+            .into_iter()
+            .flat_map(|(left, right)| [left, right])
+            .collect(),
+    })
+    .map_err(|_| SoundError::BufferFailedToLoad)?;
+
+    scene.graph.sound_context.play_ui(buffer, 1.0)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::audio::effect_preview::{make_test_buffer, preview_effect};
+    use fyrox::scene::sound::{
+        effect::{BaseEffectBuilder, ReverbEffectBuilder},
+        SAMPLE_RATE,
+    };
+
+    #[test]
+    fn test_preview_effect() {
+        let energy = |samples: &[(f32, f32)]| {
+            samples
+                .iter()
+                .map(|(left, right)| left * left + right * right)
+                .sum::<f32>()
+        };
+
+        let dry = ReverbEffectBuilder::new(BaseEffectBuilder::new())
+            .with_wet(0.0)
+            .with_dry(0.0)
+            .build_effect();
+        let reverb = ReverbEffectBuilder::new(BaseEffectBuilder::new())
+            .with_decay_time(2.0)
+            .build_effect();
+
+        let dry_samples = preview_effect(&dry, make_test_buffer()).unwrap();
+        let reverb_samples = preview_effect(&reverb, make_test_buffer()).unwrap();
+
+        assert_eq!(dry_samples.len(), 3 * SAMPLE_RATE as usize);
+        assert_eq!(dry_samples.len(), reverb_samples.len());
+
+        // The tone is audible in both cases.
+        let tone = ..SAMPLE_RATE as usize / 10;
+        assert!(energy(&dry_samples[tone.clone()]) > 0.0);
+        assert!(energy(&reverb_samples[tone]) > 0.0);
+
+        // The tone decays quickly, only reverberation makes the end of the loop audible.
+        let tail = SAMPLE_RATE as usize / 2..SAMPLE_RATE as usize;
+        assert!(energy(&reverb_samples[tail.clone()]) > 10.0 * energy(&dry_samples[tail]));
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    scene::commands::effect::AddEffectCommand, utils::window_content, ChangeSelectionCommand,
-    EditorScene, GridBuilder, Message, MessageDirection, Mode, SceneCommand, Selection,
-    UserInterface,
+    gui::make_dropdown_list_option,
+    scene::commands::effect::{AddEffectCommand, SetEffectVariantCommand},
+    send_sync_message,
+    utils::window_content,
+    ChangeSelectionCommand, EditorScene, GridBuilder, Message, MessageDirection, Mode,
+    SceneCommand, Selection, UserInterface,
 };
 use fyrox::{
     core::pool::Handle,
@@ -10,6 +13,7 @@ use fyrox::{
         border::BorderBuilder,
         button::{ButtonBuilder, ButtonMessage},
         decorator::DecoratorBuilder,
+        dropdown_list::{DropdownListBuilder, DropdownListMessage},
         grid::{Column, Row},
         list_view::{ListView, ListViewBuilder, ListViewMessage},
         message::UiMessage,
@@ -20,9 +24,12 @@ use fyrox::{
         Orientation, Thickness, UiNode,
     },
     scene::sound::effect::{BaseEffectBuilder, Effect, ReverbEffectBuilder},
+    utils::log::Log,
 };
 use std::{cmp::Ordering, rc::Rc, sync::mpsc::Sender};
+use strum::VariantNames;
 
+pub mod effect_preview;
 pub mod preview;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -44,6 +51,8 @@ pub struct AudioPanel {
     pub window: Handle<UiNode>,
     edit_context: Handle<UiNode>,
     add_effect: Handle<UiNode>,
+    preview_effect: Handle<UiNode>,
+    effect_variant: Handle<UiNode>,
     effects: Handle<UiNode>,
 }
 
@@ -59,6 +68,8 @@ impl AudioPanel {
 
         let edit_context;
         let add_effect;
+        let preview_effect;
+        let effect_variant;
         let effects;
         let window = WindowBuilder::new(WidgetBuilder::new())
             .with_content(
@@ -91,6 +102,30 @@ impl AudioPanel {
                                         .with_text("Edit Context")
                                         .build(ctx);
                                         edit_context
+                                    })
+                                    .with_child({
+                                        preview_effect = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Preview Effect")
+                                        .build(ctx);
+                                        preview_effect
+                                    })
+                                    .with_child({
+                                        effect_variant = DropdownListBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_margin(Thickness::uniform(1.0))
+                                                .with_width(100.0),
+                                        )
+                                        .with_items(
+                                            Effect::VARIANTS
+                                                .iter()
+                                                .map(|v| make_dropdown_list_option(ctx, v))
+                                                .collect(),
+                                        )
+                                        .build(ctx);
+                                        effect_variant
                                     }),
                             )
                             .with_orientation(Orientation::Horizontal)
@@ -109,6 +144,8 @@ impl AudioPanel {
             window,
             effects,
             add_effect,
+            preview_effect,
+            effect_variant,
             edit_context,
         }
     }
@@ -118,7 +155,7 @@ impl AudioPanel {
         message: &UiMessage,
         editor_scene: &EditorScene,
         sender: &Sender<Message>,
-        engine: &Engine,
+        engine: &mut Engine,
     ) {
         if let Some(ButtonMessage::Click) = message.data() {
             if message.destination() == self.edit_context {
@@ -141,6 +178,42 @@ impl AudioPanel {
                         ),
                     )))
                     .unwrap()
+            } else if message.destination() == self.preview_effect {
+                if let Selection::Effect(ref selection) = editor_scene.selection {
+                    let scene = &mut engine.scenes[editor_scene.scene];
+                    if let Some(effect) = selection
+                        .effects
+                        .first()
+                        .and_then(|e| scene.graph.sound_context.try_get_effect(*e))
+                        .cloned()
+                    {
+                        if let Err(e) = effect_preview::play_effect_preview(&effect, scene) {
+                            Log::err(format!("Unable to preview the effect. Reason: {:?}", e));
+                        }
+                    }
+                }
+            }
+        } else if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
+            if message.destination() == self.effect_variant
+                && message.direction() == MessageDirection::FromWidget
+            {
+                if let Selection::Effect(ref selection) = editor_scene.selection {
+                    let variant = Effect::VARIANTS[*index];
+                    let sound_context = &engine.scenes[editor_scene.scene].graph.sound_context;
+                    for &handle in selection.effects.iter() {
+                        if let Some(new_effect) = sound_context
+                            .try_get_effect(handle)
+                            .filter(|effect| effect.as_ref() != variant)
+                            .and_then(|effect| effect.with_variant(variant))
+                        {
+                            sender
+                                .send(Message::DoSceneCommand(SceneCommand::new(
+                                    SetEffectVariantCommand::new(handle, new_effect),
+                                )))
+                                .unwrap();
+                        }
+                    }
+                }
             }
         } else if let Some(ListViewMessage::SelectionChanged(Some(effect_index))) = message.data() {
             if message.destination() == self.effects
@@ -218,6 +291,32 @@ impl AudioPanel {
             }
             _ => (),
         }
+
+        let variant = if let Selection::Effect(ref selection) = editor_scene.selection {
+            selection
+                .effects
+                .first()
+                .and_then(|e| context.try_get_effect(*e))
+                .and_then(|effect| Effect::VARIANTS.iter().position(|v| *v == effect.as_ref()))
+        } else {
+            None
+        };
+        send_sync_message(
+            ui,
+            DropdownListMessage::selection(
+                self.effect_variant,
+                MessageDirection::ToWidget,
+                variant,
+            ),
+        );
+        send_sync_message(
+            ui,
+            WidgetMessage::enabled(
+                self.effect_variant,
+                MessageDirection::ToWidget,
+                variant.is_some(),
+            ),
+        );
     }
 
     pub fn on_mode_changed(&mut self, ui: &UserInterface, mode: &Mode) {
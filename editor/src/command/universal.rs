@@ -15,6 +15,9 @@ macro_rules! define_universal_commands {
                 fyrox::gui::inspector::PropertyAction::RemoveItem { index } => Some(<$command_wrapper>::new(
                     RemoveCollectionItemCommand::new($handle_ident, property_changed.path(), index, $($field_name),*)
                 )),
+                // Must be handled outside, new items are created using default value registry which is not
+                // available here.
+                fyrox::gui::inspector::PropertyAction::ResizeCollection { .. } => None,
                 // Must be handled outside, there is not enough context and it near to impossible to create universal reversion
                 // for InheritableVariable<T>.
                 fyrox::gui::inspector::PropertyAction::Revert => None
@@ -36,12 +39,41 @@ macro_rules! define_universal_commands {
             })
         }
 
+        // Returns display name of a property at the given path, it is taken from the info of the
+        // field, so it is a human-readable name in Title Case.
+        fn field_display_name(entity: &dyn fyrox::core::reflect::Reflect, path: &str) -> Option<String> {
+            let (parent_path, field_name) = match path.rfind('.') {
+                Some(separator_position) => (&path[..separator_position], &path[(separator_position + 1)..]),
+                None => ("", path),
+            };
+            let mut display_name = None;
+            let mut find = |parent: &dyn fyrox::core::reflect::Reflect| {
+                parent.fields_info(&mut |fields| {
+                    display_name = fields
+                        .iter()
+                        .find(|field| field.name == field_name)
+                        .map(|field| field.display_name.to_owned())
+                })
+            };
+            if parent_path.is_empty() {
+                find(entity)
+            } else {
+                fyrox::core::reflect::ResolvePath::resolve_path(entity, parent_path, &mut |result| {
+                    if let Ok(parent) = result {
+                        find(parent)
+                    }
+                })
+            }
+            display_name
+        }
+
         #[derive(Debug)]
         pub struct SetPropertyCommand {
             #[allow(dead_code)]
             $handle_ident: $handle,
             value: Option<Box<dyn fyrox::core::reflect::Reflect>>,
             path: String,
+            display_name: Option<String>,
             $($field_name: $field_type),*
         }
 
@@ -51,12 +83,16 @@ macro_rules! define_universal_commands {
                     $handle_ident,
                     value: Some(value),
                     path,
+                    display_name: None,
                     $($field_name),*
                 }
             }
 
             fn swap(&mut $self, $ctx_ident: &mut $ctx) {
                 let entity = {$entity_getter} as &mut dyn Reflect;
+                if $self.display_name.is_none() {
+                    $self.display_name = field_display_name(entity, &$self.path);
+                }
 
                 entity.set_field_by_path(&$self.path, $self.value.take().unwrap(), &mut |result| match result {
                     Ok(old_value) => {
@@ -90,7 +126,10 @@ macro_rules! define_universal_commands {
 
         impl $command for SetPropertyCommand {
             fn name(&mut $self, _: &$ctx) -> String {
-                format!("Set {} property", $self.path)
+                match $self.display_name {
+                    Some(ref display_name) => format!("Set {}", display_name),
+                    None => format!("Set {} property", $self.path),
+                }
             }
 
             fn execute(&mut $self, $ctx_ident: &mut $ctx) {
@@ -209,7 +248,6 @@ macro_rules! define_universal_commands {
                                 list.reflect_insert($self.index, $self.value.take().unwrap())
                             {
                                 $self.value = Some(item);
-                            } else {
                                 fyrox::utils::log::Log::err(format!(
                                     "Failed to insert item to {} collection. Type mismatch!",
                                     $self.path
@@ -224,3 +262,108 @@ macro_rules! define_universal_commands {
         }
     };
 }
+
+#[cfg(test)]
+mod test {
+    use crate::define_universal_commands;
+    use fyrox::{
+        core::reflect::prelude::*,
+        gui::inspector::{CollectionChanged, FieldKind, ObjectValue, PropertyChanged},
+    };
+    use std::{any::TypeId, fmt::Debug};
+
+    #[derive(Reflect, Debug)]
+    struct Entity {
+        value: f32,
+        items: Vec<u32>,
+    }
+
+    struct TestContext {
+        entity: Entity,
+    }
+
+    trait TestCommand: Debug + 'static {
+        fn name(&mut self, context: &TestContext) -> String;
+        fn execute(&mut self, context: &mut TestContext);
+        fn revert(&mut self, context: &mut TestContext);
+    }
+
+    struct TestCommandWrapper(Box<dyn TestCommand>);
+
+    impl TestCommandWrapper {
+        fn new<C: TestCommand>(cmd: C) -> Self {
+            Self(Box::new(cmd))
+        }
+    }
+
+    define_universal_commands!(
+        make_test_command,
+        TestCommand,
+        TestCommandWrapper,
+        TestContext,
+        (),
+        ctx,
+        handle,
+        self,
+        { &mut ctx.entity as &mut dyn Reflect },
+    );
+
+    fn make_command(name: &str, value: FieldKind) -> Box<dyn TestCommand> {
+        let property_changed = PropertyChanged {
+            name: name.to_string(),
+            owner_type_id: TypeId::of::<Entity>(),
+            value,
+        };
+        make_test_command((), &property_changed).unwrap().0
+    }
+
+    #[test]
+    fn test_universal_commands() {
+        let mut ctx = TestContext {
+            entity: Entity {
+                value: 1.0,
+                items: vec![1, 2, 3],
+            },
+        };
+
+        // Set
+        let mut command = make_command(
+            "value",
+            FieldKind::Object(ObjectValue {
+                value: Box::new(5.0f32),
+            }),
+        );
+        assert_eq!(command.name(&ctx), "Set value property");
+        command.execute(&mut ctx);
+        assert_eq!(command.name(&ctx), "Set Value");
+        assert_eq!(ctx.entity.value, 5.0);
+        command.revert(&mut ctx);
+        assert_eq!(ctx.entity.value, 1.0);
+        command.execute(&mut ctx);
+        assert_eq!(ctx.entity.value, 5.0);
+
+        // Add
+        let mut command = make_command(
+            "items",
+            FieldKind::Collection(Box::new(CollectionChanged::Add(ObjectValue {
+                value: Box::new(4u32),
+            }))),
+        );
+        command.execute(&mut ctx);
+        assert_eq!(ctx.entity.items, [1, 2, 3, 4]);
+        command.revert(&mut ctx);
+        assert_eq!(ctx.entity.items, [1, 2, 3]);
+
+        // Remove
+        let mut command = make_command(
+            "items",
+            FieldKind::Collection(Box::new(CollectionChanged::Remove(1))),
+        );
+        command.execute(&mut ctx);
+        assert_eq!(ctx.entity.items, [1, 3]);
+        command.revert(&mut ctx);
+        assert_eq!(ctx.entity.items, [1, 2, 3]);
+        command.execute(&mut ctx);
+        assert_eq!(ctx.entity.items, [1, 3]);
+    }
+}
@@ -19,13 +19,14 @@ use fyrox::{
         machine::{
             node::BasePoseNode,
             transition::{AndNode, LogicNode, NotNode, OrNode, XorNode},
-            BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput, Machine,
-            PlayAnimation, PoseNode, PoseWeight, State,
+            BlendAnimations, BlendAnimationsByIndex, BlendPose, BlendSpace, BlendSpacePoint,
+            IndexedBlendInput, Machine, PlayAnimation, PoseNode, PoseWeight, State,
         },
         AnimationContainer,
     },
     core::{
         futures::executor::block_on,
+        math::aabb::AxisAlignedBoundingBox,
         parking_lot::Mutex,
         pool::{ErasedHandle, Handle},
     },
@@ -81,15 +82,15 @@ use fyrox::{
         rigidbody::RigidBodyType,
         sound::{
             self,
-            effect::{BaseEffect, Effect, ReverbEffect},
-            Biquad, DistanceModel, SoundBufferResource, SoundBufferResourceLoadError,
+            effect::{BaseEffect, DelayEffect, Effect, ReverbEffect},
+            Biquad, DistanceModel, FadeCurve, SoundBufferResource, SoundBufferResourceLoadError,
             SoundBufferState, Status,
         },
         terrain::Layer,
         transform::Transform,
     },
 };
-use std::{rc::Rc, sync::mpsc::Sender};
+use std::{ops::Range, rc::Rc, sync::mpsc::Sender};
 
 pub mod animation;
 pub mod handle;
@@ -146,6 +147,7 @@ pub fn make_property_editors_container(
     container.register_inheritable_vec_collection::<Property>();
     container.register_inheritable_vec_collection::<LodControlledObject>();
     container.register_inheritable_vec_collection::<GeometrySource>();
+    container.register_inheritable_vec_collection::<String>();
 
     container.insert(make_status_enum_editor_definition());
 
@@ -227,6 +229,7 @@ pub fn make_property_editors_container(
     container.register_inheritable_enum::<Emitter, _>();
 
     container.register_inheritable_inspectable::<ReverbEffect>();
+    container.register_inheritable_inspectable::<DelayEffect>();
     container.register_inheritable_inspectable::<Biquad>();
     container.register_inheritable_inspectable::<BaseEmitter>();
     container.register_inheritable_inspectable::<SphereEmitter>();
@@ -240,8 +243,12 @@ pub fn make_property_editors_container(
     container.register_inheritable_option::<ColorGradingLut>();
     container.register_inheritable_option::<Biquad>();
     container.register_inheritable_option::<SkyBox>();
+    container.register_inheritable_option::<AxisAlignedBoundingBox>();
+    container.register_inheritable_option::<DistanceModel>();
+    container.register_inheritable_option::<Range<usize>>();
 
     container.register_inheritable_inspectable::<SkyBox>();
+    container.register_inheritable_inspectable::<AxisAlignedBoundingBox>();
 
     container.register_inheritable_enum::<dim2::collider::ColliderShape, _>();
     container.register_inheritable_enum::<CoefficientCombineRule, _>();
@@ -258,6 +265,7 @@ pub fn make_property_editors_container(
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
+    container.register_inheritable_enum::<FadeCurve, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
 
@@ -296,6 +304,9 @@ pub fn make_property_editors_container(
     container.insert(InspectablePropertyEditorDefinition::<BlendAnimationsByIndex>::new());
     container.insert(InspectablePropertyEditorDefinition::<BlendAnimations>::new());
     container.insert(InspectablePropertyEditorDefinition::<PlayAnimation>::new());
+    container.insert(InspectablePropertyEditorDefinition::<BlendSpacePoint>::new());
+    container.insert(VecCollectionPropertyEditorDefinition::<BlendSpacePoint>::new());
+    container.insert(InspectablePropertyEditorDefinition::<BlendSpace>::new());
 
     container.insert(InspectablePropertyEditorDefinition::<Handle<PoseNode>>::new());
     container.insert(InspectablePropertyEditorDefinition::<Handle<State>>::new());
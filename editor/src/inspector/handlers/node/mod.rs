@@ -3,12 +3,13 @@ use crate::{
     scene::commands::{
         make_set_node_property_command,
         terrain::{AddTerrainLayerCommand, DeleteTerrainLayerCommand},
+        ResizeCollectionCommand,
     },
     SceneCommand,
 };
 use fyrox::{
     core::pool::Handle,
-    gui::inspector::{CollectionChanged, FieldKind, PropertyChanged},
+    gui::inspector::{CollectionChanged, FieldKind, PropertyAction, PropertyChanged},
     scene::{node::Node, terrain::Terrain},
 };
 use std::any::TypeId;
@@ -22,8 +23,18 @@ impl SceneNodePropertyChangedHandler {
         handle: Handle<Node>,
         node: &mut Node,
     ) -> Option<SceneCommand> {
-        // Terrain is special and have its own commands for specific properties.
-        if args.path() == Terrain::LAYERS && args.owner_type_id == TypeId::of::<Terrain>() {
+        if let PropertyAction::ResizeCollection { new_len } =
+            PropertyAction::from_field_kind(&args.value)
+        {
+            // Resize needs default values of collection items, so it can't be done by universal
+            // commands.
+            Some(SceneCommand::new(ResizeCollectionCommand::new(
+                handle,
+                args.path(),
+                new_len,
+            )))
+        } else if args.path() == Terrain::LAYERS && args.owner_type_id == TypeId::of::<Terrain>() {
+            // Terrain is special and have its own commands for specific properties.
             match args.value {
                 FieldKind::Collection(ref collection_changed) => match **collection_changed {
                     CollectionChanged::Add(_) => Some(SceneCommand::new(
@@ -32,7 +43,7 @@ impl SceneNodePropertyChangedHandler {
                     CollectionChanged::Remove(index) => Some(SceneCommand::new(
                         DeleteTerrainLayerCommand::new(handle, index),
                     )),
-                    CollectionChanged::ItemChanged { .. } => None,
+                    CollectionChanged::ItemChanged { .. } | CollectionChanged::Resize(_) => None,
                 },
                 _ => None,
             }
@@ -58,3 +69,69 @@ impl SceneNodePropertyChangedHandler {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        inspector::handlers::node::SceneNodePropertyChangedHandler,
+        scene::commands::test::{add_point_light_radius_change, point_light_radius, TestScene},
+    };
+    use fyrox::{
+        gui::inspector::{CollectionChanged, FieldKind, PropertyChanged},
+        scene::{
+            base::BaseBuilder,
+            sound::{Sound, SoundBuilder},
+            Scene,
+        },
+    };
+    use std::any::TypeId;
+
+    #[test]
+    fn test_scalar_property_changed() {
+        let mut test_scene = TestScene::new(Scene::new());
+        let (light, property_changed) = add_point_light_radius_change(&mut test_scene.scene);
+
+        let mut command = SceneNodePropertyChangedHandler.handle(
+            &property_changed,
+            light,
+            &mut test_scene.scene.graph[light],
+        );
+
+        command.execute(&mut test_scene.context());
+        assert_eq!(point_light_radius(&test_scene.scene, light), 5.0);
+        command.revert(&mut test_scene.context());
+        assert_eq!(point_light_radius(&test_scene.scene, light), 2.0);
+    }
+
+    #[test]
+    fn test_collection_resized() {
+        let mut test_scene = TestScene::new(Scene::new());
+        let sound = SoundBuilder::new(BaseBuilder::new())
+            .with_effect_names(vec!["Reverb".to_string(), "Echo".to_string()])
+            .build(&mut test_scene.scene.graph);
+        let effect_names = |test_scene: &TestScene| {
+            test_scene.scene.graph[sound]
+                .cast::<Sound>()
+                .unwrap()
+                .effect_names()
+                .to_vec()
+        };
+
+        let property_changed = PropertyChanged {
+            name: "effect_names".to_string(),
+            owner_type_id: TypeId::of::<Sound>(),
+            value: FieldKind::Collection(Box::new(CollectionChanged::Resize(3))),
+        };
+
+        let mut command = SceneNodePropertyChangedHandler.handle(
+            &property_changed,
+            sound,
+            &mut test_scene.scene.graph[sound],
+        );
+
+        command.execute(&mut test_scene.context());
+        assert_eq!(effect_names(&test_scene), ["Reverb", "Echo", ""]);
+        command.revert(&mut test_scene.context());
+        assert_eq!(effect_names(&test_scene), ["Reverb", "Echo"]);
+    }
+}
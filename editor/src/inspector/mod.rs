@@ -14,10 +14,14 @@ use crate::{
             sound_context::handle_sound_context_property_changed,
         },
     },
-    scene::{commands::effect::make_set_effect_property_command, EditorScene, Selection},
+    scene::{
+        commands::{effect::make_set_effect_property_command, ApplyToSelectionCommand},
+        EditorScene, Selection,
+    },
     send_sync_message,
     utils::window_content,
-    Brush, CommandGroup, GameEngine, Message, Mode, WidgetMessage, WrapMode, MSG_SYNC_FLAG,
+    Brush, CommandGroup, GameEngine, Message, Mode, SceneCommand, WidgetMessage, WrapMode,
+    MSG_SYNC_FLAG,
 };
 use fyrox::{
     animation::Animation,
@@ -549,21 +553,34 @@ impl Inspector {
                 message.data::<InspectorMessage>()
             {
                 let group = match &editor_scene.selection {
-                    Selection::Graph(selection) => selection
-                        .nodes
-                        .iter()
-                        .filter_map(|&node_handle| {
-                            if scene.graph.is_valid_handle(node_handle) {
-                                Some(self.node_property_changed_handler.handle(
-                                    args,
-                                    node_handle,
-                                    &mut scene.graph[node_handle],
-                                ))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>(),
+                    Selection::Graph(selection) => {
+                        // A value is set only on the nodes that have the property, other changes
+                        // are handled per node.
+                        let apply_to_selection = if selection.nodes.len() > 1 {
+                            ApplyToSelectionCommand::new(&scene.graph, &selection.nodes, args)
+                        } else {
+                            None
+                        };
+
+                        match apply_to_selection {
+                            Some(command) => vec![SceneCommand::new(command)],
+                            None => selection
+                                .nodes
+                                .iter()
+                                .filter_map(|&node_handle| {
+                                    if scene.graph.is_valid_handle(node_handle) {
+                                        Some(self.node_property_changed_handler.handle(
+                                            args,
+                                            node_handle,
+                                            &mut scene.graph[node_handle],
+                                        ))
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .collect::<Vec<_>>(),
+                        }
+                    }
                     Selection::SoundContext => handle_sound_context_property_changed(args)
                         .map(|c| vec![c])
                         .unwrap_or_default(),
@@ -4,7 +4,11 @@ use crate::{
         calculate_gizmo_distance_scaling, gizmo::rotate_gizmo::RotationGizmo, InteractionMode,
     },
     scene::{
-        commands::{graph::RotateNodeCommand, ChangeSelectionCommand, CommandGroup, SceneCommand},
+        commands::{
+            graph::RotateNodeCommand,
+            sound::{SetSoundConeCommand, SoundCone},
+            ChangeSelectionCommand, CommandGroup, SceneCommand,
+        },
         EditorScene, Selection,
     },
     settings::Settings,
@@ -107,11 +111,28 @@ impl InteractionMode for RotateInteractionMode {
                                 .iter()
                                 .zip(self.initial_rotations.iter().zip(current_rotation.iter()))
                                 .map(|(&node, (&old_rotation, &new_rotation))| {
-                                    SceneCommand::new(RotateNodeCommand::new(
-                                        node,
-                                        old_rotation,
-                                        new_rotation,
-                                    ))
+                                    if graph[node].is_sound() {
+                                        // Rotation of a sound orients its emission cone.
+                                        let sound = graph[node].as_sound();
+                                        let old_cone = SoundCone {
+                                            inner_angle: sound.cone_inner_angle(),
+                                            outer_angle: sound.cone_outer_angle(),
+                                            rotation: old_rotation,
+                                        };
+                                        let new_cone = SoundCone {
+                                            rotation: new_rotation,
+                                            ..old_cone
+                                        };
+                                        SceneCommand::new(SetSoundConeCommand::new(
+                                            node, old_cone, new_cone,
+                                        ))
+                                    } else {
+                                        SceneCommand::new(RotateNodeCommand::new(
+                                            node,
+                                            old_rotation,
+                                            new_rotation,
+                                        ))
+                                    }
                                 })
                                 .collect::<Vec<SceneCommand>>(),
                         );
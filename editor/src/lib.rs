@@ -71,7 +71,7 @@ use crate::{
     },
     scene_viewer::SceneViewer,
     settings::{camera::SceneCameraSettings, Settings},
-    utils::path_fixer::PathFixer,
+    utils::{find_ui_nodes_by_name, path_fixer::PathFixer},
     world::{graph::selection::GraphSelection, WorldViewer},
 };
 use fyrox::{
@@ -1750,6 +1750,7 @@ impl Editor {
         self.log.update(&mut self.engine);
         self.material_editor.update(&mut self.engine);
         self.asset_browser.update(&mut self.engine);
+        self.path_fixer.update(&self.engine.user_interface);
 
         if let Some(scene) = self.scene.as_ref() {
             self.animation_editor.update(scene, &self.engine);
@@ -2013,6 +2014,18 @@ impl Editor {
         self.engine.add_plugin_constructor(plugin)
     }
 
+    /// Moves keyboard focus to the first widget of the editor whose name contains the given
+    /// substring (case-insensitive). Returns the handle of the widget, or `None` if there is no
+    /// such widget.
+    pub fn jump_to_widget(&self, name: &str) -> Option<Handle<UiNode>> {
+        let ui = &self.engine.user_interface;
+        let widget = find_ui_nodes_by_name(ui.root(), ui, name)
+            .first()
+            .cloned()?;
+        ui.send_message(WidgetMessage::focus(widget, MessageDirection::ToWidget));
+        Some(widget)
+    }
+
     pub fn run(mut self, event_loop: EventLoop<()>) -> ! {
         event_loop.run(move |event, _, control_flow| match event {
             Event::MainEventsCleared => {
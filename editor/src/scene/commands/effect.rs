@@ -1,4 +1,6 @@
-use crate::{define_universal_commands, scene::commands::SceneCommand, Command, SceneContext};
+use crate::{
+    define_universal_commands, scene::commands::SceneCommand, Command, Message, SceneContext,
+};
 use fyrox::{
     core::reflect::prelude::*,
     core::{
@@ -111,3 +113,101 @@ impl Command for RemoveEffectCommand {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct SetEffectVariantCommand {
+    handle: Handle<Effect>,
+    effect: Option<Effect>,
+}
+
+impl SetEffectVariantCommand {
+    pub fn new(handle: Handle<Effect>, effect: Effect) -> Self {
+        Self {
+            handle,
+            effect: Some(effect),
+        }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        let old_effect = context
+            .scene
+            .graph
+            .sound_context
+            .replace_effect(self.handle, self.effect.take().unwrap());
+        self.effect = Some(old_effect);
+
+        // Layout of the properties was changed, so the inspector must be re-created.
+        context
+            .message_sender
+            .send(Message::SelectionChanged {
+                old_selection: context.editor_scene.selection.clone(),
+            })
+            .unwrap();
+    }
+}
+
+impl Command for SetEffectVariantCommand {
+    fn name(&mut self, _: &SceneContext) -> String {
+        "Set Effect Variant".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::Command,
+        scene::commands::{effect::SetEffectVariantCommand, test::TestScene},
+        Message,
+    };
+    use fyrox::scene::{sound::effect::Effect, Scene};
+
+    #[test]
+    fn test_set_effect_variant_command() {
+        let mut test_scene = TestScene::new(Scene::new());
+        let mut reverb = Effect::default();
+        reverb.set_name("Echo");
+        reverb.set_gain(0.5);
+        let handle = test_scene.scene.graph.sound_context.add_effect(reverb);
+
+        let delay = test_scene
+            .scene
+            .graph
+            .sound_context
+            .effect(handle)
+            .with_variant("Delay")
+            .unwrap();
+        let mut command = SetEffectVariantCommand::new(handle, delay);
+        let effect = |test_scene: &TestScene| {
+            let effect = test_scene.scene.graph.sound_context.effect(handle);
+            (effect.is_delay(), effect.name_owned(), effect.gain())
+        };
+
+        // Execute
+        command.execute(&mut test_scene.context());
+        assert_eq!(effect(&test_scene), (true, "Echo".to_string(), 0.5));
+        assert!(matches!(
+            test_scene.receiver.try_recv(),
+            Ok(Message::SelectionChanged { .. })
+        ));
+
+        // Revert
+        command.revert(&mut test_scene.context());
+        assert_eq!(effect(&test_scene), (false, "Echo".to_string(), 0.5));
+        assert!(matches!(
+            test_scene.receiver.try_recv(),
+            Ok(Message::SelectionChanged { .. })
+        ));
+
+        // Redo
+        command.execute(&mut test_scene.context());
+        assert_eq!(effect(&test_scene), (true, "Echo".to_string(), 0.5));
+    }
+}
@@ -8,9 +8,17 @@ use crate::{
     GameEngine, Message,
 };
 use fyrox::{
-    core::{pool::Handle, reflect::prelude::*},
+    core::{
+        pool::Handle,
+        reflect::{default_registry::DefaultRegistry, prelude::*},
+    },
     engine::{resource_manager::ResourceManager, SerializationContext},
-    scene::{graph::SubGraph, node::Node, Scene},
+    gui::inspector::{PropertyAction, PropertyChanged},
+    scene::{
+        graph::{Graph, SubGraph},
+        node::Node,
+        Scene,
+    },
     utils::log::Log,
 };
 use std::{
@@ -23,6 +31,7 @@ pub mod graph;
 pub mod material;
 pub mod mesh;
 pub mod navmesh;
+pub mod sound;
 pub mod sound_context;
 pub mod terrain;
 
@@ -498,6 +507,268 @@ impl Command for RevertSceneNodePropertyCommand {
     }
 }
 
+/// Reflection path of the local position of any scene node, used to copy positions between nodes.
+pub const LOCAL_POSITION_PATH: &str = "base.local_transform.local_position";
+
+/// Copies a value of a property at the given path from one node to another. The property is read
+/// using reflection, so both nodes must have a property at the path with the same type (for example,
+/// the color of two lights).
+#[derive(Debug)]
+pub struct CopyFieldCommand {
+    from: Handle<Node>,
+    to: Handle<Node>,
+    path: String,
+    value: Option<Box<dyn Reflect>>,
+}
+
+impl CopyFieldCommand {
+    pub fn new(from: Handle<Node>, to: Handle<Node>, path: String) -> Self {
+        Self {
+            from,
+            to,
+            path,
+            value: None,
+        }
+    }
+
+    fn read_source_value(&self, graph: &Graph) -> Option<Box<dyn Reflect>> {
+        let mut value = None;
+        graph[self.from].as_reflect(&mut |node| {
+            node.resolve_path(&self.path, &mut |result| match result {
+                Ok(field) => {
+                    field.as_inheritable_variable(&mut |inheritable| {
+                        value = match inheritable {
+                            Some(inheritable) => Some(inheritable.clone_value_box()),
+                            None => field.try_clone_box(),
+                        };
+                    });
+                    if value.is_none() {
+                        Log::err(format!(
+                            "Failed to copy property {}. Reason: the value cannot be cloned!",
+                            self.path
+                        ))
+                    }
+                }
+                Err(e) => Log::err(format!(
+                    "Failed to copy property {}. Reason: {:?}",
+                    self.path, e
+                )),
+            })
+        });
+        value
+    }
+
+    fn swap(&mut self, graph: &mut Graph) {
+        if self.value.is_none() {
+            // Capture the value once, so redo will set exactly the same value even if the source
+            // has changed since then.
+            self.value = self.read_source_value(graph);
+        }
+
+        if let Some(value) = self.value.take() {
+            let mut value = Some(value);
+            graph[self.to].as_reflect_mut(&mut |node| {
+                node.set_field_by_path(
+                    &self.path,
+                    value.take().unwrap(),
+                    &mut |result| match result {
+                        Ok(old_value) => self.value = Some(old_value),
+                        Err(err) => {
+                            let value = match err {
+                                SetFieldByPathError::InvalidPath { value, .. } => value,
+                                SetFieldByPathError::InvalidValue(value) => value,
+                            };
+                            Log::err(format!(
+                                "Failed to copy property {}. Reason: incompatible target!",
+                                self.path
+                            ));
+                            self.value = Some(value);
+                        }
+                    },
+                );
+            });
+        }
+    }
+}
+
+impl Command for CopyFieldCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        format!("Copy {} Property", self.path)
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(&mut context.scene.graph);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(&mut context.scene.graph);
+    }
+}
+
+/// Sets a value of a property at the given path on every node from the given set (usually it is
+/// the current selection) as a single undoable action. Nodes that do not have such property are
+/// skipped. Each node is changed by its own [`SetPropertyCommand`], so revert restores individual
+/// values of every node.
+#[derive(Debug)]
+pub struct ApplyToSelectionCommand {
+    group: CommandGroup,
+    node_count: usize,
+}
+
+impl ApplyToSelectionCommand {
+    /// Creates the command from a property change made in the inspector. Returns `None` if the
+    /// change is not a plain modification of a value (collection or inheritance changes must be
+    /// handled per node) or none of the nodes has the property.
+    pub fn new(graph: &Graph, nodes: &[Handle<Node>], args: &PropertyChanged) -> Option<Self> {
+        let path = args.path();
+        let mut group = CommandGroup::from(Vec::new());
+        let mut node_count = 0;
+        for &node in nodes {
+            let mut has_property = false;
+            if let Some(node) = graph.try_get(node) {
+                node.as_reflect(&mut |node| {
+                    node.resolve_path(&path, &mut |result| has_property = result.is_ok())
+                });
+            }
+            if !has_property {
+                continue;
+            }
+
+            match PropertyAction::from_field_kind(&args.value) {
+                PropertyAction::Modify { value } => {
+                    group.push(SceneCommand::new(SetPropertyCommand::new(
+                        node,
+                        path.clone(),
+                        value,
+                    )));
+                    node_count += 1;
+                }
+                _ => return None,
+            }
+        }
+
+        if node_count == 0 {
+            None
+        } else {
+            Some(Self { group, node_count })
+        }
+    }
+}
+
+impl Command for ApplyToSelectionCommand {
+    fn name(&mut self, context: &SceneContext) -> String {
+        // Every command of the group sets the same property, so the name of the first one is used.
+        let name = self
+            .group
+            .commands
+            .first_mut()
+            .map(|command| command.name(context))
+            .unwrap_or_default();
+        format!("{} ({} Nodes)", name, self.node_count)
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.group.execute(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.group.revert(context);
+    }
+
+    fn finalize(&mut self, context: &mut SceneContext) {
+        self.group.finalize(context);
+    }
+}
+
+/// Resizes a collection property of a node at the given path. If the collection grows, new items are
+/// created with default values (see [`DefaultRegistry`]), if it shrinks, removed items are stored in
+/// the command, so they will be put back on revert.
+#[derive(Debug)]
+pub struct ResizeCollectionCommand {
+    handle: Handle<Node>,
+    path: String,
+    new_len: usize,
+    old_len: usize,
+    removed: Vec<Box<dyn Reflect>>,
+}
+
+impl ResizeCollectionCommand {
+    pub fn new(handle: Handle<Node>, path: String, new_len: usize) -> Self {
+        Self {
+            handle,
+            path,
+            new_len,
+            old_len: new_len,
+            removed: Default::default(),
+        }
+    }
+
+    fn modify_list<F: FnOnce(&mut dyn ReflectList)>(entity: &mut dyn Reflect, path: &str, func: F) {
+        let mut func = Some(func);
+        entity.resolve_path_mut(path, &mut |result| match result {
+            Ok(field) => field.as_list_mut(&mut |result| match result {
+                Some(list) => (func.take().unwrap())(list),
+                None => Log::err(format!("Property {} is not a collection!", path)),
+            }),
+            Err(e) => Log::err(format!(
+                "There is no such property {}! Reason: {:?}",
+                path, e
+            )),
+        })
+    }
+
+    fn resize(&mut self, entity: &mut dyn Reflect, default_registry: &DefaultRegistry) {
+        Self::modify_list(entity, &self.path, |list| {
+            self.old_len = list.reflect_len();
+            match list.reflect_resize(self.new_len, default_registry) {
+                Some(removed) => self.removed = removed,
+                None => {
+                    // Nothing changed, so revert will do nothing too.
+                    self.old_len = self.new_len;
+                    Log::err(format!(
+                        "Failed to resize {} collection. There's no default value for its items!",
+                        self.path
+                    ))
+                }
+            }
+        })
+    }
+
+    fn restore(&mut self, entity: &mut dyn Reflect, default_registry: &DefaultRegistry) {
+        Self::modify_list(entity, &self.path, |list| {
+            // Shrinking never needs default values.
+            if self.old_len < list.reflect_len() {
+                list.reflect_resize(self.old_len, default_registry);
+            }
+
+            for item in self.removed.drain(..) {
+                if list.reflect_push(item).is_err() {
+                    Log::err(format!(
+                        "Failed to put item back to {} collection. Type mismatch!",
+                        self.path
+                    ))
+                }
+            }
+        })
+    }
+}
+
+impl Command for ResizeCollectionCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        format!("Resize {} Collection", self.path)
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        let registry = &context.serialization_context.default_registry;
+        context.scene.graph[self.handle].as_reflect_mut(&mut |node| self.resize(node, registry));
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        let registry = &context.serialization_context.default_registry;
+        context.scene.graph[self.handle].as_reflect_mut(&mut |node| self.restore(node, registry));
+    }
+}
+
 define_universal_commands!(
     make_set_node_property_command,
     Command,
@@ -509,3 +780,316 @@ define_universal_commands!(
     self,
     { &mut ctx.scene.graph[self.handle] as &mut dyn Reflect },
 );
+
+#[cfg(test)]
+pub mod test {
+    use crate::{
+        camera::CameraController,
+        command::Command,
+        scene::{
+            commands::{
+                make_set_node_property_command, ApplyToSelectionCommand, CopyFieldCommand,
+                ResizeCollectionCommand, SceneContext, LOCAL_POSITION_PATH,
+            },
+            EditorScene,
+        },
+        Message,
+    };
+    use fyrox::{
+        core::{
+            algebra::Vector3,
+            color::Color,
+            pool::Handle,
+            reflect::{default_registry::DefaultRegistry, prelude::*},
+        },
+        engine::{resource_manager::ResourceManager, SerializationContext},
+        gui::inspector::{FieldKind, ObjectValue, PropertyChanged},
+        scene::{
+            base::BaseBuilder,
+            graph::{Graph, GraphUpdateSwitches},
+            light::{
+                point::{PointLight, PointLightBuilder},
+                BaseLight, BaseLightBuilder,
+            },
+            node::Node,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+            Scene,
+        },
+    };
+    use std::{
+        any::TypeId,
+        sync::{
+            mpsc::{self, Receiver, Sender},
+            Arc,
+        },
+    };
+
+    /// A scene with everything that is needed to execute scene commands without the editor.
+    pub struct TestScene {
+        pub scene: Scene,
+        pub editor_scene: EditorScene,
+        pub serialization_context: Arc<SerializationContext>,
+        pub resource_manager: ResourceManager,
+        pub sender: Sender<Message>,
+        pub receiver: Receiver<Message>,
+    }
+
+    impl TestScene {
+        pub fn new(mut scene: Scene) -> Self {
+            let root = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+            let camera_controller = CameraController::new(&mut scene.graph, root, None);
+            let serialization_context = Arc::new(SerializationContext::new());
+            let (sender, receiver) = mpsc::channel();
+
+            Self {
+                editor_scene: EditorScene {
+                    has_unsaved_changes: false,
+                    path: None,
+                    scene: Default::default(),
+                    editor_objects_root: root,
+                    selection: Default::default(),
+                    clipboard: Default::default(),
+                    camera_controller,
+                    navmeshes: Default::default(),
+                    preview_camera: Default::default(),
+                    graph_switches: GraphUpdateSwitches::default(),
+                },
+                scene,
+                resource_manager: ResourceManager::new(serialization_context.clone()),
+                serialization_context,
+                sender,
+                receiver,
+            }
+        }
+
+        pub fn context(&mut self) -> SceneContext {
+            SceneContext {
+                editor_scene: &mut self.editor_scene,
+                scene: &mut self.scene,
+                message_sender: self.sender.clone(),
+                resource_manager: self.resource_manager.clone(),
+                serialization_context: self.serialization_context.clone(),
+            }
+        }
+    }
+
+    /// Adds a point light with radius 2.0 to the scene. Returns its handle and a property change
+    /// that sets the radius to 5.0.
+    pub fn add_point_light_radius_change(scene: &mut Scene) -> (Handle<Node>, PropertyChanged) {
+        let light = PointLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new()))
+            .with_radius(2.0)
+            .build(&mut scene.graph);
+
+        let property_changed = PropertyChanged {
+            name: "radius".to_string(),
+            owner_type_id: TypeId::of::<PointLight>(),
+            value: FieldKind::Object(ObjectValue {
+                value: Box::new(5.0f32),
+            }),
+        };
+
+        (light, property_changed)
+    }
+
+    pub fn point_light_radius(scene: &Scene, light: Handle<Node>) -> f32 {
+        scene.graph[light].cast::<PointLight>().unwrap().radius()
+    }
+
+    #[test]
+    fn test_set_node_property_command() {
+        let mut test_scene = TestScene::new(Scene::new());
+        let (light, property_changed) = add_point_light_radius_change(&mut test_scene.scene);
+
+        let mut command = make_set_node_property_command(light, &property_changed).unwrap();
+
+        // Execute
+        command.execute(&mut test_scene.context());
+        assert_eq!(point_light_radius(&test_scene.scene, light), 5.0);
+        assert_eq!(command.name(&test_scene.context()), "Set Radius");
+
+        // Revert
+        command.revert(&mut test_scene.context());
+        assert_eq!(point_light_radius(&test_scene.scene, light), 2.0);
+
+        // Redo
+        command.execute(&mut test_scene.context());
+        assert_eq!(point_light_radius(&test_scene.scene, light), 5.0);
+
+        // Nested property.
+        let property_changed = PropertyChanged {
+            name: "base_light".to_string(),
+            owner_type_id: TypeId::of::<PointLight>(),
+            value: FieldKind::Inspectable(Box::new(PropertyChanged {
+                name: "color".to_string(),
+                owner_type_id: TypeId::of::<BaseLight>(),
+                value: FieldKind::Object(ObjectValue {
+                    value: Box::new(Color::RED),
+                }),
+            })),
+        };
+        let light_color = |test_scene: &TestScene| {
+            test_scene.scene.graph[light]
+                .query_component_ref::<BaseLight>()
+                .unwrap()
+                .color()
+        };
+        let old_color = light_color(&test_scene);
+
+        let mut command = make_set_node_property_command(light, &property_changed).unwrap();
+        command.execute(&mut test_scene.context());
+        assert_eq!(light_color(&test_scene), Color::RED);
+        assert_eq!(command.name(&test_scene.context()), "Set Color");
+        command.revert(&mut test_scene.context());
+        assert_eq!(light_color(&test_scene), old_color);
+    }
+
+    fn light_color(graph: &Graph, handle: Handle<Node>) -> Color {
+        graph[handle]
+            .query_component_ref::<BaseLight>()
+            .unwrap()
+            .color()
+    }
+
+    #[test]
+    fn test_copy_field_command() {
+        let mut graph = Graph::new();
+        let from = PointLightBuilder::new(
+            BaseLightBuilder::new(BaseBuilder::new()).with_color(Color::RED),
+        )
+        .build(&mut graph);
+        let to = PointLightBuilder::new(
+            BaseLightBuilder::new(BaseBuilder::new()).with_color(Color::GREEN),
+        )
+        .build(&mut graph);
+
+        let mut command = CopyFieldCommand::new(from, to, "base_light.color".to_string());
+
+        // Execute
+        command.swap(&mut graph);
+        assert_eq!(light_color(&graph, to), Color::RED);
+        assert_eq!(light_color(&graph, from), Color::RED);
+
+        // Revert
+        command.swap(&mut graph);
+        assert_eq!(light_color(&graph, to), Color::GREEN);
+        assert_eq!(light_color(&graph, from), Color::RED);
+
+        // Redo
+        command.swap(&mut graph);
+        assert_eq!(light_color(&graph, to), Color::RED);
+    }
+
+    #[test]
+    fn test_copy_position_field_command() {
+        let mut graph = Graph::new();
+        let from = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 2.0, 3.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        let to = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let position =
+            |graph: &Graph, handle: Handle<Node>| **graph[handle].local_transform().position();
+
+        let mut command = CopyFieldCommand::new(from, to, LOCAL_POSITION_PATH.to_string());
+
+        command.swap(&mut graph);
+        assert_eq!(position(&graph, to), Vector3::new(1.0, 2.0, 3.0));
+        command.swap(&mut graph);
+        assert_eq!(position(&graph, to), Vector3::default());
+        command.swap(&mut graph);
+        assert_eq!(position(&graph, to), Vector3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn test_apply_to_selection_command() {
+        let mut test_scene = TestScene::new(Scene::new());
+        let colors = [Color::RED, Color::GREEN, Color::BLUE];
+        let mut nodes = colors
+            .iter()
+            .map(|color| {
+                PointLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new()).with_color(*color))
+                    .build(&mut test_scene.scene.graph)
+            })
+            .collect::<Vec<_>>();
+        // Pivot does not have a color, it must be left untouched.
+        let pivot = PivotBuilder::new(BaseBuilder::new()).build(&mut test_scene.scene.graph);
+        nodes.push(pivot);
+
+        let property_changed = PropertyChanged {
+            name: "base_light".to_string(),
+            owner_type_id: TypeId::of::<PointLight>(),
+            value: FieldKind::Inspectable(Box::new(PropertyChanged {
+                name: "color".to_string(),
+                owner_type_id: TypeId::of::<BaseLight>(),
+                value: FieldKind::Object(ObjectValue {
+                    value: Box::new(Color::WHITE),
+                }),
+            })),
+        };
+
+        let mut command =
+            ApplyToSelectionCommand::new(&test_scene.scene.graph, &nodes, &property_changed)
+                .unwrap();
+        let light_colors = |test_scene: &TestScene| {
+            nodes[..3]
+                .iter()
+                .map(|&node| {
+                    test_scene.scene.graph[node]
+                        .query_component_ref::<BaseLight>()
+                        .unwrap()
+                        .color()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Execute
+        command.execute(&mut test_scene.context());
+        assert_eq!(light_colors(&test_scene), [Color::WHITE; 3]);
+        assert_eq!(command.name(&test_scene.context()), "Set Color (3 Nodes)");
+
+        // Revert
+        command.revert(&mut test_scene.context());
+        assert_eq!(light_colors(&test_scene), colors);
+
+        // Redo
+        command.execute(&mut test_scene.context());
+        assert_eq!(light_colors(&test_scene), [Color::WHITE; 3]);
+    }
+
+    #[test]
+    fn test_resize_collection_command() {
+        #[derive(Reflect, Debug)]
+        struct Entity {
+            items: Vec<f32>,
+        }
+
+        let registry = DefaultRegistry::new();
+        let mut entity = Entity {
+            items: vec![1.5, 2.5],
+        };
+
+        // Grow
+        let mut command = ResizeCollectionCommand::new(Default::default(), "items".to_string(), 4);
+        command.resize(&mut entity, &registry);
+        assert_eq!(entity.items, [1.5, 2.5, 0.0, 0.0]);
+        command.restore(&mut entity, &registry);
+        assert_eq!(entity.items, [1.5, 2.5]);
+
+        // Shrink
+        let mut command = ResizeCollectionCommand::new(Default::default(), "items".to_string(), 1);
+        command.resize(&mut entity, &registry);
+        assert_eq!(entity.items, [1.5]);
+        command.restore(&mut entity, &registry);
+        assert_eq!(entity.items, [1.5, 2.5]);
+
+        // Redo
+        command.resize(&mut entity, &registry);
+        assert_eq!(entity.items, [1.5]);
+    }
+}
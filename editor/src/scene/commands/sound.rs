@@ -0,0 +1,112 @@
+use crate::{command::Command, scene::commands::SceneContext};
+use fyrox::{
+    core::{algebra::UnitQuaternion, pool::Handle},
+    scene::{graph::Graph, node::Node},
+};
+
+/// Emission cone of a sound node. The cone is oriented along the look vector of the node, so the
+/// rotation of the node is a part of the cone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundCone {
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+    pub rotation: UnitQuaternion<f32>,
+}
+
+/// Sets angles of the emission cone of a sound node together with its orientation.
+#[derive(Debug)]
+pub struct SetSoundConeCommand {
+    node: Handle<Node>,
+    old_cone: SoundCone,
+    new_cone: SoundCone,
+}
+
+impl SetSoundConeCommand {
+    pub fn new(node: Handle<Node>, old_cone: SoundCone, new_cone: SoundCone) -> Self {
+        Self {
+            node,
+            old_cone,
+            new_cone,
+        }
+    }
+
+    fn swap(&mut self, graph: &mut Graph) {
+        let cone = self.new_cone;
+        std::mem::swap(&mut self.new_cone, &mut self.old_cone);
+
+        let node = &mut graph[self.node];
+        node.local_transform_mut().set_rotation(cone.rotation);
+        let sound = node.as_sound_mut();
+        sound.set_cone_inner_angle(cone.inner_angle);
+        sound.set_cone_outer_angle(cone.outer_angle);
+    }
+}
+
+impl Command for SetSoundConeCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Sound Cone".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(&mut context.scene.graph);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(&mut context.scene.graph);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::commands::sound::{SetSoundConeCommand, SoundCone};
+    use fyrox::{
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::Handle,
+        },
+        scene::{base::BaseBuilder, graph::Graph, node::Node, sound::SoundBuilder},
+    };
+
+    fn sound_cone(graph: &Graph, handle: Handle<Node>) -> SoundCone {
+        let node = &graph[handle];
+        let sound = node.as_sound();
+        SoundCone {
+            inner_angle: sound.cone_inner_angle(),
+            outer_angle: sound.cone_outer_angle(),
+            rotation: **node.local_transform().rotation(),
+        }
+    }
+
+    #[test]
+    fn test_set_sound_cone_command() {
+        let mut graph = Graph::new();
+        let sound = SoundBuilder::new(BaseBuilder::new())
+            .with_cone_inner_angle(1.0)
+            .with_cone_outer_angle(2.0)
+            .build(&mut graph);
+
+        let old_cone = SoundCone {
+            inner_angle: 1.0,
+            outer_angle: 2.0,
+            rotation: UnitQuaternion::identity(),
+        };
+        let new_cone = SoundCone {
+            inner_angle: 0.5,
+            outer_angle: 1.5,
+            rotation: UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.5),
+        };
+        let mut command = SetSoundConeCommand::new(sound, old_cone, new_cone);
+
+        // Execute
+        command.swap(&mut graph);
+        assert_eq!(sound_cone(&graph, sound), new_cone);
+
+        // Revert
+        command.swap(&mut graph);
+        assert_eq!(sound_cone(&graph, sound), old_cone);
+
+        // Redo
+        command.swap(&mut graph);
+        assert_eq!(sound_cone(&graph, sound), new_cone);
+    }
+}
@@ -33,6 +33,7 @@ use fyrox::{
         },
         node::Node,
         pivot::PivotBuilder,
+        sound::Sound,
         Scene,
     },
 };
@@ -342,6 +343,40 @@ impl EditorScene {
                     Color::GREEN,
                     false,
                 );
+            } else if let Some(sound) = node.query_component_ref::<Sound>() {
+                // Emission cones are oriented along the look vector of the node, so they're
+                // rotated by the rotation gizmo together with the node.
+                let transform = Matrix4::new_translation(&sound.global_position())
+                    * UnitQuaternion::from_matrix_eps(
+                        &sound.global_transform().basis(),
+                        f32::EPSILON,
+                        16,
+                        UnitQuaternion::identity(),
+                    )
+                    .to_homogeneous()
+                    * UnitQuaternion::from_axis_angle(
+                        &Vector3::x_axis(),
+                        -std::f32::consts::FRAC_PI_2,
+                    )
+                    .to_homogeneous();
+                let length = sound.radius();
+                for (angle, color) in [
+                    (sound.cone_inner_angle(), Color::GREEN),
+                    (sound.cone_outer_angle(), Color::ORANGE),
+                ] {
+                    // Wide cones can't be drawn as a cone, such sounds are (almost) omnidirectional.
+                    if angle < std::f32::consts::PI {
+                        ctx.draw_cone(
+                            16,
+                            (angle * 0.5).tan() * length,
+                            length,
+                            transform
+                                * Matrix4::new_translation(&Vector3::new(0.0, -length * 0.5, 0.0)),
+                            color,
+                            false,
+                        );
+                    }
+                }
             }
 
             for &child in node.children() {
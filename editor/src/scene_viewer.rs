@@ -1,7 +1,7 @@
 use crate::{
     camera::PickingOptions, gui::make_dropdown_list_option,
     gui::make_dropdown_list_option_with_height, load_image, settings::keys::KeyBindings,
-    utils::enable_widget, AddModelCommand, AssetItem, AssetKind, BuildProfile,
+    utils::enable_widgets, AddModelCommand, AssetItem, AssetKind, BuildProfile,
     ChangeSelectionCommand, CommandGroup, DropdownListBuilder, EditorScene, GameEngine,
     GraphSelection, InteractionMode, InteractionModeKind, Message, Mode, SceneCommand, Selection,
     SetMeshTextureCommand, Settings,
@@ -703,9 +703,11 @@ impl SceneViewer {
             MessageDirection::ToWidget,
             ButtonContent::text(if enabled { "Play" } else { "Stop" }),
         ));
-        for widget in [self.interaction_mode_panel, self.contextual_actions] {
-            enable_widget(widget, enabled, ui);
-        }
+        enable_widgets(
+            &[self.interaction_mode_panel, self.contextual_actions],
+            enabled,
+            ui,
+        );
     }
 
     pub fn set_render_target(&self, ui: &UserInterface, render_target: Option<Texture>) {
@@ -10,33 +10,33 @@ use fyrox::{
     resource::texture::{CompressionOptions, Texture},
     scene::camera::{SkyBox, SkyBoxBuilder},
 };
+use std::{collections::HashMap, hash::Hash};
 
 pub mod path_fixer;
+pub mod task;
 
-pub fn is_slice_equal_permutation<T: PartialEq>(a: &[T], b: &[T]) -> bool {
-    if a.is_empty() && !b.is_empty() {
-        false
-    } else {
-        // TODO: Find a way to do this faster.
-        for source in a.iter() {
-            let mut found = false;
-            for other in b.iter() {
-                if other == source {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                return false;
-            }
+/// Returns true if both slices contain the same elements in any order. Every element is counted, so
+/// the slices are equal only if they have the same elements with the same number of occurrences.
+pub fn is_slice_equal_permutation_hashed<T: Eq + Hash>(a: &[T], b: &[T]) -> bool {
+    fn count<T: Eq + Hash>(slice: &[T]) -> HashMap<&T, usize> {
+        let mut counts = HashMap::with_capacity(slice.len());
+        for item in slice {
+            *counts.entry(item).or_insert(0) += 1;
         }
-        true
+        counts
     }
+
+    a.len() == b.len() && count(a) == count(b)
+}
+
+/// Returns a reference to the window with the given handle, or `None` if the handle is invalid or
+/// it does not point to a window.
+pub fn try_window(handle: Handle<UiNode>, ui: &UserInterface) -> Option<&Window> {
+    ui.try_get_node(handle).and_then(|n| n.cast::<Window>())
 }
 
 pub fn window_content(window: Handle<UiNode>, ui: &UserInterface) -> Handle<UiNode> {
-    ui.node(window)
-        .cast::<Window>()
+    try_window(window, ui)
         .map(|w| w.content())
         .unwrap_or_default()
 }
@@ -49,6 +49,13 @@ pub fn enable_widget(handle: Handle<UiNode>, state: bool, ui: &UserInterface) {
     ));
 }
 
+/// Enables or disables every widget from the given list.
+pub fn enable_widgets(handles: &[Handle<UiNode>], state: bool, ui: &UserInterface) {
+    for &handle in handles {
+        enable_widget(handle, state, ui);
+    }
+}
+
 pub fn create_file_selector(
     ctx: &mut BuildContext,
     extension: &'static str,
@@ -92,7 +99,7 @@ fn load_texture(data: &[u8]) -> Texture {
         .unwrap()
 }
 
-pub fn built_in_skybox() -> SkyBox {
+fn decode_built_in_skybox() -> SkyBox {
     let front = load_texture(include_bytes!("../../resources/embed/skybox/front.png"));
     let back = load_texture(include_bytes!("../../resources/embed/skybox/back.png"));
     let top = load_texture(include_bytes!("../../resources/embed/skybox/top.png"));
@@ -112,37 +119,315 @@ pub fn built_in_skybox() -> SkyBox {
     .unwrap()
 }
 
+lazy_static! {
+    // Decoding of the skybox textures is quite slow, so they're decoded once and then shared.
+    static ref BUILT_IN_SKYBOX: SkyBox = decode_built_in_skybox();
+}
+
+/// Returns a copy of the built-in skybox. The copy shares the textures with the original skybox,
+/// the textures are decoded only once.
+pub fn built_in_skybox() -> SkyBox {
+    BUILT_IN_SKYBOX.clone()
+}
+
 pub fn make_node_name(name: &str, handle: ErasedHandle) -> String {
     format!("{} ({}:{})", name, handle.index(), handle.generation())
 }
 
+/// Maximum depth of the UI tree that is visited by [`find_ui_nodes_by_name`].
+pub const MAX_NAME_SEARCH_DEPTH: usize = 256;
+
+/// Walks the UI tree starting from the given root and collects handles of every node whose name
+/// contains the given substring (case-insensitive). The handles are in depth-first pre-order.
+/// Nodes deeper than [`MAX_NAME_SEARCH_DEPTH`] are ignored.
+pub fn find_ui_nodes_by_name(
+    root: Handle<UiNode>,
+    ui: &UserInterface,
+    substring: &str,
+) -> Vec<Handle<UiNode>> {
+    fn find_recursive(
+        node: Handle<UiNode>,
+        ui: &UserInterface,
+        substring: &str,
+        depth: usize,
+        result: &mut Vec<Handle<UiNode>>,
+    ) {
+        if depth > MAX_NAME_SEARCH_DEPTH {
+            return;
+        }
+
+        let node_ref = ui.node(node);
+
+        if node_ref.name().to_lowercase().contains(substring) {
+            result.push(node);
+        }
+
+        for &child in node_ref.children() {
+            find_recursive(child, ui, substring, depth + 1, result);
+        }
+    }
+
+    let mut result = Vec::new();
+    find_recursive(root, ui, &substring.to_lowercase(), 0, &mut result);
+    result
+}
+
+// Walks the tree from the given root and calls `visit` for every node for which the filter returns
+// `Some`, passing true if the node itself or any of its descendants matches the filter. Returns true
+// if there is any match in the tree.
+fn visit_filter_matches<F>(
+    node: Handle<UiNode>,
+    ui: &UserInterface,
+    filter: &F,
+    visit: &mut dyn FnMut(Handle<UiNode>, bool),
+) -> bool
+where
+    F: Fn(&UiNode) -> Option<bool>,
+{
+    let node_ref = ui.node(node);
+
+    let mut is_any_match = false;
+    for &child in node_ref.children() {
+        is_any_match |= visit_filter_matches(child, ui, filter, visit)
+    }
+
+    if let Some(has_match) = filter(node_ref) {
+        is_any_match |= has_match;
+
+        visit(node, is_any_match);
+    }
+
+    is_any_match
+}
+
 pub fn apply_visibility_filter<F>(root: Handle<UiNode>, ui: &UserInterface, filter: F)
 where
     F: Fn(&UiNode) -> Option<bool>,
 {
-    fn apply_filter_recursive<F>(node: Handle<UiNode>, ui: &UserInterface, filter: &F) -> bool
-    where
-        F: Fn(&UiNode) -> Option<bool>,
-    {
-        let node_ref = ui.node(node);
+    visit_filter_matches(root, ui, &filter, &mut |node, is_any_match| {
+        ui.send_message(WidgetMessage::visibility(
+            node,
+            MessageDirection::ToWidget,
+            is_any_match,
+        ));
+    });
+}
 
-        let mut is_any_match = false;
-        for &child in node_ref.children() {
-            is_any_match |= apply_filter_recursive(child, ui, filter)
+/// Same as [`apply_visibility_filter`], but does not change visibility of the nodes. Instead, it
+/// returns handles of every node that would be visible - the nodes that match the filter and the
+/// nodes that have a matching descendant. The handles are in depth-first post-order.
+pub fn collect_visibility_matches<F>(
+    root: Handle<UiNode>,
+    ui: &UserInterface,
+    filter: F,
+) -> Vec<Handle<UiNode>>
+where
+    F: Fn(&UiNode) -> Option<bool>,
+{
+    let mut matches = Vec::new();
+    visit_filter_matches(root, ui, &filter, &mut |node, is_any_match| {
+        if is_any_match {
+            matches.push(node);
         }
+    });
+    matches
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::{
+        built_in_skybox, collect_visibility_matches, decode_built_in_skybox, enable_widgets,
+        find_ui_nodes_by_name, is_slice_equal_permutation_hashed, try_window, window_content,
+        MAX_NAME_SEARCH_DEPTH,
+    };
+    use fyrox::{
+        core::{algebra::Vector2, pool::Handle},
+        gui::{
+            button::ButtonBuilder,
+            stack_panel::StackPanelBuilder,
+            text::{Text, TextBuilder},
+            widget::{WidgetBuilder, WidgetMessage},
+            window::{WindowBuilder, WindowTitle},
+            UiNode, UserInterface,
+        },
+    };
+    use std::time::Instant;
+
+    #[test]
+    fn test_collect_visibility_matches() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let foo = TextBuilder::new(WidgetBuilder::new())
+            .with_text("Foo")
+            .build(ctx);
+        let bar = TextBuilder::new(WidgetBuilder::new())
+            .with_text("Bar")
+            .build(ctx);
+        let foobar = TextBuilder::new(WidgetBuilder::new())
+            .with_text("FooBar")
+            .build(ctx);
+        let group = StackPanelBuilder::new(WidgetBuilder::new().with_child(foobar)).build(ctx);
+        let empty_group = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+        let root = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_child(foo)
+                .with_child(bar)
+                .with_child(group)
+                .with_child(empty_group),
+        )
+        .build(ctx);
+
+        // Drop the messages produced while building the widgets.
+        while ui.poll_message().is_some() {}
+
+        let filter = |node: &UiNode| {
+            if let Some(text) = node.query_component::<Text>() {
+                Some(text.text().contains("Foo"))
+            } else if node.handle() == group || node.handle() == empty_group {
+                // Groups are filtered by their children only.
+                Some(false)
+            } else {
+                None
+            }
+        };
+
+        assert_eq!(
+            collect_visibility_matches(root, &ui, filter),
+            vec![foo, foobar, group]
+        );
+
+        // The UI must stay untouched.
+        assert_eq!(ui.poll_message(), None);
+    }
+
+    #[test]
+    fn test_built_in_skybox_is_shared() {
+        // Textures are compared by their shared state, so equal skyboxes share the same textures.
+        assert_eq!(built_in_skybox(), built_in_skybox());
+    }
+
+    #[test]
+    fn test_built_in_skybox_decode_time() {
+        // Make sure that the shared skybox is decoded already.
+        built_in_skybox();
+
+        let start = Instant::now();
+        let _ = decode_built_in_skybox();
+        let decode_time = start.elapsed();
+
+        let start = Instant::now();
+        let _ = built_in_skybox();
+        let shared_time = start.elapsed();
+
+        // Run with `--nocapture` to see the saved time.
+        println!(
+            "Built-in skybox: decoding takes {:?}, sharing takes {:?}",
+            decode_time, shared_time
+        );
+        assert!(shared_time < decode_time);
+    }
+
+    #[test]
+    fn test_is_slice_equal_permutation_hashed() {
+        let f = is_slice_equal_permutation_hashed::<u32>;
+        assert!(f(&[], &[]));
+        assert!(f(&[1, 2, 3], &[3, 1, 2]));
+        assert!(f(&[1, 1, 2], &[1, 2, 1]));
+        assert!(!f(&[1, 2, 3], &[1, 2, 4]));
+
+        // Different lengths.
+        assert!(!f(&[], &[1]));
+        assert!(!f(&[1], &[]));
+        assert!(!f(&[1], &[1, 1]));
+        assert!(!f(&[1, 1], &[1]));
+        assert!(!f(&[1, 2], &[1, 2, 3]));
+
+        // Duplicates with different number of occurrences.
+        assert!(!f(&[1, 1, 2], &[1, 2, 2]));
+        assert!(!f(&[1, 2, 2], &[1, 1, 2]));
+        assert!(f(&[2, 1, 2, 1], &[1, 1, 2, 2]));
+    }
 
-        if let Some(has_match) = filter(node_ref) {
-            is_any_match |= has_match;
+    #[test]
+    fn test_find_ui_nodes_by_name() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let ok = ButtonBuilder::new(WidgetBuilder::new().with_name("OkButton")).build(ctx);
+        let cancel = ButtonBuilder::new(WidgetBuilder::new().with_name("CancelButton")).build(ctx);
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_name("ButtonsPanel")
+                .with_child(ok)
+                .with_child(cancel),
+        )
+        .build(ctx);
+        let root = StackPanelBuilder::new(WidgetBuilder::new().with_name("Root").with_child(panel))
+            .build(ctx);
+
+        assert_eq!(
+            find_ui_nodes_by_name(root, &ui, "button"),
+            vec![panel, ok, cancel]
+        );
+        assert_eq!(find_ui_nodes_by_name(root, &ui, "CANCEL"), vec![cancel]);
+        assert!(find_ui_nodes_by_name(root, &ui, "Foo").is_empty());
+
+        // Nodes that are too deep must be ignored.
+        let ctx = &mut ui.build_ctx();
+        let deepest = StackPanelBuilder::new(WidgetBuilder::new().with_name("Deep")).build(ctx);
+        let mut deep_root = deepest;
+        for _ in 0..=MAX_NAME_SEARCH_DEPTH {
+            deep_root =
+                StackPanelBuilder::new(WidgetBuilder::new().with_child(deep_root)).build(ctx);
+        }
+        assert!(find_ui_nodes_by_name(deep_root, &ui, "Deep").is_empty());
+    }
 
-            ui.send_message(WidgetMessage::visibility(
-                node,
-                MessageDirection::ToWidget,
-                is_any_match,
-            ));
+    fn poll_enabled_messages(ui: &mut UserInterface) -> Vec<(Handle<UiNode>, bool)> {
+        let mut messages = Vec::new();
+        while let Some(message) = ui.poll_message() {
+            if let Some(WidgetMessage::Enabled(state)) = message.data() {
+                messages.push((message.destination(), *state));
+            }
         }
+        messages
+    }
+
+    #[test]
+    fn test_enable_widgets() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let a = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+        let b = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+
+        // Drop the messages produced while building the widgets.
+        poll_enabled_messages(&mut ui);
 
-        is_any_match
+        enable_widgets(&[a, b], false, &ui);
+        assert_eq!(poll_enabled_messages(&mut ui), vec![(a, false), (b, false)]);
     }
 
-    apply_filter_recursive(root, ui, &filter);
+    #[test]
+    fn test_try_window() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let content = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+        let window = WindowBuilder::new(WidgetBuilder::new())
+            .with_title(WindowTitle::text("Window"))
+            .with_content(content)
+            .build(ctx);
+
+        assert!(try_window(window, &ui).is_some());
+        assert_eq!(window_content(window, &ui), content);
+
+        // Not a window.
+        assert!(try_window(content, &ui).is_none());
+        assert_eq!(window_content(content, &ui), Handle::NONE);
+
+        // Invalid handle.
+        assert!(try_window(Handle::NONE, &ui).is_none());
+    }
 }
@@ -1,7 +1,11 @@
 //! Special utility that allows you to fix paths to resources. It is very useful if you've
 //! moved a resource in a file system, but a scene has old path.
 
-use crate::{make_scene_file_filter, Message};
+use crate::{
+    make_scene_file_filter,
+    utils::task::{TaskMessage, TaskRunner},
+    Message,
+};
 use fyrox::{
     asset::ResourceData,
     core::{
@@ -11,7 +15,7 @@ use fyrox::{
     gui::{
         border::BorderBuilder,
         brush::Brush,
-        button::{ButtonBuilder, ButtonMessage},
+        button::{ButtonBuilder, ButtonContent, ButtonMessage},
         decorator::DecoratorBuilder,
         file_browser::{FileSelectorBuilder, FileSelectorMessage},
         formatted_text::WrapMode,
@@ -56,6 +60,8 @@ pub struct PathFixer {
     resource_path: Handle<UiNode>,
     new_path_selector: Handle<UiNode>,
     auto_fix: Handle<UiNode>,
+    // Pairs of indices of orphaned resources and their new paths.
+    auto_fix_task: Option<TaskRunner<Vec<(usize, PathBuf)>>>,
 }
 
 #[derive(Clone)]
@@ -115,6 +121,28 @@ fn find_file(name: &Path) -> Vec<PathBuf> {
     files
 }
 
+/// Searches new paths for every given file name on a worker thread, since every search walks
+/// through the whole working directory.
+fn spawn_auto_fix(file_names: Vec<(usize, PathBuf)>) -> TaskRunner<Vec<(usize, PathBuf)>> {
+    TaskRunner::spawn(move |ctx| {
+        let mut fixes = Vec::new();
+        for (n, (index, file_name)) in file_names.iter().enumerate() {
+            if ctx.is_cancelled() {
+                break;
+            }
+
+            let mut candidates = find_file(file_name);
+            // Skip ambiguous file paths.
+            if candidates.len() == 1 {
+                fixes.push((*index, candidates.pop().unwrap()));
+            }
+
+            ctx.report_progress((n + 1) as f32 / file_names.len() as f32);
+        }
+        fixes
+    })
+}
+
 impl PathFixer {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let scene_selector = FileSelectorBuilder::new(
@@ -266,6 +294,59 @@ impl PathFixer {
             new_path_selector,
             auto_fix,
             scene_path_value: Default::default(),
+            auto_fix_task: None,
+        }
+    }
+
+    /// Applies the paths found by the auto-fix task (if any) once it is finished. Must be called
+    /// every frame.
+    pub fn update(&mut self, ui: &UserInterface) {
+        if let Some(task) = self.auto_fix_task.as_mut() {
+            let messages = task.poll();
+            if task.is_finished() {
+                self.auto_fix_task = None;
+                self.reset_auto_fix_button(ui);
+            }
+
+            for message in messages {
+                match message {
+                    TaskMessage::Progress(progress) => {
+                        // Ignore late progress reports, the button is already reset.
+                        if self.auto_fix_task.is_some() {
+                            ui.send_message(ButtonMessage::content(
+                                self.auto_fix,
+                                MessageDirection::ToWidget,
+                                ButtonContent::text(format!("{:.0}%", progress * 100.0)),
+                            ));
+                        }
+                    }
+                    TaskMessage::Finished(fixes) => {
+                        for (index, new_path) in fixes {
+                            self.fix_path(index, replace_slashes(new_path), ui);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn reset_auto_fix_button(&self, ui: &UserInterface) {
+        ui.send_message(ButtonMessage::content(
+            self.auto_fix,
+            MessageDirection::ToWidget,
+            ButtonContent::text("Auto Fix"),
+        ));
+        ui.send_message(WidgetMessage::enabled(
+            self.auto_fix,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
+    fn cancel_auto_fix(&mut self, ui: &UserInterface) {
+        if let Some(task) = self.auto_fix_task.take() {
+            task.cancel();
+            self.reset_auto_fix_button(ui);
         }
     }
 
@@ -310,6 +391,8 @@ impl PathFixer {
     ) {
         if let Some(FileSelectorMessage::Commit(path)) = message.data::<FileSelectorMessage>() {
             if message.destination() == self.scene_selector {
+                self.cancel_auto_fix(ui);
+
                 let message;
                 match block_on(Visitor::load_binary(path)) {
                     Ok(mut visitor) => {
@@ -493,11 +576,13 @@ impl PathFixer {
                     true,
                 ));
             } else if message.destination() == self.cancel {
+                self.cancel_auto_fix(ui);
                 ui.send_message(WindowMessage::close(
                     self.window,
                     MessageDirection::ToWidget,
                 ));
             } else if message.destination() == self.ok {
+                self.cancel_auto_fix(ui);
                 ui.send_message(WindowMessage::close(
                     self.window,
                     MessageDirection::ToWidget,
@@ -564,19 +649,24 @@ impl PathFixer {
                         true,
                     ));
                 }
-            } else if message.destination() == self.auto_fix {
-                for (i, orphaned_resource) in
-                    self.orphaned_scene_resources.clone().iter().enumerate()
-                {
-                    if let Some(file_name) = orphaned_resource.path().file_name() {
-                        let candidates = find_file(file_name.as_ref());
-                        // Skip ambiguous file paths.
-                        if candidates.len() == 1 {
-                            let new_path = candidates.first().unwrap().clone();
-                            self.fix_path(i, replace_slashes(new_path), ui);
-                        }
-                    }
-                }
+            } else if message.destination() == self.auto_fix && self.auto_fix_task.is_none() {
+                let file_names = self
+                    .orphaned_scene_resources
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, orphaned_resource)| {
+                        orphaned_resource
+                            .path()
+                            .file_name()
+                            .map(|file_name| (i, PathBuf::from(file_name)))
+                    })
+                    .collect::<Vec<_>>();
+                self.auto_fix_task = Some(spawn_auto_fix(file_names));
+                ui.send_message(WidgetMessage::enabled(
+                    self.auto_fix,
+                    MessageDirection::ToWidget,
+                    false,
+                ));
             }
         } else if let Some(ListViewMessage::SelectionChanged(selection)) =
             message.data::<ListViewMessage>()
@@ -0,0 +1,167 @@
+//! Runs long operations (lightmap baking, atlas packing, asset import, etc.) on a worker thread,
+//! so they won't block the event loop of the editor. The results are polled in the UI loop.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+/// A message that is sent from a task to the UI loop.
+#[derive(Debug, PartialEq)]
+pub enum TaskMessage<T> {
+    /// Progress of the task in `[0; 1]` range.
+    Progress(f32),
+    /// The task is finished with the given result.
+    Finished(T),
+}
+
+/// Allows a task to report its progress and to check whether it was cancelled. Long tasks should
+/// check the cancellation flag from time to time and stop as soon as possible when it is set.
+pub struct TaskContext<T> {
+    sender: Sender<TaskMessage<T>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T> TaskContext<T> {
+    /// Reports progress of the task, it will be clamped to `[0; 1]` range.
+    pub fn report_progress(&self, progress: f32) {
+        // Runner could be dropped already, nobody is interested in the progress then.
+        let _ = self
+            .sender
+            .send(TaskMessage::Progress(progress.clamp(0.0, 1.0)));
+    }
+
+    /// Returns true if the task was cancelled by its runner.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Runs a task on a worker thread and collects its messages. It should be polled every frame of
+/// the editor (see [`Self::poll`]).
+pub struct TaskRunner<T> {
+    receiver: Receiver<TaskMessage<T>>,
+    cancelled: Arc<AtomicBool>,
+    finished: bool,
+}
+
+impl<T: Send + 'static> TaskRunner<T> {
+    /// Starts the given task on a new worker thread.
+    pub fn spawn<F>(task: F) -> Self
+    where
+        F: FnOnce(&TaskContext<T>) -> T + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let context = TaskContext {
+            sender,
+            cancelled: cancelled.clone(),
+        };
+        thread::spawn(move || {
+            let result = task(&context);
+            let _ = context.sender.send(TaskMessage::Finished(result));
+        });
+
+        Self {
+            receiver,
+            cancelled,
+            finished: false,
+        }
+    }
+
+    /// Asks the task to stop. The task still has to finish (and provide some result), see
+    /// [`TaskContext::is_cancelled`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if the task is finished and its result was polled.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Collects all pending messages of the task without blocking. This method should be called
+    /// in the UI loop, the messages could be used to update progress indicators, etc.
+    pub fn poll(&mut self) -> Vec<TaskMessage<T>> {
+        let mut messages = Vec::new();
+
+        while !self.finished {
+            match self.receiver.try_recv() {
+                Ok(message) => {
+                    if let TaskMessage::Finished(_) = message {
+                        self.finished = true;
+                    }
+                    messages.push(message);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    // The task panicked.
+                    self.finished = true;
+                }
+            }
+        }
+
+        messages
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::task::{TaskMessage, TaskRunner};
+    use std::{thread, time::Duration};
+
+    fn run_to_completion<T: Send + 'static>(runner: &mut TaskRunner<T>) -> Vec<TaskMessage<T>> {
+        let mut messages = Vec::new();
+        while !runner.is_finished() {
+            messages.extend(runner.poll());
+            thread::sleep(Duration::from_millis(1));
+        }
+        messages
+    }
+
+    #[test]
+    fn test_task_runner() {
+        let mut runner = TaskRunner::spawn(|ctx| {
+            let mut sum = 0;
+            for i in 1..=4 {
+                sum += i;
+                ctx.report_progress(i as f32 / 4.0);
+            }
+            sum
+        });
+
+        let messages = run_to_completion(&mut runner);
+        assert_eq!(
+            messages,
+            [
+                TaskMessage::Progress(0.25),
+                TaskMessage::Progress(0.5),
+                TaskMessage::Progress(0.75),
+                TaskMessage::Progress(1.0),
+                TaskMessage::Finished(10),
+            ]
+        );
+        assert!(runner.poll().is_empty());
+    }
+
+    #[test]
+    fn test_task_runner_cancel() {
+        let mut runner = TaskRunner::spawn(|ctx| {
+            let mut iterations = 0u32;
+            while !ctx.is_cancelled() {
+                iterations += 1;
+                thread::sleep(Duration::from_millis(1));
+            }
+            iterations
+        });
+
+        runner.cancel();
+        let messages = run_to_completion(&mut runner);
+        assert!(matches!(messages.last(), Some(TaskMessage::Finished(_))));
+    }
+}
@@ -4,7 +4,8 @@ use crate::{
     scene::{
         commands::{
             graph::{AddNodeCommand, ReplaceNodeCommand},
-            make_delete_selection_command,
+            make_delete_selection_command, CommandGroup, CopyFieldCommand, SceneCommand,
+            LOCAL_POSITION_PATH,
         },
         EditorScene, Selection,
     },
@@ -39,6 +40,7 @@ pub struct ItemContextMenu {
     save_as_prefab: Handle<UiNode>,
     save_as_prefab_dialog: Handle<UiNode>,
     paste: Handle<UiNode>,
+    copy_position_to_selection: Handle<UiNode>,
 }
 
 impl ItemContextMenu {
@@ -47,6 +49,7 @@ impl ItemContextMenu {
         let copy_selection;
         let save_as_prefab;
         let paste;
+        let copy_position_to_selection;
 
         let (create_entity_menu, create_entity_menu_root_items) = CreateEntityMenu::new(ctx);
         let (replace_with_menu, replace_with_menu_root_items) = CreateEntityMenu::new(ctx);
@@ -70,6 +73,11 @@ impl ItemContextMenu {
                             paste = create_menu_item("Paste As Child", vec![], ctx);
                             paste
                         })
+                        .with_child({
+                            copy_position_to_selection =
+                                create_menu_item("Copy Position To Selection", vec![], ctx);
+                            copy_position_to_selection
+                        })
                         .with_child({
                             save_as_prefab = create_menu_item("Save As Prefab...", vec![], ctx);
                             save_as_prefab
@@ -119,6 +127,7 @@ impl ItemContextMenu {
             save_as_prefab_dialog,
             replace_with_menu,
             paste,
+            copy_position_to_selection,
         }
     }
 
@@ -175,6 +184,33 @@ impl ItemContextMenu {
                         }
                     }
                 }
+            } else if message.destination() == self.copy_position_to_selection {
+                if let Selection::Graph(graph_selection) = &editor_scene.selection {
+                    if let Some(source) = engine
+                        .user_interface
+                        .try_get_node(self.placement_target)
+                        .and_then(|n| n.query_component::<SceneItem<Node>>())
+                        .map(|item| item.entity_handle)
+                    {
+                        let commands = graph_selection
+                            .nodes
+                            .iter()
+                            .filter(|&&node| node != source)
+                            .map(|&node| {
+                                SceneCommand::new(CopyFieldCommand::new(
+                                    source,
+                                    node,
+                                    LOCAL_POSITION_PATH.to_string(),
+                                ))
+                            })
+                            .collect::<Vec<_>>();
+                        if !commands.is_empty() {
+                            sender
+                                .send(Message::do_scene_command(CommandGroup::from(commands)))
+                                .unwrap();
+                        }
+                    }
+                }
             } else if message.destination() == self.preview_camera {
                 let new_preview_camera = engine
                     .user_interface
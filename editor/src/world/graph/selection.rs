@@ -12,7 +12,7 @@ pub struct GraphSelection {
 
 impl PartialEq for GraphSelection {
     fn eq(&self, other: &Self) -> bool {
-        utils::is_slice_equal_permutation(self.nodes(), other.nodes())
+        utils::is_slice_equal_permutation_hashed(self.nodes(), other.nodes())
     }
 }
 
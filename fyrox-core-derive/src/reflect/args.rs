@@ -23,6 +23,20 @@ pub struct TypeArgs {
     #[darling(default)]
     pub bounds: Option<Vec<WherePredicate>>,
 
+    /// `#[reflect(clone)]`
+    ///
+    /// Implements `Reflect::try_clone_box` using `Clone` impl of the type.
+    #[darling(default)]
+    pub clone: bool,
+
+    /// `#[reflect(clone_with = "<function path>")]`
+    ///
+    /// Implements `Reflect::try_clone_box` with the given function, it is useful for generic types
+    /// that are not always `Clone`.
+    /// Expected signature: `fn(&Self) -> Option<Box<dyn Reflect>>`
+    #[darling(default)]
+    pub clone_with: Option<Path>,
+
     #[darling(default, rename = "ReflectArray")]
     pub impl_as_array: bool,
 
@@ -90,6 +104,26 @@ impl TypeArgs {
         }
     }
 
+    pub fn try_clone_box_impl(&self) -> TokenStream2 {
+        if let Some(clone_with) = &self.clone_with {
+            return quote! {
+                fn try_clone_box(&self) -> Option<Box<dyn Reflect>> {
+                    #clone_with(self)
+                }
+            };
+        }
+
+        if !self.clone {
+            return quote!();
+        }
+
+        quote! {
+            fn try_clone_box(&self) -> Option<Box<dyn Reflect>> {
+                Some(Box::new(self.clone()))
+            }
+        }
+    }
+
     pub fn as_array_impl(&self) -> TokenStream2 {
         if !self.impl_as_array {
             return quote!();
@@ -176,6 +210,18 @@ pub struct FieldArgs {
     #[darling(default)]
     pub max_value: Option<f64>,
 
+    /// `#[reflect(min = "0.0")]`
+    ///
+    /// Shorthand for `min_value`.
+    #[darling(default)]
+    pub min: Option<f64>,
+
+    /// `#[reflect(max = "1.0")]`
+    ///
+    /// Shorthand for `max_value`.
+    #[darling(default)]
+    pub max: Option<f64>,
+
     /// `#[reflect(step = "0.1")]`
     ///
     /// Increment/decrement step of the field. Works only for numeric fields!
@@ -213,6 +259,24 @@ impl FieldArgs {
             self.field = Some(parse_quote!(deref()));
             self.field_mut = Some(parse_quote!(deref_mut()));
         }
+
+        assert!(
+            self.min.is_none() || self.min_value.is_none(),
+            "can't use both `min` and `min_value`"
+        );
+
+        assert!(
+            self.max.is_none() || self.max_value.is_none(),
+            "can't use both `max` and `max_value`"
+        );
+
+        if let Some(min) = self.min.take() {
+            self.min_value = Some(min);
+        }
+
+        if let Some(max) = self.max.take() {
+            self.max_value = Some(max);
+        }
     }
 }
 
@@ -6,8 +6,10 @@ use std::any::TypeId;
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
+use fyrox_core::algebra::Vector3;
 use fyrox_core::parking_lot::Mutex;
 use fyrox_core::reflect::*;
+use fyrox_core::variable::InheritableVariable;
 
 #[allow(dead_code)]
 #[derive(Reflect, Debug, Clone)]
@@ -163,6 +165,40 @@ fn reflect_path() {
     hie.get_resolve_path_mut::<usize>("e.Tuple@0", &mut |result| assert_eq!(result, Ok(&mut 10)));
 }
 
+#[test]
+fn resolve_tuple_path() {
+    #[derive(Reflect, Debug)]
+    pub struct Positional {
+        tuple_struct: Tuple,
+        tuple: (usize, f32),
+    }
+
+    let mut positional = Positional {
+        tuple_struct: Tuple(1, 2),
+        tuple: (3, 4.0),
+    };
+
+    positional
+        .get_resolve_path::<usize>("tuple_struct.0", &mut |result| assert_eq!(result, Ok(&1)));
+    positional
+        .get_resolve_path::<usize>("tuple_struct.1", &mut |result| assert_eq!(result, Ok(&2)));
+    positional.get_resolve_path::<usize>("tuple.0", &mut |result| assert_eq!(result, Ok(&3)));
+    positional.get_resolve_path::<f32>("tuple.1", &mut |result| assert_eq!(result, Ok(&4.0)));
+    positional.resolve_path("tuple.2", &mut |result| assert!(result.is_err()));
+
+    positional.get_resolve_path_mut::<usize>("tuple_struct.0", &mut |result| *result.unwrap() = 10);
+    positional.get_resolve_path_mut::<f32>("tuple.1", &mut |result| *result.unwrap() = 5.0);
+    assert_eq!(positional.tuple_struct.0, 10);
+    assert_eq!(positional.tuple.1, 5.0);
+
+    // Elements of tuples are visible as fields as well.
+    positional.tuple.fields_info(&mut |fields_info| {
+        assert_eq!(fields_info.len(), 2);
+        assert_eq!(fields_info[0].name, "0");
+        assert_eq!(fields_info[1].name, "1");
+    });
+}
+
 #[test]
 fn reflect_list() {
     let mut data = vec![10usize, 11usize];
@@ -393,6 +429,29 @@ fn inspect_attributes() {
     data.fields_info(&mut |fields_info| assert_eq!(fields_info[0..2], expected));
 }
 
+#[test]
+fn inspect_range_attributes() {
+    #[derive(Debug, Default, Reflect)]
+    pub struct Data {
+        #[reflect(min = -1.0, max = 1.0, step = 0.05)]
+        x: f32,
+        #[reflect(min = 0.0)]
+        y: u32,
+    }
+
+    let data = Data::default();
+
+    data.fields_info(&mut |fields_info| {
+        assert_eq!(fields_info[0].min_value, Some(-1.0));
+        assert_eq!(fields_info[0].max_value, Some(1.0));
+        assert_eq!(fields_info[0].step, Some(0.05));
+
+        assert_eq!(fields_info[1].min_value, Some(0.0));
+        assert_eq!(fields_info[1].max_value, None);
+        assert_eq!(fields_info[1].step, None);
+    });
+}
+
 #[test]
 fn inspect_struct() {
     #[derive(Debug, Default, Reflect)]
@@ -639,3 +698,519 @@ fn test_hash_map() {
         assert_eq!(*result.unwrap(), 555)
     });
 }
+
+#[test]
+fn test_hash_map_entry_editing() {
+    #[derive(Reflect, Debug)]
+    struct Abilities {
+        cooldowns: HashMap<String, f32>,
+    }
+
+    let mut abilities = Abilities {
+        cooldowns: Default::default(),
+    };
+    let abilities = &mut abilities as &mut dyn Reflect;
+
+    // Insert.
+    abilities.resolve_path_mut("cooldowns", &mut |result| {
+        result.unwrap().as_hash_map_mut(&mut |result| {
+            let cooldowns = result.unwrap();
+            assert!(cooldowns
+                .reflect_insert(Box::new("fireball".to_string()), Box::new(1.5f32))
+                .is_none());
+            assert_eq!(cooldowns.reflect_len(), 1);
+        })
+    });
+    abilities.get_resolve_path::<f32>("cooldowns[fireball]", &mut |result| {
+        assert_eq!(result, Ok(&1.5))
+    });
+
+    // Edit.
+    abilities.set_field_by_path("cooldowns[fireball]", Box::new(3.0f32), &mut |result| {
+        assert_eq!(result.ok().unwrap().take::<f32>().unwrap(), 1.5)
+    });
+    abilities.get_resolve_path::<f32>("cooldowns[fireball]", &mut |result| {
+        assert_eq!(result, Ok(&3.0))
+    });
+    abilities.set_field_by_path("cooldowns[fireball]", Box::new(1u32), &mut |result| {
+        assert!(matches!(result, Err(SetFieldByPathError::InvalidValue(_))))
+    });
+    abilities.set_field_by_path("cooldowns[frostbolt]", Box::new(1.0f32), &mut |result| {
+        assert!(matches!(
+            result,
+            Err(SetFieldByPathError::InvalidPath { .. })
+        ))
+    });
+
+    // Remove.
+    abilities.resolve_path_mut("cooldowns", &mut |result| {
+        result.unwrap().as_hash_map_mut(&mut |result| {
+            let cooldowns = result.unwrap();
+            cooldowns.reflect_remove(&"fireball".to_string(), &mut |removed| {
+                assert_eq!(removed.unwrap().take::<f32>().unwrap(), 3.0)
+            });
+            assert_eq!(cooldowns.reflect_len(), 0);
+        })
+    });
+    abilities.get_resolve_path::<f32>(
+        "cooldowns[fireball]",
+        &mut |result| assert!(result.is_err()),
+    );
+
+    // Items of lists are set the same way.
+    let mut items = vec![1u32, 2, 3];
+    (&mut items as &mut dyn Reflect).set_field_by_path("[1]", Box::new(5u32), &mut |result| {
+        assert_eq!(result.ok().unwrap().take::<u32>().unwrap(), 2)
+    });
+    assert_eq!(items, [1, 5, 3]);
+}
+
+#[test]
+fn test_resolve_path_through_option() {
+    #[derive(Reflect, Debug)]
+    struct Weapon {
+        damage: f32,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct Character {
+        weapon: Option<Weapon>,
+    }
+
+    let mut character = Character {
+        weapon: Some(Weapon { damage: 10.0 }),
+    };
+
+    character.get_resolve_path::<f32>("weapon.damage", &mut |result| assert_eq!(result, Ok(&10.0)));
+    // Explicit variant field still works.
+    character.get_resolve_path::<f32>("weapon.Some@0.damage", &mut |result| {
+        assert_eq!(result, Ok(&10.0))
+    });
+    character.get_resolve_path_mut::<f32>("weapon.damage", &mut |result| {
+        *result.unwrap() = 20.0;
+    });
+    assert_eq!(character.weapon.as_ref().unwrap().damage, 20.0);
+    character.get_resolve_path::<f32>("weapon.speed", &mut |result| {
+        assert!(matches!(
+            result,
+            Err(ReflectPathError::UnknownField { s: "speed" })
+        ))
+    });
+
+    character.weapon = None;
+
+    character.get_resolve_path::<f32>("weapon.damage", &mut |result| {
+        assert!(matches!(
+            result,
+            Err(ReflectPathError::UnknownField { s: "damage" })
+        ))
+    });
+    character.get_resolve_path_mut::<f32>("weapon.damage", &mut |result| assert!(result.is_err()));
+}
+
+#[test]
+fn test_set_field_by_path_through_option() {
+    #[derive(Reflect, Debug)]
+    struct Weapon {
+        damage: f32,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct Character {
+        weapon: Option<Weapon>,
+    }
+
+    let mut character = Character {
+        weapon: Some(Weapon { damage: 10.0 }),
+    };
+
+    (&mut character as &mut dyn Reflect).set_field_by_path(
+        "weapon.damage",
+        Box::new(20.0f32),
+        &mut |result| assert_eq!(result.ok().unwrap().take::<f32>().unwrap(), 10.0),
+    );
+    assert_eq!(character.weapon.as_ref().unwrap().damage, 20.0);
+
+    character.weapon = None;
+
+    (&mut character as &mut dyn Reflect).set_field_by_path(
+        "weapon.damage",
+        Box::new(30.0f32),
+        &mut |result| assert!(matches!(result, Err(SetFieldByPathError::InvalidValue(_)))),
+    );
+    assert!(character.weapon.is_none());
+}
+
+#[test]
+fn test_default_registry() {
+    use fyrox_core::reflect::default_registry::DefaultRegistry;
+
+    #[derive(Reflect, Debug, Default, PartialEq)]
+    struct Foo {
+        bar: usize,
+        baz: String,
+    }
+
+    let registry = DefaultRegistry::default();
+    assert!(registry.try_create(&TypeId::of::<Foo>()).is_none());
+
+    registry.add::<Foo>();
+    assert!(registry.contains(&TypeId::of::<Foo>()));
+
+    let value = registry.try_create(&TypeId::of::<Foo>()).unwrap();
+    assert_eq!(value.take::<Foo>().unwrap(), Foo::default());
+
+    registry.remove(TypeId::of::<Foo>());
+    assert!(registry.is_empty());
+
+    // Built-in types are available out-of-the-box.
+    let registry = DefaultRegistry::new();
+    let value = registry.try_create(&TypeId::of::<f32>()).unwrap();
+    assert_eq!(value.take::<f32>().unwrap(), 0.0);
+}
+
+#[test]
+fn test_reflect_apply_patch() {
+    #[derive(Reflect, Debug, Default)]
+    struct Inner {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Reflect, Debug, Default)]
+    struct Target {
+        a: usize,
+        b: String,
+        c: u32,
+        inner: Inner,
+    }
+
+    let mut target = Target {
+        a: 1,
+        b: "foo".to_string(),
+        c: 2,
+        inner: Inner { x: 3.0, y: 4.0 },
+    };
+
+    let mut patch = HashMap::new();
+    patch.insert(
+        "b".to_string(),
+        Box::new("bar".to_string()) as Box<dyn Reflect>,
+    );
+    patch.insert("inner.y".to_string(), Box::new(5.0f32) as Box<dyn Reflect>);
+
+    let report = reflect_apply_patch(&mut target, patch);
+    assert!(report.is_fully_applied());
+    assert_eq!(report.old_values.len(), 2);
+
+    // Only patched fields are changed.
+    assert_eq!(target.a, 1);
+    assert_eq!(target.b, "bar");
+    assert_eq!(target.c, 2);
+    assert_eq!(target.inner.x, 3.0);
+    assert_eq!(target.inner.y, 5.0);
+
+    // Invalid paths and type mismatches are rejected.
+    let mut patch = HashMap::new();
+    patch.insert("d".to_string(), Box::new(1usize) as Box<dyn Reflect>);
+    patch.insert("a".to_string(), Box::new(1.0f32) as Box<dyn Reflect>);
+
+    let report = reflect_apply_patch(&mut target, patch);
+    assert!(!report.is_fully_applied());
+    assert_eq!(report.rejected.len(), 2);
+    assert_eq!(target.a, 1);
+}
+
+#[test]
+fn test_reflect_clone_value() {
+    #[derive(Reflect, Clone, Debug, PartialEq)]
+    #[reflect(clone)]
+    struct Inner {
+        value: f32,
+    }
+
+    #[derive(Reflect, Clone, Debug, PartialEq)]
+    #[reflect(clone)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct NotCloneable {
+        value: f32,
+    }
+
+    // Scalar.
+    let scalar = 123u32;
+    let cloned = reflect_clone_value(&scalar).unwrap();
+    assert_eq!(cloned.take::<u32>().unwrap(), 123);
+
+    // Nested struct.
+    let outer = Outer {
+        name: "Foo".to_string(),
+        inner: Inner { value: 1.5 },
+    };
+    let cloned = reflect_clone_value(&outer).unwrap();
+    assert_eq!(cloned.take::<Outer>().unwrap(), outer);
+
+    // Nested field fetched by path.
+    outer.resolve_path("inner", &mut |result| {
+        let cloned = reflect_clone_value(result.unwrap()).unwrap();
+        assert_eq!(cloned.take::<Inner>().unwrap(), Inner { value: 1.5 });
+    });
+
+    assert!(reflect_clone_value(&NotCloneable { value: 1.0 }).is_none());
+
+    // Containers.
+    let items = vec![Some(1u32), None];
+    let cloned = reflect_clone_value(&items).unwrap();
+    assert_eq!(cloned.take::<Vec<Option<u32>>>().unwrap(), items);
+
+    let items = vec![NotCloneable { value: 1.0 }];
+    assert!(reflect_clone_value(&items).is_none());
+    assert!(reflect_clone_value(&Some(NotCloneable { value: 1.0 })).is_none());
+}
+
+#[test]
+fn test_reflect_clone_inheritable_field() {
+    #[derive(Reflect, Debug)]
+    struct Node {
+        position: InheritableVariable<Vector3<f32>>,
+    }
+
+    let node = Node {
+        position: InheritableVariable::new(Vector3::new(1.0, 2.0, 3.0)),
+    };
+
+    node.resolve_path("position", &mut |result| {
+        let cloned = reflect_clone_value(result.unwrap()).unwrap();
+        assert_eq!(
+            *cloned.take::<InheritableVariable<Vector3<f32>>>().unwrap(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+    });
+
+    // Copy of a variable could be put back.
+    let mut other = Node {
+        position: InheritableVariable::new(Vector3::default()),
+    };
+    let cloned = reflect_clone_value(&node.position).unwrap();
+    (&mut other as &mut dyn Reflect)
+        .set_field_by_path("position", cloned, &mut |result| assert!(result.is_ok()));
+    assert_eq!(*other.position, Vector3::new(1.0, 2.0, 3.0));
+    assert!(other.position.is_modified());
+
+    let items = vec![
+        InheritableVariable::new_modified(1u32),
+        InheritableVariable::new(2u32),
+    ];
+    let cloned = reflect_clone_value(&items)
+        .unwrap()
+        .take::<Vec<InheritableVariable<u32>>>()
+        .unwrap();
+    assert_eq!(cloned, items);
+    assert!(cloned[0].is_modified());
+    assert!(!cloned[1].is_modified());
+}
+
+#[test]
+fn test_reflect_validate() {
+    #[derive(Reflect, Debug)]
+    struct Inner {
+        gain: f32,
+        values: Vec<f64>,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+    }
+
+    let mut outer = Outer {
+        name: "Foo".to_string(),
+        inner: Inner {
+            gain: 1.0,
+            values: vec![0.0, 1.0],
+        },
+    };
+    assert!(reflect_validate(&outer).is_empty());
+
+    outer.inner.gain = f32::NAN;
+    outer.inner.values[1] = f64::INFINITY;
+    let errors = reflect_validate(&outer);
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("inner.gain"));
+    assert!(errors[1].starts_with("inner.values[1]"));
+}
+
+#[test]
+fn test_reflect_validate_map_paths() {
+    #[derive(Reflect, Debug)]
+    struct Target {
+        gains: HashMap<String, f32>,
+    }
+
+    let mut target = Target {
+        gains: HashMap::new(),
+    };
+    for i in 0..8 {
+        target.gains.insert(format!("Gain{}", i), 1.0);
+    }
+    target.gains.insert("Broken".to_string(), f32::NAN);
+
+    let errors = reflect_validate(&target);
+    assert_eq!(errors.len(), 1);
+    let path = errors[0].split(':').next().unwrap();
+    assert_eq!(path, "gains[Broken]");
+
+    // The reported path must point to the invalid value.
+    target.get_resolve_path::<f32>(path, &mut |result| assert!(result.unwrap().is_nan()));
+}
+
+#[test]
+fn test_reflect_snapshot_diff() {
+    #[derive(Reflect, Debug)]
+    struct Inner {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct Target {
+        name: String,
+        inner: Inner,
+        items: Vec<u32>,
+    }
+
+    let mut target = Target {
+        name: "Foo".to_string(),
+        inner: Inner { x: 1.0, y: 2.0 },
+        items: vec![1, 2, 3],
+    };
+
+    let mut snapshot = ReflectSnapshot::capture(&target);
+    assert_eq!(snapshot.len(), 6);
+
+    // Nothing changed.
+    assert!(reflect_snapshot_diff(&mut snapshot, &target).is_empty());
+
+    target.inner.y = 3.0;
+
+    let mut diff = reflect_snapshot_diff(&mut snapshot, &target);
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff.remove("inner.y").unwrap().take::<f32>().unwrap(), 3.0);
+
+    // The snapshot must be updated after diff.
+    assert!(reflect_snapshot_diff(&mut snapshot, &target).is_empty());
+
+    // The diff is compatible with patches.
+    let mut other = Target {
+        name: "Foo".to_string(),
+        inner: Inner { x: 1.0, y: 2.0 },
+        items: vec![1, 2, 3],
+    };
+    target.name = "Bar".to_string();
+    let report = reflect_apply_patch(&mut other, reflect_snapshot_diff(&mut snapshot, &target));
+    assert!(report.is_fully_applied());
+    assert_eq!(other.name, "Bar");
+}
+
+#[test]
+fn test_reflect_snapshot_diff_map() {
+    #[derive(Reflect, Debug)]
+    struct Target {
+        cooldowns: HashMap<String, f32>,
+    }
+
+    let make_target = || {
+        let mut cooldowns = HashMap::new();
+        for i in 0..16 {
+            cooldowns.insert(format!("Skill{}", i), i as f32);
+        }
+        Target { cooldowns }
+    };
+
+    let mut target = make_target();
+    let mut snapshot = ReflectSnapshot::capture(&target);
+    assert_eq!(snapshot.len(), 16);
+
+    // Unchanged map must not produce a diff, even for a different instance with other iteration
+    // order.
+    assert!(reflect_snapshot_diff(&mut snapshot, &target).is_empty());
+    assert!(reflect_snapshot_diff(&mut snapshot, &make_target()).is_empty());
+
+    target.cooldowns.insert("Skill3".to_string(), 10.0);
+    let diff = reflect_snapshot_diff(&mut snapshot, &target);
+    assert_eq!(diff.len(), 1);
+    assert!(diff.contains_key("cooldowns[Skill3]"));
+
+    // Paths of the diff can be applied back.
+    let mut other = make_target();
+    let report = reflect_apply_patch(&mut other, diff);
+    assert!(report.is_fully_applied());
+    assert_eq!(other.cooldowns["Skill3"], 10.0);
+}
+
+#[test]
+fn test_reflect_schema() {
+    #[derive(Reflect, Debug)]
+    struct Inner {
+        gain: f32,
+    }
+
+    #[derive(Reflect, Debug)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+        items: Vec<Inner>,
+        empty: Vec<u32>,
+    }
+
+    let outer = Outer {
+        name: "Foo".to_string(),
+        inner: Inner { gain: 1.0 },
+        items: vec![Inner { gain: 2.0 }],
+        empty: vec![],
+    };
+
+    let schema = reflect_schema(&outer);
+    assert_eq!(schema.name, "");
+    assert_eq!(schema.kind, SchemaKind::Struct);
+
+    let fields = schema
+        .children
+        .iter()
+        .map(|field| (field.name.as_str(), field.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [
+            ("name", SchemaKind::Scalar),
+            ("inner", SchemaKind::Struct),
+            ("items", SchemaKind::Collection),
+            ("empty", SchemaKind::Collection),
+        ]
+    );
+
+    let inner = &schema.children[1];
+    assert_eq!(inner.children.len(), 1);
+    assert_eq!(inner.children[0].name, "gain");
+    assert_eq!(inner.children[0].type_name, "f32");
+    assert_eq!(inner.children[0].kind, SchemaKind::Scalar);
+
+    // Layout of items is taken from the first item.
+    let items = &schema.children[2];
+    assert_eq!(items.children.len(), 1);
+    assert_eq!(items.children[0].name, "[]");
+    assert_eq!(
+        items.children[0],
+        SchemaNode {
+            name: "[]".to_string(),
+            ..inner.clone()
+        }
+    );
+
+    assert!(schema.children[3].children.is_empty());
+}
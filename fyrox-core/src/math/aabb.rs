@@ -1,10 +1,11 @@
 use crate::{
     algebra::{Matrix4, Vector3},
     math::Matrix4Ext,
+    reflect::prelude::*,
     visitor::{Visit, VisitResult, Visitor},
 };
 
-#[derive(Copy, Clone, Debug, Visit)]
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
 pub struct AxisAlignedBoundingBox {
     pub min: Vector3<f32>,
     pub max: Vector3<f32>,
@@ -1,12 +1,16 @@
 //! Runtime reflection
 
+pub mod default_registry;
 mod external_impls;
 mod std_impls;
 
+use crate::reflect::default_registry::DefaultRegistry;
 pub use fyrox_core_derive::Reflect;
 use std::{
     any::{Any, TypeId},
+    collections::{hash_map::DefaultHasher, HashMap},
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     mem::ManuallyDrop,
 };
 
@@ -170,6 +174,15 @@ pub trait Reflect: Any + Debug {
 
     fn set(&mut self, value: Box<dyn Reflect>) -> Result<Box<dyn Reflect>, Box<dyn Reflect>>;
 
+    /// Tries to create a boxed copy of the value. Returns `None` if the type does not support
+    /// reflective cloning. Built-in types (numbers, strings, vectors, etc.) and containers of
+    /// cloneable values support it out of the box, derived types should be marked with
+    /// `#[reflect(clone)]` attribute (requires the type to be [`Clone`]) or with
+    /// `#[reflect(clone_with = "<function path>")]` attribute.
+    fn try_clone_box(&self) -> Option<Box<dyn Reflect>> {
+        None
+    }
+
     /// Calls user method specified with `#[reflect(setter = ..)]` or falls back to
     /// [`Reflect::field_mut`]
     #[allow(clippy::type_complexity)]
@@ -269,8 +282,15 @@ pub trait ReflectList: ReflectArray {
         index: usize,
         value: Box<dyn Reflect>,
     ) -> Result<(), Box<dyn Reflect>>;
+    /// Returns type id of the items of the list (if known). It is used to create new items with
+    /// default values when the list is resized using `reflect_resize`.
+    fn reflect_item_type_id(&self) -> Option<TypeId> {
+        None
+    }
 }
 
+/// [`Reflect`] sub trait for working with `HashMap`-like types. Values of such types could be accessed
+/// by keys using paths like `map[key]`, see [`ResolvePath`].
 pub trait ReflectHashMap: Reflect {
     fn reflect_insert(
         &mut self,
@@ -529,6 +549,9 @@ fn try_fetch_by_str_path_mut(
     }
 }
 
+/// Name of the field of `Option::Some` variant.
+const OPTION_SOME_FIELD: &str = "Some@0";
+
 /// Simple path parser / reflect path component
 pub enum Component<'p> {
     Field(&'p str),
@@ -577,8 +600,14 @@ impl<'p> Component<'p> {
         func: &mut dyn FnMut(Result<&dyn Reflect, ReflectPathError<'p>>),
     ) {
         match self {
-            Self::Field(path) => reflect.field(path, &mut |field| {
-                func(field.ok_or(ReflectPathError::UnknownField { s: path }))
+            Self::Field(path) => reflect.field(path, &mut |field| match field {
+                Some(field) => func(Ok(field)),
+                // Transparently descend into `Some(x)`, so fields of optional values can be
+                // accessed as if they were not wrapped in an `Option`.
+                None => reflect.field(OPTION_SOME_FIELD, &mut |inner| match inner {
+                    Some(inner) => self.resolve(inner, func),
+                    None => func(Err(ReflectPathError::UnknownField { s: path })),
+                }),
             }),
             Self::Index(path) => {
                 reflect.as_array(&mut |result| match result {
@@ -608,9 +637,21 @@ impl<'p> Component<'p> {
         func: &mut dyn FnMut(Result<&mut dyn Reflect, ReflectPathError<'p>>),
     ) {
         match self {
-            Self::Field(path) => reflect.field_mut(path, &mut |field| {
-                func(field.ok_or(ReflectPathError::UnknownField { s: path }))
-            }),
+            Self::Field(path) => {
+                let mut succeeded = true;
+                reflect.field_mut(path, &mut |field| match field {
+                    Some(field) => func(Ok(field)),
+                    None => succeeded = false,
+                });
+
+                if !succeeded {
+                    // See `resolve`.
+                    reflect.field_mut(OPTION_SOME_FIELD, &mut |inner| match inner {
+                        Some(inner) => self.resolve_mut(inner, func),
+                        None => func(Err(ReflectPathError::UnknownField { s: path })),
+                    })
+                }
+            }
             Self::Index(path) => {
                 let mut succeeded = true;
                 reflect.as_array_mut(&mut |array| match array {
@@ -693,7 +734,11 @@ pub enum SetFieldByPathError<'p> {
 impl dyn Reflect {
     pub fn downcast<T: Reflect>(self: Box<dyn Reflect>) -> Result<Box<T>, Box<dyn Reflect>> {
         if self.is::<T>() {
-            Ok(self.into_any().downcast().unwrap())
+            // `into_any` can't be used here, because transparent wrappers (such as
+            // `InheritableVariable`) forward it to their inner values.
+            let raw: *mut dyn Reflect = Box::into_raw(self);
+            // SAFETY: The type id of the value is checked above.
+            Ok(unsafe { Box::from_raw(raw as *mut T) })
         } else {
             Err(self)
         }
@@ -719,7 +764,8 @@ impl dyn Reflect {
     }
 
     /// Sets a field by its path in the given entity. This method always uses [`Reflect::set_field`] which means,
-    /// that it will always call custom property setters.
+    /// that it will always call custom property setters. Items of collections (`items[1]`, `map[key]`) have no
+    /// setters, such values are set directly using [`Reflect::set`].
     #[inline]
     pub fn set_field_by_path<'p>(
         &mut self,
@@ -727,7 +773,21 @@ impl dyn Reflect {
         value: Box<dyn Reflect>,
         func: &mut dyn FnMut(Result<Box<dyn Reflect>, SetFieldByPathError<'p>>),
     ) {
-        if let Some(separator_position) = path.rfind('.') {
+        if path.ends_with(']') {
+            let mut opt_value = Some(value);
+            self.resolve_path_mut(path, &mut |result| match result {
+                Err(reason) => {
+                    func(Err(SetFieldByPathError::InvalidPath {
+                        reason,
+                        value: opt_value.take().unwrap(),
+                    }));
+                }
+                Ok(item) => match item.set(opt_value.take().unwrap()) {
+                    Ok(value) => func(Ok(value)),
+                    Err(e) => func(Err(SetFieldByPathError::InvalidValue(e))),
+                },
+            });
+        } else if let Some(separator_position) = path.rfind('.') {
             let mut opt_value = Some(value);
             let parent_path = &path[..separator_position];
             let field = &path[(separator_position + 1)..];
@@ -738,16 +798,18 @@ impl dyn Reflect {
                         value: opt_value.take().unwrap(),
                     }));
                 }
-                Ok(property) => {
-                    property.set_field(field, opt_value.take().unwrap(), &mut |result| match result
-                    {
+                Ok(property) => set_field_through_option(
+                    property,
+                    field,
+                    opt_value.take().unwrap(),
+                    &mut |result| match result {
                         Ok(value) => func(Ok(value)),
                         Err(e) => func(Err(SetFieldByPathError::InvalidValue(e))),
-                    })
-                }
+                    },
+                ),
             });
         } else {
-            self.set_field(path, value, &mut |result| match result {
+            set_field_through_option(self, path, value, &mut |result| match result {
                 Ok(value) => func(Ok(value)),
                 Err(e) => func(Err(SetFieldByPathError::InvalidValue(e))),
             });
@@ -755,6 +817,338 @@ impl dyn Reflect {
     }
 }
 
+/// Sets a field using [`Reflect::set_field`], if there's no such field, then tries to set it on
+/// the `Some(x)` value (if any). This mirrors the behaviour of path resolution, which transparently
+/// descends into optional values.
+fn set_field_through_option(
+    reflect: &mut dyn Reflect,
+    field: &str,
+    value: Box<dyn Reflect>,
+    func: &mut dyn FnMut(Result<Box<dyn Reflect>, Box<dyn Reflect>>),
+) {
+    let mut rejected = None;
+    reflect.set_field(field, value, &mut |result| match result {
+        Ok(old_value) => func(Ok(old_value)),
+        Err(value) => rejected = Some(value),
+    });
+
+    if let Some(value) = rejected {
+        let mut value = Some(value);
+        reflect.field_mut(OPTION_SOME_FIELD, &mut |inner| match inner {
+            Some(inner) => set_field_through_option(inner, field, value.take().unwrap(), func),
+            None => func(Err(value.take().unwrap())),
+        });
+    }
+}
+
+/// A result of [`reflect_apply_patch`].
+#[derive(Default)]
+pub struct PatchReport {
+    /// Previous values of the fields that were successfully changed. Could be used to revert the patch.
+    pub old_values: HashMap<String, Box<dyn Reflect>>,
+    /// Values that could not be applied (invalid path or type mismatch) along with their paths.
+    pub rejected: HashMap<String, Box<dyn Reflect>>,
+}
+
+impl PatchReport {
+    /// Returns `true` if every value of the patch was applied.
+    pub fn is_fully_applied(&self) -> bool {
+        self.rejected.is_empty()
+    }
+}
+
+/// Applies a partial update to the target. The patch is a set of `path -> value` pairs, only the fields
+/// at specified paths will be changed, every other field will be left untouched. Each value is set using
+/// [`Reflect::set_field`] which means that custom property setters will be called.
+pub fn reflect_apply_patch(
+    target: &mut dyn Reflect,
+    patch: HashMap<String, Box<dyn Reflect>>,
+) -> PatchReport {
+    let mut report = PatchReport::default();
+
+    for (path, value) in patch {
+        target.set_field_by_path(&path, value, &mut |result| match result {
+            Ok(old_value) => {
+                report.old_values.insert(path.clone(), old_value);
+            }
+            Err(SetFieldByPathError::InvalidPath { value, .. })
+            | Err(SetFieldByPathError::InvalidValue(value)) => {
+                report.rejected.insert(path.clone(), value);
+            }
+        });
+    }
+
+    report
+}
+
+/// Tries to make a boxed copy of the given value using reflection, it could be used to capture the
+/// value of a field before changing it (for example to be able to revert the change later). Returns
+/// `None` if the type of the value does not support reflective cloning, see [`Reflect::try_clone_box`]
+/// for more info.
+pub fn reflect_clone_value(value: &dyn Reflect) -> Option<Box<dyn Reflect>> {
+    value.try_clone_box()
+}
+
+/// Walks the given value using reflection and searches for non-finite (NaN or infinite) `f32` and
+/// `f64` values. Returns a list of human-readable descriptions of such values, each description
+/// starts with a path to the value (in the same format as used by [`ResolvePath`]). An empty list
+/// means that the value is valid. Only visible (non-hidden) fields are checked.
+///
+/// It could be used as a debug check to catch invalid values early, before they spread over
+/// the rest of the state (for example a NaN in a transform or a gain).
+pub fn reflect_validate(value: &dyn Reflect) -> Vec<String> {
+    let mut errors = Vec::new();
+    visit_leaves(value, "", &mut |path, value| {
+        let mut non_finite = None;
+        value.as_any(&mut |any| {
+            if let Some(v) = any.downcast_ref::<f32>() {
+                if !v.is_finite() {
+                    non_finite = Some(*v as f64);
+                }
+            } else if let Some(v) = any.downcast_ref::<f64>() {
+                if !v.is_finite() {
+                    non_finite = Some(*v);
+                }
+            }
+        });
+        if let Some(v) = non_finite {
+            errors.push(format!("{}: non-finite value {}", path, v));
+        }
+    });
+    errors
+}
+
+/// A snapshot of a reflected value, that is used to find changes in the value since the moment
+/// when the snapshot was made. The snapshot does not store the values themselves, only hashes of
+/// their debug representation, so it is relatively cheap to store. See [`reflect_snapshot_diff`]
+/// for more info.
+#[derive(Default, Clone, Debug)]
+pub struct ReflectSnapshot {
+    hashes: HashMap<String, u64>,
+}
+
+impl ReflectSnapshot {
+    /// Makes a snapshot of the given value.
+    pub fn capture(value: &dyn Reflect) -> Self {
+        let mut hashes = HashMap::new();
+        visit_leaves(value, "", &mut |path, value| {
+            hashes.insert(path.to_string(), leaf_hash(value));
+        });
+        Self { hashes }
+    }
+
+    /// Returns total amount of values in the snapshot.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if the snapshot is empty.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+fn leaf_hash(value: &dyn Reflect) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", value).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Finds every value that was changed since the moment when the snapshot was made (or last diff)
+/// and updates the snapshot. Returns a set of `path -> value` pairs of changed values, that is
+/// compatible with [`reflect_apply_patch`], so it could be used to store small deltas instead of
+/// full copies of the value (for example for autosave).
+///
+/// # Limitations
+///
+/// Only values that support reflective cloning (see [`Reflect::try_clone_box`]) can be put in
+/// the diff, other changed values are skipped (but the snapshot is still updated). Removed values
+/// (for example when a collection shrinks) are not reported.
+pub fn reflect_snapshot_diff(
+    snapshot: &mut ReflectSnapshot,
+    value: &dyn Reflect,
+) -> HashMap<String, Box<dyn Reflect>> {
+    let mut diff = HashMap::new();
+    let mut hashes = HashMap::with_capacity(snapshot.hashes.len());
+    visit_leaves(value, "", &mut |path, value| {
+        let hash = leaf_hash(value);
+        if snapshot.hashes.get(path) != Some(&hash) {
+            if let Some(value) = value.try_clone_box() {
+                diff.insert(path.to_string(), value);
+            }
+        }
+        hashes.insert(path.to_string(), hash);
+    });
+    snapshot.hashes = hashes;
+    diff
+}
+
+/// Kind of a value in a [`SchemaNode`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// A value without any visible fields or items (numbers, strings, etc.).
+    Scalar,
+    /// A value with a set of named fields.
+    Struct,
+    /// An array, a list or a hash map.
+    Collection,
+}
+
+/// Describes a layout of a reflected value, see [`reflect_schema`] for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaNode {
+    /// Name of the field (or `[]` for items of a collection). Name of the root node is empty.
+    pub name: String,
+    /// Type name of the value.
+    pub type_name: String,
+    /// Kind of the value.
+    pub kind: SchemaKind,
+    /// Fields of a struct or the layout of items of a collection.
+    pub children: Vec<SchemaNode>,
+}
+
+/// Exports the layout of the given value as a tree of field names, type names and kinds. It could be
+/// used by external tools (level editors, validators, etc.) to learn what fields a type has.
+/// Inheritable variables are transparent, the layout of their inner values is exported instead.
+///
+/// # Limitations
+///
+/// Reflection works with instances, so the layout of items of a collection is taken from its first
+/// item, empty collections have no children. Enums export the fields of their current variant only.
+pub fn reflect_schema(value: &dyn Reflect) -> SchemaNode {
+    schema_node(value, String::new())
+}
+
+fn schema_node(value: &dyn Reflect, name: String) -> SchemaNode {
+    let mut inner = None;
+    value.as_inheritable_variable(&mut |variable| {
+        if let Some(variable) = variable {
+            inner = Some(schema_node(variable.inner_value_ref(), name.clone()));
+        }
+    });
+    if let Some(inner) = inner {
+        return inner;
+    }
+
+    let mut node = SchemaNode {
+        name,
+        type_name: value.type_name().to_string(),
+        kind: SchemaKind::Scalar,
+        children: Vec::new(),
+    };
+
+    value.as_array(&mut |array| {
+        if let Some(array) = array {
+            node.kind = SchemaKind::Collection;
+            if let Some(item) = array.reflect_index(0) {
+                node.children.push(schema_node(item, "[]".to_string()));
+            }
+        }
+    });
+    if node.kind == SchemaKind::Collection {
+        return node;
+    }
+
+    value.as_hash_map(&mut |map| {
+        if let Some(map) = map {
+            node.kind = SchemaKind::Collection;
+            if let Some(item) = map.reflect_get_nth_value_ref(0) {
+                node.children.push(schema_node(item, "[]".to_string()));
+            }
+        }
+    });
+    if node.kind == SchemaKind::Collection {
+        return node;
+    }
+
+    value.fields_info(&mut |fields| {
+        if !fields.is_empty() {
+            node.kind = SchemaKind::Struct;
+            node.children = fields
+                .iter()
+                .map(|field| schema_node(field.reflect_value, field.name.to_string()))
+                .collect();
+        }
+    });
+
+    node
+}
+
+// Converts a key of a hash map to an index component of a path. Only string keys could be used to
+// resolve a path, other keys are formatted using their debug representation, it is stable, but the
+// resulting path cannot be resolved.
+fn map_key_path(key: &dyn Reflect) -> String {
+    let mut path = None;
+    key.downcast_ref::<String>(&mut |string| path = string.cloned());
+    if path.is_none() {
+        key.downcast_ref::<ImmutableString>(&mut |string| {
+            path = string.map(|string| string.to_string())
+        });
+    }
+    path.unwrap_or_else(|| format!("{:?}", key))
+}
+
+// Calls the given function for every "leaf" value (a value without any visible fields, items, etc.)
+// in the given value along with its path.
+fn visit_leaves(value: &dyn Reflect, path: &str, func: &mut dyn FnMut(&str, &dyn Reflect)) {
+    let mut handled = false;
+
+    value.as_inheritable_variable(&mut |variable| {
+        if let Some(variable) = variable {
+            visit_leaves(variable.inner_value_ref(), path, func);
+            handled = true;
+        }
+    });
+    if handled {
+        return;
+    }
+
+    value.as_array(&mut |array| {
+        if let Some(array) = array {
+            for i in 0..array.reflect_len() {
+                if let Some(item) = array.reflect_index(i) {
+                    visit_leaves(item, &format!("{}[{}]", path, i), func);
+                }
+            }
+            handled = true;
+        }
+    });
+    if handled {
+        return;
+    }
+
+    value.as_hash_map(&mut |map| {
+        if let Some(map) = map {
+            // Entries are addressed by their keys (not by iteration order, which is not stable),
+            // the same way as `ResolvePath` does.
+            for i in 0..map.reflect_len() {
+                if let Some((key, item)) = map.reflect_get_at(i) {
+                    visit_leaves(item, &format!("{}[{}]", path, map_key_path(key)), func);
+                }
+            }
+            handled = true;
+        }
+    });
+    if handled {
+        return;
+    }
+
+    value.fields_info(&mut |fields| {
+        for field in fields.iter() {
+            let field_path = if path.is_empty() {
+                field.name.to_string()
+            } else {
+                format!("{}.{}", path, field.name)
+            };
+            visit_leaves(field.reflect_value, &field_path, func);
+        }
+        handled = !fields.is_empty();
+    });
+    if !handled {
+        func(path, value);
+    }
+}
+
 // Make it a trait?
 impl dyn ReflectList {
     pub fn get_reflect_index<T: Reflect + 'static>(
@@ -780,6 +1174,46 @@ impl dyn ReflectList {
             func(None)
         }
     }
+
+    /// Resizes the list to the given length. If the list grows, new items are created using default
+    /// value constructors from the given registry. If the list shrinks, it is truncated and the
+    /// removed items are returned (in their original order), so they could be put back later (for
+    /// example on undo). Returns `None` and leaves the list untouched if the list must grow, but
+    /// the type of its items is unknown or there's no default value constructor for it.
+    pub fn reflect_resize(
+        &mut self,
+        new_len: usize,
+        default_registry: &DefaultRegistry,
+    ) -> Option<Vec<Box<dyn Reflect>>> {
+        let len = self.reflect_len();
+        if new_len > len {
+            let item_type_id = self.reflect_item_type_id()?;
+
+            // Create every item first, so the list stays untouched if any of them can't be created.
+            let items = (len..new_len)
+                .map(|_| default_registry.try_create(&item_type_id))
+                .collect::<Option<Vec<_>>>()?;
+
+            for item in items {
+                if self.reflect_push(item).is_err() {
+                    // Roll back to keep the list in its original state.
+                    while self.reflect_len() > len {
+                        self.reflect_pop();
+                    }
+                    return None;
+                }
+            }
+
+            Some(Vec::new())
+        } else {
+            let mut removed = Vec::with_capacity(len - new_len);
+            while self.reflect_len() > new_len {
+                removed.extend(self.reflect_pop());
+            }
+            removed.reverse();
+            Some(removed)
+        }
+    }
 }
 
 #[macro_export]
@@ -0,0 +1,103 @@
+//! A special container that is able to create default values of reflected types by their type id.
+
+use crate::{
+    algebra::{UnitQuaternion, Vector2, Vector3, Vector4},
+    parking_lot::{Mutex, MutexGuard},
+    reflect::Reflect,
+    sstorage::ImmutableString,
+};
+use fxhash::FxHashMap;
+use std::{any::TypeId, path::PathBuf, time::Duration};
+
+/// A simple type alias for a default value constructor.
+pub type DefaultConstructor = fn() -> Box<dyn Reflect>;
+
+/// A special container that is able to create default values of reflected types by their type id.
+/// It is used in places where a value of an arbitrary type must be created in a type-erased
+/// context (for example when a new item is inserted in a collection from the editor).
+#[derive(Default)]
+pub struct DefaultRegistry {
+    map: Mutex<FxHashMap<TypeId, DefaultConstructor>>,
+}
+
+impl DefaultRegistry {
+    /// Creates default value registry with constructors for built-in types (numbers, strings,
+    /// vectors, etc.).
+    pub fn new() -> Self {
+        let registry = DefaultRegistry::default();
+
+        registry
+            .add::<f32>()
+            .add::<f64>()
+            .add::<usize>()
+            .add::<u8>()
+            .add::<u16>()
+            .add::<u32>()
+            .add::<u64>()
+            .add::<isize>()
+            .add::<i8>()
+            .add::<i16>()
+            .add::<i32>()
+            .add::<i64>()
+            .add::<bool>()
+            .add::<String>()
+            .add::<PathBuf>()
+            .add::<Duration>()
+            .add::<ImmutableString>()
+            .add::<Vector2<f32>>()
+            .add::<Vector3<f32>>()
+            .add::<Vector4<f32>>()
+            .add::<UnitQuaternion<f32>>();
+
+        registry
+    }
+
+    /// Adds new default value constructor for a given type. Previous constructor for the type will
+    /// be replaced (if any).
+    pub fn add<T>(&self) -> &Self
+    where
+        T: Reflect + Default,
+    {
+        self.map
+            .lock()
+            .insert(TypeId::of::<T>(), || Box::new(T::default()));
+
+        self
+    }
+
+    /// Adds custom default value constructor.
+    pub fn add_custom(&self, type_id: TypeId, constructor: DefaultConstructor) {
+        self.map.lock().insert(type_id, constructor);
+    }
+
+    /// Unregisters default value constructor.
+    pub fn remove(&self, type_id: TypeId) {
+        self.map.lock().remove(&type_id);
+    }
+
+    /// Returns `true` if there is a default value constructor for a given type id.
+    pub fn contains(&self, type_id: &TypeId) -> bool {
+        self.map.lock().contains_key(type_id)
+    }
+
+    /// Makes an attempt to create a default value of a type with given type id. It may fail if
+    /// there is no constructor for specified type id.
+    pub fn try_create(&self, type_id: &TypeId) -> Option<Box<dyn Reflect>> {
+        self.map.lock().get(type_id).map(|c| (c)())
+    }
+
+    /// Returns inner map of default value constructors.
+    pub fn map(&self) -> MutexGuard<FxHashMap<TypeId, DefaultConstructor>> {
+        self.map.lock()
+    }
+
+    /// Returns total amount of constructors.
+    pub fn len(&self) -> usize {
+        self.map.lock().len()
+    }
+
+    /// Returns true if the container is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -7,6 +7,7 @@ use std::fmt::Debug;
 use crate::reflect::prelude::*;
 
 impl_reflect! {
+    #[reflect(clone, bounds = "Self: Clone")]
     pub struct Matrix<T: 'static, R: Dim + 'static, C: Dim + 'static, S: 'static> {
         pub data: S,
         // _phantoms: PhantomData<(T, R, C)>,
@@ -18,12 +19,14 @@ impl_reflect! {
 }
 
 impl_reflect! {
+    #[reflect(clone, bounds = "Self: Clone")]
     pub struct Unit<T: Debug + 'static> {
         // pub(crate) value: T,
     }
 }
 
 impl_reflect! {
+    #[reflect(clone, bounds = "Self: Clone")]
     pub struct Quaternion<T: Debug> {
         pub coords: Vector4<T>,
     }
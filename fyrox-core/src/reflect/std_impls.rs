@@ -9,7 +9,7 @@ use crate::{
 use fyrox_core_derive::impl_reflect;
 use parking_lot::Mutex;
 use std::{
-    any::Any,
+    any::{Any, TypeId},
     cell::Cell,
     collections::HashMap,
     fmt::Debug,
@@ -24,6 +24,10 @@ macro_rules! impl_blank_reflect {
         $(
             impl Reflect for $ty {
                 blank_reflect!();
+
+                fn try_clone_box(&self) -> Option<Box<dyn Reflect>> {
+                    Some(Box::new(self.clone()))
+                }
             }
         )*
     }
@@ -40,26 +44,100 @@ impl_blank_reflect! {
     ImmutableString
 }
 
+// Tuples expose their elements as fields named by their positions (`"0"`, `"1"`, ...), the same
+// way as derived tuple structs do, so paths like `pair.0` could be resolved.
 macro_rules! impl_reflect_tuple {
     (
         $(
-            ( $($t:ident,)* );
+            ( $($i:tt: $t:ident,)* );
         )*
     ) => {
         $(
             impl< $($t: Reflect),* > Reflect for ( $($t,)* ) {
-                blank_reflect!();
+                fn type_name(&self) -> &'static str {
+                    std::any::type_name::<Self>()
+                }
+
+                fn fields_info(&self, func: &mut dyn FnMut(Vec<FieldInfo>)) {
+                    func(vec![
+                        $(
+                            FieldInfo {
+                                owner_type_id: TypeId::of::<Self>(),
+                                name: stringify!($i),
+                                display_name: stringify!($i),
+                                description: "",
+                                type_name: std::any::type_name::<$t>(),
+                                value: &self.$i,
+                                reflect_value: &self.$i,
+                                read_only: false,
+                                min_value: None,
+                                max_value: None,
+                                step: None,
+                                precision: None,
+                            },
+                        )*
+                    ])
+                }
+
+                fn into_any(self: Box<Self>) -> Box<dyn Any> {
+                    self
+                }
+
+                fn as_any(&self, func: &mut dyn FnMut(&dyn Any)) {
+                    func(self)
+                }
+
+                fn as_any_mut(&mut self, func: &mut dyn FnMut(&mut dyn Any)) {
+                    func(self)
+                }
+
+                fn as_reflect(&self, func: &mut dyn FnMut(&dyn Reflect)) {
+                    func(self)
+                }
+
+                fn as_reflect_mut(&mut self, func: &mut dyn FnMut(&mut dyn Reflect)) {
+                    func(self)
+                }
+
+                fn set(&mut self, value: Box<dyn Reflect>) -> Result<Box<dyn Reflect>, Box<dyn Reflect>> {
+                    let this = std::mem::replace(self, value.take()?);
+                    Ok(Box::new(this))
+                }
+
+                fn fields(&self, func: &mut dyn FnMut(Vec<&dyn Reflect>)) {
+                    func(vec![$(&self.$i as &dyn Reflect,)*])
+                }
+
+                fn fields_mut(&mut self, func: &mut dyn FnMut(Vec<&mut dyn Reflect>)) {
+                    func(vec![$(&mut self.$i as &mut dyn Reflect,)*])
+                }
+
+                fn field(&self, name: &str, func: &mut dyn FnMut(Option<&dyn Reflect>)) {
+                    match name {
+                        "self" => func(Some(self)),
+                        $(stringify!($i) => func(Some(&self.$i)),)*
+                        _ => func(None),
+                    }
+                }
+
+                fn field_mut(&mut self, name: &str, func: &mut dyn FnMut(Option<&mut dyn Reflect>)) {
+                    match name {
+                        "self" => func(Some(self)),
+                        $(stringify!($i) => func(Some(&mut self.$i)),)*
+                        _ => func(None),
+                    }
+                }
             }
         )*
     }
 }
 
 impl_reflect_tuple! {
-    (T0,);
-    (T0, T1, );
-    (T0, T1, T2, );
-    (T0, T1, T2, T3,);
-    (T0, T1, T2, T3, T4,);
+    (0: T0,);
+    (0: T0, 1: T1,);
+    (0: T0, 1: T1, 2: T2,);
+    (0: T0, 1: T1, 2: T2, 3: T3,);
+    (0: T0, 1: T1, 2: T2, 3: T3, 4: T4,);
 }
 
 impl<const N: usize, T: Reflect> Reflect for [T; N] {
@@ -97,10 +175,20 @@ impl<const N: usize, T: Reflect> ReflectArray for [T; N] {
 }
 
 impl_reflect! {
-    #[reflect(ReflectList, ReflectArray)]
+    #[reflect(ReflectList, ReflectArray, clone_with = "try_clone_vec")]
     pub struct Vec<T: Reflect + 'static>;
 }
 
+// Items are cloned using reflection, so `T` does not have to be `Clone`, but every item must support
+// reflective cloning.
+fn try_clone_vec<T: Reflect>(slice: &[T]) -> Option<Box<dyn Reflect>> {
+    slice
+        .iter()
+        .map(|item| item.try_clone_box()?.take::<T>().ok())
+        .collect::<Option<Vec<T>>>()
+        .map(|vec| Box::new(vec) as Box<dyn Reflect>)
+}
+
 impl<T: Reflect + 'static> ReflectArray for Vec<T> {
     fn reflect_index(&self, index: usize) -> Option<&dyn Reflect> {
         self.get(index).map(|x| x as &dyn Reflect)
@@ -146,6 +234,10 @@ impl<T: Reflect + 'static> ReflectList for Vec<T> {
         self.insert(index, *value.downcast::<T>()?);
         Ok(())
     }
+
+    fn reflect_item_type_id(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
 }
 
 impl<K, V, S> Reflect for HashMap<K, V, S>
@@ -261,12 +353,21 @@ impl_reflect! {
 }
 
 impl_reflect! {
+    #[reflect(clone_with = "try_clone_option")]
     pub enum Option<T> {
         Some(T),
         None
     }
 }
 
+fn try_clone_option<T: Reflect>(option: &Option<T>) -> Option<Box<dyn Reflect>> {
+    let cloned = match option {
+        Some(value) => Some(value.try_clone_box()?.take::<T>().ok()?),
+        None => None,
+    };
+    Some(Box::new(cloned))
+}
+
 impl_reflect! {
     pub struct Range<Idx> {
         pub start: Idx,
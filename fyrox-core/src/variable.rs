@@ -272,9 +272,23 @@ where
         self.value.as_reflect_mut(func)
     }
 
+    fn try_clone_box(&self) -> Option<Box<dyn Reflect>> {
+        Some(Box::new(self.clone()))
+    }
+
     fn set(&mut self, value: Box<dyn Reflect>) -> Result<Box<dyn Reflect>, Box<dyn Reflect>> {
-        self.mark_modified_and_need_sync();
-        self.value.set(value)
+        // Only the inner value is replaced, the flags are preserved. Rejected value must not
+        // break inheritance, so the variable is marked as modified only on success. The value
+        // could also be a whole variable (for example a copy made by `try_clone_box`).
+        let value = match value.downcast::<Self>() {
+            Ok(variable) => Box::new(variable.value),
+            Err(value) => value,
+        };
+        let result = self.value.set(value);
+        if result.is_ok() {
+            self.mark_modified_and_need_sync();
+        }
+        result
     }
 
     fn set_field(
@@ -283,8 +297,14 @@ where
         value: Box<dyn Reflect>,
         func: &mut dyn FnMut(Result<Box<dyn Reflect>, Box<dyn Reflect>>),
     ) {
-        self.mark_modified_and_need_sync();
-        self.value.set_field(field, value, func)
+        let mut is_set = false;
+        self.value.set_field(field, value, &mut |result| {
+            is_set = result.is_ok();
+            func(result)
+        });
+        if is_set {
+            self.mark_modified_and_need_sync();
+        }
     }
 
     fn fields(&self, func: &mut dyn FnMut(Vec<&dyn Reflect>)) {
@@ -529,6 +549,107 @@ pub fn try_inherit_properties(
     result.unwrap_or(Ok(()))
 }
 
+/// Simultaneously walks over fields of given instance and its prefab (parent) and returns paths of the
+/// properties that are overridden in the instance. A property is overridden if it is an
+/// [`InheritableVariable`] that is marked as modified and its value differs from the prefab's value,
+/// such properties won't be inherited from the prefab (see [`try_inherit_properties`]). Paths have
+/// the same format as the paths used by [`crate::reflect::ResolvePath`].
+pub fn reflect_prefab_overrides(instance: &dyn Reflect, prefab: &dyn Reflect) -> Vec<String> {
+    let mut overrides = Vec::new();
+    collect_prefab_overrides(instance, prefab, "", &mut overrides);
+    overrides
+}
+
+fn collect_prefab_overrides(
+    instance: &dyn Reflect,
+    prefab: &dyn Reflect,
+    path: &str,
+    overrides: &mut Vec<String>,
+) {
+    if (*instance).type_id() != (*prefab).type_id() {
+        return;
+    }
+
+    let mut handled = false;
+
+    instance.as_inheritable_variable(&mut |instance_variable| {
+        if let Some(instance_variable) = instance_variable {
+            prefab.as_inheritable_variable(&mut |prefab_variable| {
+                if let Some(prefab_variable) = prefab_variable {
+                    if instance_variable.is_modified()
+                        && !instance_variable.value_equals(prefab_variable)
+                    {
+                        overrides.push(path.to_string());
+                    } else {
+                        // Inner fields may also be InheritableVariable<T>.
+                        collect_prefab_overrides(
+                            instance_variable.inner_value_ref(),
+                            prefab_variable.inner_value_ref(),
+                            path,
+                            overrides,
+                        );
+                    }
+                    handled = true;
+                }
+            })
+        }
+    });
+    if handled {
+        return;
+    }
+
+    instance.as_array(&mut |instance_collection| {
+        if let Some(instance_collection) = instance_collection {
+            prefab.as_array(&mut |prefab_collection| {
+                if let Some(prefab_collection) = prefab_collection {
+                    if instance_collection.reflect_len() == prefab_collection.reflect_len() {
+                        for i in 0..instance_collection.reflect_len() {
+                            if let (Some(instance_item), Some(prefab_item)) = (
+                                instance_collection.reflect_index(i),
+                                prefab_collection.reflect_index(i),
+                            ) {
+                                collect_prefab_overrides(
+                                    instance_item,
+                                    prefab_item,
+                                    &format!("{}[{}]", path, i),
+                                    overrides,
+                                );
+                            }
+                        }
+                    }
+                    handled = true;
+                }
+            })
+        }
+    });
+    if handled {
+        return;
+    }
+
+    instance.fields_info(&mut |instance_fields| {
+        prefab.fields_info(&mut |prefab_fields| {
+            for (instance_field, prefab_field) in instance_fields.iter().zip(prefab_fields.iter()) {
+                // Enums could have different variants.
+                if instance_field.name != prefab_field.name {
+                    break;
+                }
+
+                let field_path = if path.is_empty() {
+                    instance_field.name.to_string()
+                } else {
+                    format!("{}.{}", path, instance_field.name)
+                };
+                collect_prefab_overrides(
+                    instance_field.reflect_value,
+                    prefab_field.reflect_value,
+                    &field_path,
+                    overrides,
+                );
+            }
+        })
+    });
+}
+
 pub fn reset_inheritable_properties(object: &mut dyn Reflect) {
     object.fields_mut(&mut |fields| {
         for field in fields {
@@ -547,7 +668,7 @@ pub fn reset_inheritable_properties(object: &mut dyn Reflect) {
 mod test {
     use crate::{
         reflect::{prelude::*, ReflectInheritableVariable},
-        variable::{try_inherit_properties, InheritableVariable},
+        variable::{reflect_prefab_overrides, try_inherit_properties, InheritableVariable},
     };
 
     #[derive(Reflect, Clone, Debug, PartialEq)]
@@ -594,6 +715,42 @@ mod test {
         assert_eq!(child.foo.value.value, 3.21);
     }
 
+    #[test]
+    fn test_edit_inheritable_variable_via_reflection() {
+        let parent = Bar {
+            foo: Foo {
+                value: InheritableVariable::new(1.23),
+            },
+            other_value: InheritableVariable::new("Foobar".to_string()),
+        };
+
+        let mut child = parent.clone();
+
+        // Editing must set the inner value and mark the variable as modified.
+        child.set_field_by_path("foo.value", Box::new(3.21f32), &mut |result| {
+            assert_eq!(result.unwrap().downcast::<f32>().ok(), Some(Box::new(1.23)));
+        });
+        assert_eq!(child.foo.value.value, 3.21);
+        assert!(ReflectInheritableVariable::is_modified(&child.foo.value));
+
+        // Value of wrong type must be rejected without touching the flags.
+        child.set_field_by_path("other_value", Box::new(123u32), &mut |result| {
+            assert!(result.is_err());
+        });
+        assert_eq!(child.other_value.value, "Foobar".to_string());
+        assert!(!ReflectInheritableVariable::is_modified(&child.other_value));
+
+        // The edited property must survive inheritance, the other one must still be inherited.
+        let mut parent = parent;
+        parent.foo.value.set_value_and_mark_modified(5.0);
+        parent
+            .other_value
+            .set_value_and_mark_modified("Baz".to_string());
+        try_inherit_properties(&mut child, &parent, &[]).unwrap();
+        assert_eq!(child.foo.value.value, 3.21);
+        assert_eq!(child.other_value.value, "Baz".to_string());
+    }
+
     #[test]
     fn test_inheritable_variable_equality() {
         let va = InheritableVariable::new(1.23);
@@ -702,4 +859,57 @@ mod test {
             parent.inheritable_data.foo.value
         );
     }
+
+    #[test]
+    fn test_prefab_overrides() {
+        #[derive(Reflect, Clone, Debug)]
+        struct Item {
+            count: InheritableVariable<u32>,
+        }
+
+        #[derive(Reflect, Clone, Debug)]
+        struct Entity {
+            bar: Bar,
+            items: Vec<Item>,
+            name: InheritableVariable<String>,
+            speed: InheritableVariable<f32>,
+        }
+
+        let prefab = Entity {
+            bar: Bar {
+                foo: Foo {
+                    value: InheritableVariable::new(1.0),
+                },
+                other_value: InheritableVariable::new("Foo".to_string()),
+            },
+            items: vec![
+                Item {
+                    count: InheritableVariable::new(1),
+                },
+                Item {
+                    count: InheritableVariable::new(2),
+                },
+            ],
+            name: InheritableVariable::new("Prefab".to_string()),
+            speed: InheritableVariable::new(1.0),
+        };
+
+        let mut instance = prefab.clone();
+        assert!(reflect_prefab_overrides(&instance, &prefab).is_empty());
+
+        instance.bar.foo.value.set_value_and_mark_modified(2.0);
+        instance.items[1].count.set_value_and_mark_modified(5);
+        instance
+            .name
+            .set_value_and_mark_modified("Instance".to_string());
+        // Modified, but equal to the prefab's value.
+        instance.speed.set_value_and_mark_modified(1.0);
+        // Differs, but not modified - it will be inherited.
+        instance.bar.other_value.set_value_silent("Bar".to_string());
+
+        assert_eq!(
+            reflect_prefab_overrides(&instance, &prefab),
+            ["bar.foo.value", "items[1].count", "name"]
+        );
+    }
 }
@@ -22,7 +22,10 @@
 
 #![allow(clippy::manual_range_contains)]
 
-use crate::{buffer::DataSource, decoder::Decoder};
+use crate::{
+    buffer::{DataSource, LoopRegion},
+    decoder::Decoder,
+};
 use fyrox_core::visitor::{Visit, VisitResult, Visitor};
 use std::path::Path;
 use std::{path::PathBuf, time::Duration};
@@ -41,6 +44,8 @@ pub struct GenericBuffer {
     pub(crate) sample_rate: usize,
     #[visit(rename = "Path")]
     pub(crate) external_source_path: PathBuf,
+    #[visit(skip)]
+    pub(crate) loop_region: Option<LoopRegion>,
 }
 
 impl GenericBuffer {
@@ -73,6 +78,7 @@ impl GenericBuffer {
                         channel_count,
                         sample_rate,
                         external_source_path: Default::default(),
+                        loop_region: None,
                     })
                 }
             }
@@ -107,12 +113,18 @@ impl GenericBuffer {
                     }
                 }
 
-                Ok(Self {
+                let loop_region = decoder.loop_region();
+
+                let mut buffer = Self {
                     sample_rate: decoder.get_sample_rate(),
                     channel_count: decoder.get_channel_count(),
                     samples: decoder.into_samples(),
                     external_source_path,
-                })
+                    loop_region: None,
+                };
+                buffer.set_loop_region(loop_region);
+
+                Ok(buffer)
             }
         }
     }
@@ -159,6 +171,34 @@ impl GenericBuffer {
         self.sample_rate
     }
 
+    /// Returns loop region of the buffer (if any). Looping sound sources will repeat this region
+    /// instead of the whole buffer. Loop region is extracted from the source file automatically
+    /// (if it has one), but it could also be set manually using [`Self::set_loop_region`].
+    ///
+    /// # Notes
+    ///
+    /// Loop regions are respected only by generic buffers, streaming buffers ignore them.
+    #[inline]
+    pub fn loop_region(&self) -> Option<LoopRegion> {
+        self.loop_region
+    }
+
+    /// Sets new loop region of the buffer. Invalid regions (empty or out of bounds) are discarded.
+    pub fn set_loop_region(&mut self, loop_region: Option<LoopRegion>) {
+        let frame_count = self.frame_count();
+        self.loop_region = loop_region.filter(|region| region.is_valid_for(frame_count));
+    }
+
+    /// Returns total amount of samples per channel.
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        if self.channel_count == 0 {
+            0
+        } else {
+            self.samples.len() / self.channel_count
+        }
+    }
+
     /// Returns exact duration of the buffer.
     #[inline]
     pub fn duration(&self) -> Duration {
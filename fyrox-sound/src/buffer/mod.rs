@@ -150,6 +150,25 @@ impl Seek for DataSource {
     }
 }
 
+/// A region of a sound buffer that should be repeated when a sound source is looping. Some audio
+/// files (for example WAV files with `smpl` chunk) contain authored loop points, such points will
+/// be automatically extracted when a buffer is loaded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct LoopRegion {
+    /// Index of the first sample (per channel) of the region.
+    pub start: usize,
+    /// Index of the sample (per channel) **after** the last sample of the region.
+    pub end: usize,
+}
+
+impl LoopRegion {
+    /// Returns `true` if the region contains at least two samples and fits into a buffer with
+    /// given amount of samples per channel.
+    pub fn is_valid_for(&self, frame_count: usize) -> bool {
+        self.start + 1 < self.end && self.end <= frame_count
+    }
+}
+
 /// An error that can occur during loading of sound buffer.
 #[derive(Debug)]
 pub enum SoundBufferResourceLoadError {
@@ -179,6 +179,7 @@ impl StreamingBuffer {
                 sample_rate: streaming_source.sample_rate(),
                 channel_count: streaming_source.channel_count(),
                 external_source_path,
+                loop_region: None,
             },
             use_count: 0,
             streaming_source,
@@ -0,0 +1,410 @@
+//! Audio bus module.
+//!
+//! # Overview
+//!
+//! Audio bus is a named group of sound sources with its own gain and a set of effect inserts.
+//! Buses form a hierarchy (for example `Master -> Music/SFX/Voice -> Ambience`), gain of a bus
+//! is multiplied with gains of all its ancestors, the same applies to effect inserts - a source
+//! routed to a bus is passed to effects of the bus as well as to effects of all its ancestors.
+//!
+//! Sound sources are routed to a bus by its name, see [`crate::source::SoundSource::set_bus`].
+//! If there is no bus with the name, the source will be routed to the primary bus.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fyrox_sound::{
+//!     bus::{AudioBus, BusGraph},
+//!     context::SoundContext,
+//! };
+//!
+//! let context = SoundContext::new();
+//! let mut state = context.state();
+//! let graph = state.bus_graph_mut();
+//! let sfx = graph.add_bus(AudioBus::new("SFX").with_parent(graph.primary_bus_handle()));
+//! graph.add_bus(AudioBus::new("Ambience").with_parent(sfx).with_gain(0.5));
+//! ```
+
+use crate::{
+    effects::{Effect, EffectInput},
+    source::SoundSource,
+};
+use fyrox_core::{
+    pool::{Handle, Pool},
+    visitor::prelude::*,
+};
+use std::collections::HashMap;
+
+/// See module docs.
+#[derive(Debug, Clone, Visit)]
+pub struct AudioBus {
+    name: String,
+    parent: Handle<AudioBus>,
+    gain: f32,
+    effects: Vec<Handle<Effect>>,
+}
+
+impl Default for AudioBus {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            parent: Handle::NONE,
+            gain: 1.0,
+            effects: Default::default(),
+        }
+    }
+}
+
+impl AudioBus {
+    /// Creates new root audio bus with given name.
+    pub fn new<N: AsRef<str>>(name: N) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets desired parent bus.
+    pub fn with_parent(mut self, parent: Handle<AudioBus>) -> Self {
+        self.parent = parent;
+        self
+    }
+
+    /// Sets desired gain of the bus.
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.set_gain(gain);
+        self
+    }
+
+    /// Sets desired effect inserts of the bus.
+    pub fn with_effects(mut self, effects: Vec<Handle<Effect>>) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    /// Sets new name of the bus.
+    pub fn set_name<N: AsRef<str>>(&mut self, name: N) {
+        self.name = name.as_ref().to_owned();
+    }
+
+    /// Returns name of the bus.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets new parent of the bus. [`Handle::NONE`] makes the bus a root one.
+    pub fn set_parent(&mut self, parent: Handle<AudioBus>) {
+        self.parent = parent;
+    }
+
+    /// Returns a handle of the parent bus.
+    pub fn parent(&self) -> Handle<AudioBus> {
+        self.parent
+    }
+
+    /// Sets new gain of the bus. It does not include gains of parent buses, use
+    /// [`BusGraph::effective_gain`] to get the final gain.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.max(0.0);
+    }
+
+    /// Returns own gain of the bus.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Adds new effect insert to the bus. Every source routed to the bus (or to any of its
+    /// descendants) will be automatically added as an input of the effect.
+    pub fn add_effect(&mut self, effect: Handle<Effect>) {
+        if !self.effects.contains(&effect) {
+            self.effects.push(effect)
+        }
+    }
+
+    /// Removes effect insert from the bus. Sources routed to the bus will be removed from the inputs
+    /// of the effect on next render of the context.
+    pub fn remove_effect(&mut self, effect: Handle<Effect>) {
+        self.effects.retain(|e| *e != effect)
+    }
+
+    /// Returns a slice with all effect inserts of the bus.
+    pub fn effects(&self) -> &[Handle<Effect>] {
+        &self.effects
+    }
+}
+
+/// A hierarchy of audio buses. It always has the primary bus (see [`BusGraph::PRIMARY_BUS`])
+/// at creation.
+#[derive(Debug, Clone, Visit)]
+pub struct BusGraph {
+    buses: Pool<AudioBus>,
+    primary_bus: Handle<AudioBus>,
+}
+
+impl Default for BusGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BusGraph {
+    /// Name of the primary bus. All sources are routed to this bus by default.
+    pub const PRIMARY_BUS: &'static str = "Master";
+
+    /// Creates new bus graph with the primary bus.
+    pub fn new() -> Self {
+        let mut buses = Pool::new();
+        let primary_bus = buses.spawn(AudioBus::new(Self::PRIMARY_BUS));
+        Self { buses, primary_bus }
+    }
+
+    /// Adds new bus to the graph and returns its handle.
+    pub fn add_bus(&mut self, bus: AudioBus) -> Handle<AudioBus> {
+        self.buses.spawn(bus)
+    }
+
+    /// Removes a bus from the graph. Children buses of the removed bus become root buses. The
+    /// primary bus cannot be removed.
+    pub fn remove_bus(&mut self, handle: Handle<AudioBus>) -> Option<AudioBus> {
+        if handle == self.primary_bus || !self.buses.is_valid_handle(handle) {
+            return None;
+        }
+
+        for bus in self.buses.iter_mut() {
+            if bus.parent == handle {
+                bus.parent = Handle::NONE;
+            }
+        }
+
+        Some(self.buses.free(handle))
+    }
+
+    /// Returns a handle of the primary bus.
+    pub fn primary_bus_handle(&self) -> Handle<AudioBus> {
+        self.primary_bus
+    }
+
+    /// Tries to borrow a bus by its handle.
+    pub fn try_get(&self, handle: Handle<AudioBus>) -> Option<&AudioBus> {
+        self.buses.try_borrow(handle)
+    }
+
+    /// Tries to borrow a bus by its handle.
+    pub fn try_get_mut(&mut self, handle: Handle<AudioBus>) -> Option<&mut AudioBus> {
+        self.buses.try_borrow_mut(handle)
+    }
+
+    /// Searches for a bus with given name. Returns [`Handle::NONE`] if there is no such bus.
+    pub fn find_by_name(&self, name: &str) -> Handle<AudioBus> {
+        self.buses
+            .pair_iter()
+            .find_map(|(handle, bus)| if bus.name == name { Some(handle) } else { None })
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over all buses of the graph.
+    pub fn buses(&self) -> impl Iterator<Item = (Handle<AudioBus>, &AudioBus)> {
+        self.buses.pair_iter()
+    }
+
+    /// Returns total amount of buses in the graph.
+    pub fn bus_count(&self) -> u32 {
+        self.buses.alive_count()
+    }
+
+    // Returns a bus with the given handle and all its ancestors. Amount of iterations is limited
+    // by the total amount of buses to prevent infinite loops in case of cyclic references.
+    fn chain(&self, handle: Handle<AudioBus>) -> impl Iterator<Item = &AudioBus> {
+        let mut current = self.buses.try_borrow(handle);
+        std::iter::from_fn(move || {
+            let bus = current?;
+            current = self.buses.try_borrow(bus.parent);
+            Some(bus)
+        })
+        .take(self.buses.alive_count() as usize)
+    }
+
+    // Returns a bus to which a source with given bus name should be routed.
+    fn resolve(&self, name: &str) -> Handle<AudioBus> {
+        let handle = self.find_by_name(name);
+        if handle.is_some() {
+            handle
+        } else {
+            self.primary_bus
+        }
+    }
+
+    /// Calculates final gain of a bus, which is its own gain multiplied with the gains of all its
+    /// ancestors.
+    pub fn effective_gain(&self, handle: Handle<AudioBus>) -> f32 {
+        self.chain(handle).map(|bus| bus.gain).product()
+    }
+
+    /// Calculates final gain of a bus that will be used for a source with given bus name.
+    pub fn effective_gain_by_name(&self, name: &str) -> f32 {
+        self.effective_gain(self.resolve(name))
+    }
+
+    // Updates routing of the sources: every source is added as an input to effect inserts of its
+    // bus and the bus ancestors and removed from the effects it is no longer routed to. `routes`
+    // holds effects to which each source was added by the routing, inputs that were added
+    // manually are never touched. Only sources with changed bus are processed, unless `force` is
+    // set (it must be set when the graph or the set of sources has changed). Effective gain of the
+    // bus is cached in the source as well.
+    pub(crate) fn route_sources(
+        &self,
+        sources: &mut Pool<SoundSource>,
+        effects: &mut Pool<Effect>,
+        routes: &mut HashMap<Handle<SoundSource>, Vec<Handle<Effect>>>,
+        force: bool,
+    ) {
+        if force {
+            // Detach removed sources.
+            routes.retain(|source, routed| {
+                if sources.is_valid_handle(*source) {
+                    true
+                } else {
+                    for effect in routed.iter() {
+                        if let Some(effect) = effects.try_borrow_mut(*effect) {
+                            effect.remove_input_by_source(*source);
+                        }
+                    }
+                    false
+                }
+            });
+        }
+
+        for (source_handle, source) in sources.pair_iter_mut() {
+            if !force && !source.bus_changed {
+                continue;
+            }
+            source.bus_changed = false;
+
+            let bus = self.resolve(source.bus());
+            source.bus_gain = self.effective_gain(bus);
+
+            let old_routed = routes.remove(&source_handle).unwrap_or_default();
+
+            let mut routed = Vec::new();
+            for bus in self.chain(bus) {
+                for effect_handle in bus.effects.iter() {
+                    if routed.contains(effect_handle) {
+                        continue;
+                    }
+                    if old_routed.contains(effect_handle) {
+                        routed.push(*effect_handle);
+                    } else if let Some(effect) = effects.try_borrow_mut(*effect_handle) {
+                        if effect
+                            .inputs_ref()
+                            .iter()
+                            .all(|input| input.source() != source_handle)
+                        {
+                            effect.add_input(EffectInput::direct(source_handle));
+                            routed.push(*effect_handle);
+                        }
+                    }
+                }
+            }
+
+            for effect_handle in old_routed.iter() {
+                if !routed.contains(effect_handle) {
+                    if let Some(effect) = effects.try_borrow_mut(*effect_handle) {
+                        effect.remove_input_by_source(source_handle);
+                    }
+                }
+            }
+
+            if !routed.is_empty() {
+                routes.insert(source_handle, routed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bus::{AudioBus, BusGraph},
+        context::SoundContext,
+        effects::{reverb::Reverb, BaseEffect, Effect},
+        source::SoundSourceBuilder,
+    };
+
+    #[test]
+    fn test_child_bus_gain_multiplies_with_parent() {
+        let mut graph = BusGraph::new();
+        let primary = graph.primary_bus_handle();
+        graph.try_get_mut(primary).unwrap().set_gain(0.5);
+        let sfx = graph.add_bus(AudioBus::new("SFX").with_parent(primary).with_gain(0.8));
+        let ambience = graph.add_bus(AudioBus::new("Ambience").with_parent(sfx).with_gain(0.25));
+
+        assert_eq!(graph.find_by_name("SFX"), sfx);
+        assert_eq!(graph.effective_gain(sfx), 0.5 * 0.8);
+        assert_eq!(graph.effective_gain(ambience), 0.5 * 0.8 * 0.25);
+        assert_eq!(graph.effective_gain_by_name("Ambience"), 0.5 * 0.8 * 0.25);
+        // Unknown buses are routed to the primary bus.
+        assert_eq!(graph.effective_gain_by_name("Foobar"), 0.5);
+
+        // Cyclic references must not hang.
+        graph.try_get_mut(primary).unwrap().set_parent(ambience);
+        assert!(graph.effective_gain(ambience) > 0.0);
+    }
+
+    #[test]
+    fn test_bus_effect_inserts() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let reverb = state.add_effect(Effect::Reverb(Reverb::new(BaseEffect::default())));
+        let graph = state.bus_graph_mut();
+        let primary = graph.primary_bus_handle();
+        let sfx = graph.add_bus(AudioBus::new("SFX").with_parent(primary));
+        graph.try_get_mut(sfx).unwrap().add_effect(reverb);
+
+        let source = state.add_source(SoundSourceBuilder::new().with_bus("SFX").build().unwrap());
+        let other = state.add_source(SoundSourceBuilder::new().build().unwrap());
+
+        let mut buf = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(1.0, &mut buf);
+
+        let inputs = state.effect(reverb).inputs_ref();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].source(), source);
+        assert_ne!(inputs[0].source(), other);
+    }
+
+    #[test]
+    fn test_moving_source_to_other_bus_detaches_it_from_old_effects() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let reverb = state.add_effect(Effect::Reverb(Reverb::new(BaseEffect::default())));
+        let graph = state.bus_graph_mut();
+        let primary = graph.primary_bus_handle();
+        let sfx = graph.add_bus(AudioBus::new("SFX").with_parent(primary));
+        graph.try_get_mut(sfx).unwrap().add_effect(reverb);
+        graph.add_bus(AudioBus::new("Music").with_parent(primary).with_gain(0.5));
+
+        let source = state.add_source(SoundSourceBuilder::new().with_bus("SFX").build().unwrap());
+
+        let mut buf = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        state.render(1.0, &mut buf);
+        assert_eq!(state.effect(reverb).inputs_ref().len(), 1);
+        assert_eq!(state.source(source).bus_gain, 1.0);
+
+        state.source_mut(source).set_bus("Music");
+        state.render(1.0, &mut buf);
+        assert!(state.effect(reverb).inputs_ref().is_empty());
+        assert_eq!(state.source(source).bus_gain, 0.5);
+
+        // Removing the effect from the bus must detach the source as well.
+        state.source_mut(source).set_bus("SFX");
+        state.render(1.0, &mut buf);
+        assert_eq!(state.effect(reverb).inputs_ref().len(), 1);
+        state
+            .bus_graph_mut()
+            .try_get_mut(sfx)
+            .unwrap()
+            .remove_effect(reverb);
+        state.render(1.0, &mut buf);
+        assert!(state.effect(reverb).inputs_ref().is_empty());
+    }
+}
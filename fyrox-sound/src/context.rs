@@ -11,6 +11,9 @@
 //! sounds, only your level will do.
 
 use crate::{
+    buffer::SoundBufferResource,
+    bus::BusGraph,
+    dsp::limiter::{Limiter, LimiterParams},
     effects::{Effect, EffectRenderTrait},
     listener::Listener,
     pool::Ticket,
@@ -18,11 +21,15 @@ use crate::{
     source::{SoundSource, Status},
 };
 use fyrox_core::{
+    curve::Curve,
+    math::lerpf,
     pool::{Handle, Pool},
     reflect::prelude::*,
     visitor::prelude::*,
 };
 use std::{
+    cmp::Ordering,
+    collections::HashMap,
     sync::{Arc, Mutex, MutexGuard},
     time::Duration,
 };
@@ -85,6 +92,30 @@ impl Default for DistanceModel {
     }
 }
 
+impl DistanceModel {
+    /// Calculates distance attenuation for a source with given parameters at given distance. See
+    /// docs of each variant for formulae.
+    pub fn attenuation(
+        self,
+        distance: f32,
+        radius: f32,
+        rolloff_factor: f32,
+        max_distance: f32,
+    ) -> f32 {
+        let distance = distance.clamp(radius, max_distance);
+        match self {
+            DistanceModel::None => 1.0,
+            DistanceModel::InverseDistance => {
+                radius / (radius + rolloff_factor * (distance - radius))
+            }
+            DistanceModel::LinearDistance => {
+                1.0 - radius * (distance - radius) / (max_distance - radius)
+            }
+            DistanceModel::ExponentDistance => (distance / radius).powf(-rolloff_factor),
+        }
+    }
+}
+
 /// See module docs.
 #[derive(Clone, Default, Debug, Visit)]
 pub struct SoundContext {
@@ -97,6 +128,47 @@ impl PartialEq for SoundContext {
     }
 }
 
+/// Audio budget of a context, it limits the amount of simultaneously audible sources. Each update,
+/// playing sources are sorted by their score, which combines priority of a source (see
+/// [`SoundSource::set_priority`]) and its gain at the listener position (distance attenuation
+/// multiplied by the gain of the source). Only the best `max_voices` sources are spatialized and
+/// mixed, the rest are virtualized - they continue to play, but they are silent. Virtual sources
+/// become audible again as soon as their score is good enough. Sources are faded in and out when
+/// their state changes, so there are no clicks (see
+/// [`crate::source::VIRTUALIZATION_FADE_SAMPLE_COUNT`]).
+///
+/// Regardless of the budget, a context never mixes more than [`AudioBudget::MAX_VOICES`] sources,
+/// when there are more playing sources, the ones with the lowest score (low priority, quiet or
+/// distant sources) are virtualized first.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AudioBudget {
+    /// Maximum amount of audible sources, it is clamped to [`AudioBudget::MAX_VOICES`].
+    pub max_voices: usize,
+    /// Weight of source priority in the score, the score is calculated as
+    /// `priority * priority_weight + distance_gain * gain`.
+    pub priority_weight: f32,
+    /// A bonus to the score of currently audible sources. It prevents sources with close scores
+    /// from switching between audible and virtual states back and forth every update.
+    pub hysteresis: f32,
+}
+
+impl AudioBudget {
+    /// Hard limit of simultaneously audible sources of a context. It is high enough for any sane
+    /// scene, its purpose is to prevent the mixer from choking when something spawns sources
+    /// uncontrollably.
+    pub const MAX_VOICES: usize = 256;
+}
+
+impl Default for AudioBudget {
+    fn default() -> Self {
+        Self {
+            max_voices: Self::MAX_VOICES,
+            priority_weight: 1.0,
+            hysteresis: 0.05,
+        }
+    }
+}
+
 /// Internal state of context.
 #[derive(Default, Debug, Clone)]
 pub struct State {
@@ -108,6 +180,16 @@ pub struct State {
     effects: Pool<Effect>,
     distance_model: DistanceModel,
     paused: bool,
+    bus_graph: BusGraph,
+    limiter: Option<Limiter>,
+    audio_budget: AudioBudget,
+    max_instances_per_buffer: HashMap<SoundBufferResource, usize>,
+    // Effects to which sources were added by the bus routing.
+    bus_routes: HashMap<Handle<SoundSource>, Vec<Handle<Effect>>>,
+    // True if the bus graph or the set of sources has changed and every source must be re-routed.
+    bus_routing_dirty: bool,
+    // Scores of the playing sources, it is reused by the audio budget to not allocate on each render.
+    budget_scores: Vec<(Handle<SoundSource>, f32)>,
 }
 
 impl State {
@@ -201,6 +283,115 @@ impl State {
         self.master_gain
     }
 
+    /// Enables or disables master limiter of the context. The limiter is applied to the final mix
+    /// of the context and prevents it from exceeding the threshold, so many loud sources will not
+    /// cause harsh clipping. `None` disables the limiter.
+    ///
+    /// # Latency
+    ///
+    /// The limiter delays the output by [`LimiterParams::lookahead`] samples.
+    pub fn set_limiter(&mut self, params: Option<LimiterParams>) {
+        self.limiter = params.map(Limiter::new);
+    }
+
+    /// Returns parameters of the master limiter, if any.
+    pub fn limiter(&self) -> Option<LimiterParams> {
+        self.limiter.as_ref().map(|l| l.params())
+    }
+
+    /// Sets audio budget of the context, see [`AudioBudget`] docs for more info. Default budget
+    /// is unlimited.
+    pub fn set_audio_budget(&mut self, budget: AudioBudget) {
+        self.audio_budget = budget;
+    }
+
+    /// Returns current audio budget of the context.
+    pub fn audio_budget(&self) -> AudioBudget {
+        self.audio_budget
+    }
+
+    /// Limits the amount of simultaneously playing sources with the given buffer. When the limit is
+    /// exceeded, the oldest instances are stopped, so only the most recent `max_instances` sources
+    /// continue to play. It prevents harsh amplitude when the same sound is played many times in a
+    /// short period of time (shots of a machine gun for example). `None` removes the limit.
+    pub fn set_max_instances_per_buffer(
+        &mut self,
+        buffer: SoundBufferResource,
+        max_instances: Option<usize>,
+    ) {
+        if let Some(max_instances) = max_instances {
+            self.max_instances_per_buffer.insert(buffer, max_instances);
+        } else {
+            self.max_instances_per_buffer.remove(&buffer);
+        }
+    }
+
+    /// Returns the maximum amount of simultaneously playing sources with the given buffer, if any.
+    pub fn max_instances_per_buffer(&self, buffer: &SoundBufferResource) -> Option<usize> {
+        self.max_instances_per_buffer.get(buffer).cloned()
+    }
+
+    // Stops the oldest instances of buffers with limited amount of instances.
+    fn limit_buffer_instances(&mut self) {
+        for (buffer, &max_instances) in self.max_instances_per_buffer.iter() {
+            // Instances that are fading out are already stopped.
+            let is_instance = |source: &SoundSource| {
+                source.status() == Status::Playing
+                    && !source.fading_out
+                    && source.buffer().as_ref() == Some(buffer)
+            };
+
+            let mut instance_count = self.sources.iter().filter(|s| is_instance(s)).count();
+            while instance_count > max_instances {
+                if let Some(oldest) = self
+                    .sources
+                    .iter_mut()
+                    .filter(|s| is_instance(s))
+                    .min_by_key(|s| s.instance_stamp)
+                {
+                    // Buffers with limited instances are shared between sources, so they cannot be
+                    // streaming and stopping cannot fail.
+                    let _ = oldest.stop();
+                }
+                instance_count -= 1;
+            }
+        }
+    }
+
+    // Selects the best audible sources within the audio budget and virtualizes the rest.
+    fn apply_audio_budget(&mut self) {
+        let mut scores = std::mem::take(&mut self.budget_scores);
+        scores.clear();
+
+        for (handle, source) in self.sources.pair_iter_mut() {
+            if source.status() == Status::Playing {
+                let distance_gain = lerpf(
+                    1.0,
+                    source.calculate_distance_gain(&self.listener, self.distance_model),
+                    source.spatial_blend(),
+                );
+                let mut score = source.priority() as f32 * self.audio_budget.priority_weight
+                    + distance_gain * source.gain();
+                if !source.is_virtual {
+                    score += self.audio_budget.hysteresis;
+                }
+                scores.push((handle, score));
+            } else {
+                source.is_virtual = false;
+            }
+        }
+
+        let max_voices = self.audio_budget.max_voices.min(AudioBudget::MAX_VOICES);
+        if scores.len() > max_voices {
+            scores.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        }
+        for (i, (handle, _)) in scores.iter().enumerate() {
+            self.sources[*handle].is_virtual = i >= max_voices;
+        }
+
+        self.budget_scores = scores;
+    }
+
     /// Adds new sound source and returns handle of it by which it can be accessed later on.
     pub fn add_source(&mut self, source: SoundSource) -> Handle<SoundSource> {
         self.sources.spawn(source)
@@ -209,6 +400,7 @@ impl State {
     /// Removes sound source from the context.
     pub fn remove_source(&mut self, source: Handle<SoundSource>) {
         self.sources.free(source);
+        self.bus_routing_dirty = true;
     }
 
     /// Returns shared reference to a pool with all sound sources.
@@ -226,6 +418,32 @@ impl State {
         self.sources.borrow(handle)
     }
 
+    /// Enables or disables automatic reverb send of a sound source with given handle. See
+    /// [`SoundSource::set_auto_reverb_send`] for more info. If handle is invalid, this method will
+    /// panic.
+    pub fn set_auto_reverb_send(
+        &mut self,
+        source: Handle<SoundSource>,
+        enabled: bool,
+        curve: Curve,
+    ) {
+        self.sources
+            .borrow_mut(source)
+            .set_auto_reverb_send(enabled, curve);
+    }
+
+    /// Mutes or unmutes a sound source with given handle, see [`SoundSource::set_muted`] for more
+    /// info. If handle is invalid, this method will panic.
+    pub fn set_sound_muted(&mut self, source: Handle<SoundSource>, muted: bool) {
+        self.sources.borrow_mut(source).set_muted(muted);
+    }
+
+    /// Returns true if a sound source with given handle is muted. If handle is invalid, this method
+    /// will panic.
+    pub fn is_sound_muted(&self, source: Handle<SoundSource>) -> bool {
+        self.sources.borrow(source).is_muted()
+    }
+
     /// Checks whether a handle to a sound source is valid or not.
     pub fn is_valid_handle(&self, handle: Handle<SoundSource>) -> bool {
         self.sources.is_valid_handle(handle)
@@ -261,22 +479,76 @@ impl State {
         self.effects.borrow_mut(handle)
     }
 
+    /// Returns shared reference to the audio bus graph of the context.
+    pub fn bus_graph_ref(&self) -> &BusGraph {
+        &self.bus_graph
+    }
+
+    /// Returns mutable reference to the audio bus graph of the context.
+    pub fn bus_graph_mut(&mut self) -> &mut BusGraph {
+        // The graph could be changed in any way, so routing of every source must be recalculated.
+        self.bus_routing_dirty = true;
+        &mut self.bus_graph
+    }
+
     pub(crate) fn render(&mut self, master_gain: f32, buf: &mut [(f32, f32)]) {
         let last_time = fyrox_core::instant::Instant::now();
 
         if !self.paused {
+            let mut any_removed = false;
             self.sources.retain(|source| {
                 let done = source.is_play_once() && source.status() == Status::Stopped;
+                any_removed |= done;
                 !done
             });
 
+            self.bus_graph.route_sources(
+                &mut self.sources,
+                &mut self.effects,
+                &mut self.bus_routes,
+                self.bus_routing_dirty || any_removed,
+            );
+            self.bus_routing_dirty = false;
+
+            self.limit_buffer_instances();
+            self.apply_audio_budget();
+
             for source in self
                 .sources
                 .iter_mut()
                 .filter(|s| s.status() == Status::Playing)
             {
+                source.doppler_pitch = source.calculate_doppler_pitch(&self.listener);
                 source.render(buf.len());
 
+                if !source.apply_virtualization_fade() {
+                    // Virtual source is rendered only to advance its playback position, its signal
+                    // must not be passed to the output and the effects.
+                    for sample in source.frame_samples.iter_mut() {
+                        *sample = (0.0, 0.0);
+                    }
+                    continue;
+                }
+
+                // Apply gain of the bus here, so it will affect the signal of the source passed
+                // to the effects too. Muted sources are silenced the same way.
+                let bus_gain = if source.is_muted() {
+                    0.0
+                } else {
+                    source.bus_gain
+                };
+                if bus_gain != 1.0 {
+                    for (left, right) in source.frame_samples.iter_mut() {
+                        *left *= bus_gain;
+                        *right *= bus_gain;
+                    }
+                }
+
+                if !source.is_direct_output() {
+                    // The signal of the source is passed to the effects only.
+                    continue;
+                }
+
                 match self.renderer {
                     Renderer::Default => {
                         // Simple rendering path. Much faster (4-5 times) than HRTF path.
@@ -300,10 +572,14 @@ impl State {
             let global_gain = self.master_gain * master_gain;
 
             // Apply master gain to be able to control total sound volume.
-            for (left, right) in buf {
+            for (left, right) in buf.iter_mut() {
                 *left *= global_gain;
                 *right *= global_gain;
             }
+
+            if let Some(limiter) = self.limiter.as_mut() {
+                limiter.process(buf);
+            }
         }
 
         self.render_duration = fyrox_core::instant::Instant::now() - last_time;
@@ -334,6 +610,13 @@ impl SoundContext {
                 effects: Pool::new(),
                 distance_model: DistanceModel::InverseDistance,
                 paused: false,
+                bus_graph: BusGraph::new(),
+                limiter: None,
+                audio_budget: Default::default(),
+                max_instances_per_buffer: Default::default(),
+                bus_routes: Default::default(),
+                bus_routing_dirty: false,
+                budget_scores: Default::default(),
             }))),
         }
     }
@@ -378,6 +661,8 @@ impl Visit for State {
             self.sources.clear();
             self.effects.clear();
             self.renderer = Renderer::Default;
+            self.bus_routes.clear();
+            self.bus_routing_dirty = true;
         }
 
         let mut region = visitor.enter_region(name)?;
@@ -389,7 +674,264 @@ impl Visit for State {
         self.renderer.visit("Renderer", &mut region)?;
         self.paused.visit("Paused", &mut region)?;
         self.distance_model.visit("DistanceModel", &mut region)?;
+        let _ = self.bus_graph.visit("BusGraph", &mut region); // Backward compatibility.
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource},
+        context::{AudioBudget, DistanceModel, SoundContext, State},
+        source::{SoundSource, SoundSourceBuilder, Status, VIRTUALIZATION_FADE_SAMPLE_COUNT},
+    };
+    use fyrox_core::{algebra::Vector3, pool::Handle};
+
+    #[test]
+    fn test_audio_budget() {
+        let mut state = State::default();
+        state.set_distance_model(DistanceModel::InverseDistance);
+
+        let mut add_source = |distance: f32, priority: u8| {
+            let mut source = SoundSourceBuilder::new()
+                .with_position(Vector3::new(distance, 0.0, 0.0))
+                .with_priority(priority)
+                .build()
+                .unwrap();
+            source.play();
+            state.add_source(source)
+        };
+
+        let near = add_source(1.0, 0);
+        let far = add_source(100.0, 0);
+        let far_important = add_source(100.0, 5);
+        let middle = add_source(10.0, 0);
+
+        // Default budget does not limit a few sources.
+        state.apply_audio_budget();
+        assert!(state.sources().iter().all(|s| !s.is_virtual()));
+
+        state.set_audio_budget(AudioBudget {
+            max_voices: 2,
+            ..Default::default()
+        });
+        state.apply_audio_budget();
+        assert!(!state.source(near).is_virtual());
+        assert!(!state.source(far_important).is_virtual());
+        assert!(state.source(far).is_virtual());
+        assert!(state.source(middle).is_virtual());
+
+        // Stopped sources do not occupy voices.
+        state.source_mut(near).stop().unwrap();
+        state.apply_audio_budget();
+        assert!(!state.source(far_important).is_virtual());
+        assert!(!state.source(middle).is_virtual());
+        assert!(state.source(far).is_virtual());
+    }
+
+    #[test]
+    fn test_voice_cap() {
+        let mut state = State::default();
+        state.set_distance_model(DistanceModel::InverseDistance);
+
+        // Even unlimited budget is capped.
+        state.set_audio_budget(AudioBudget {
+            max_voices: usize::MAX,
+            ..Default::default()
+        });
+
+        let mut add_source = |distance: f32, priority: u8| {
+            let mut source = SoundSourceBuilder::new()
+                .with_position(Vector3::new(distance, 0.0, 0.0))
+                .with_priority(priority)
+                .build()
+                .unwrap();
+            source.play();
+            state.add_source(source)
+        };
+
+        // Fill the cap with loud low-priority sources and add an important distant one.
+        for _ in 0..AudioBudget::MAX_VOICES {
+            add_source(1.0, 0);
+        }
+        let important = add_source(100.0, 10);
+
+        state.apply_audio_budget();
+
+        assert!(!state.source(important).is_virtual());
+        assert_eq!(state.sources().iter().filter(|s| s.is_virtual()).count(), 1);
+    }
+
+    #[test]
+    fn test_audio_budget_hysteresis() {
+        let mut state = State::default();
+        state.set_distance_model(DistanceModel::InverseDistance);
+        state.set_audio_budget(AudioBudget {
+            max_voices: 1,
+            ..Default::default()
+        });
+
+        let mut add_source = |gain: f32| {
+            let mut source = SoundSourceBuilder::new().with_gain(gain).build().unwrap();
+            source.play();
+            state.add_source(source)
+        };
+        let first = add_source(1.0);
+        let second = add_source(0.9);
+        state.apply_audio_budget();
+        assert!(!state.source(first).is_virtual());
+        assert!(state.source(second).is_virtual());
+
+        // Slightly better score is not enough to take the voice.
+        state.source_mut(second).set_gain(1.01);
+        state.apply_audio_budget();
+        assert!(!state.source(first).is_virtual());
+        assert!(state.source(second).is_virtual());
+
+        // Significantly better score is.
+        state.source_mut(second).set_gain(1.5);
+        state.apply_audio_budget();
+        assert!(state.source(first).is_virtual());
+        assert!(!state.source(second).is_virtual());
+    }
+
+    #[test]
+    fn test_virtualization_is_faded() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let buffer = constant_buffer();
+
+        let first = play(&mut state, &buffer);
+        let second = play(&mut state, &buffer);
+        state.source_mut(second).set_priority(1);
+
+        let mut buf = vec![(0.0, 0.0); VIRTUALIZATION_FADE_SAMPLE_COUNT * 2];
+        state.render(1.0, &mut buf);
+
+        state.set_audio_budget(AudioBudget {
+            max_voices: 1,
+            ..Default::default()
+        });
+        state.render(1.0, &mut buf);
+        assert!(state.source(first).is_virtual());
+
+        // The virtualized source must fade out instead of being silenced instantly.
+        let samples = state
+            .source(first)
+            .frame_samples()
+            .iter()
+            .map(|(left, _)| *left)
+            .collect::<Vec<_>>();
+        assert!(samples[0] > 0.9);
+        assert!(samples
+            .windows(2)
+            .take(VIRTUALIZATION_FADE_SAMPLE_COUNT)
+            .all(|w| w[1] <= w[0]));
+        assert!(samples[VIRTUALIZATION_FADE_SAMPLE_COUNT..]
+            .iter()
+            .all(|s| *s == 0.0));
+
+        // And fade in back when it becomes audible again.
+        state.set_audio_budget(Default::default());
+        state.render(1.0, &mut buf);
+        let samples = state.source(first).frame_samples();
+        assert!(samples[0].0 < 0.1);
+        assert!(samples[VIRTUALIZATION_FADE_SAMPLE_COUNT..]
+            .iter()
+            .all(|s| s.0 > 0.9));
+    }
+
+    fn constant_buffer() -> SoundBufferResource {
+        SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![1.0; 44100],
+        })
+        .unwrap()
+    }
+
+    fn play(state: &mut State, buffer: &SoundBufferResource) -> Handle<SoundSource> {
+        state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer.clone())
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_max_instances_per_buffer() {
+        let mut state = State::default();
+
+        let buffer = constant_buffer();
+        let other_buffer = constant_buffer();
+        state.set_max_instances_per_buffer(buffer.clone(), Some(2));
+        assert_eq!(state.max_instances_per_buffer(&buffer), Some(2));
+        assert_eq!(state.max_instances_per_buffer(&other_buffer), None);
+
+        let first = play(&mut state, &buffer);
+        let second = play(&mut state, &buffer);
+        let other = play(&mut state, &other_buffer);
+        state.limit_buffer_instances();
+
+        let third = play(&mut state, &buffer);
+        state.limit_buffer_instances();
+
+        // The oldest instance must be stopped.
+        assert_eq!(state.source(first).status(), Status::Stopped);
+        assert_eq!(state.source(second).status(), Status::Playing);
+        assert_eq!(state.source(third).status(), Status::Playing);
+        // Other buffers must not be affected.
+        assert_eq!(state.source(other).status(), Status::Playing);
+
+        // Resuming after pause keeps the age of an instance.
+        state.source_mut(second).pause();
+        state.source_mut(second).play();
+        let fourth = play(&mut state, &buffer);
+        state.limit_buffer_instances();
+        assert_eq!(state.source(second).status(), Status::Stopped);
+        assert_eq!(state.source(third).status(), Status::Playing);
+        assert_eq!(state.source(fourth).status(), Status::Playing);
+
+        // Restarting a stopped instance makes it the most recent one.
+        state.source_mut(second).play();
+        state.limit_buffer_instances();
+        assert_eq!(state.source(second).status(), Status::Playing);
+        assert_eq!(state.source(third).status(), Status::Stopped);
+
+        // Removing the limit allows any amount of instances.
+        state.set_max_instances_per_buffer(buffer.clone(), None);
+        let fifth = play(&mut state, &buffer);
+        state.limit_buffer_instances();
+        assert_eq!(state.source(fourth).status(), Status::Playing);
+        assert_eq!(state.source(fifth).status(), Status::Playing);
+    }
+
+    #[test]
+    fn test_direct_output() {
+        // Default state has zero master gain, so the context is used to get the audible one.
+        let context = SoundContext::new();
+        let mut state = context.state();
+        let buffer = constant_buffer();
+
+        let render = |state: &mut State| {
+            let mut buf = vec![(0.0, 0.0); 256];
+            state.render(1.0, &mut buf);
+            buf.iter()
+                .map(|(left, right)| left.abs() + right.abs())
+                .sum::<f32>()
+        };
+
+        let source = play(&mut state, &buffer);
+        assert!(state.source(source).is_direct_output());
+        assert!(render(&mut state) > 0.0);
+
+        // Source without effect sends must not be audible without direct output.
+        state.source_mut(source).set_direct_output(false);
+        assert_eq!(render(&mut state), 0.0);
+        assert_eq!(state.source(source).status(), Status::Playing);
+    }
+}
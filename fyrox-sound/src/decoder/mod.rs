@@ -1,5 +1,5 @@
 use crate::{
-    buffer::DataSource,
+    buffer::{DataSource, LoopRegion},
     decoder::{vorbis::OggDecoder, wav::WavDecoder},
     error::SoundError,
 };
@@ -68,6 +68,13 @@ impl Decoder {
         }
     }
 
+    pub fn loop_region(&self) -> Option<LoopRegion> {
+        match self {
+            Decoder::Wav(wav) => wav.loop_region(),
+            Decoder::Ogg(_) => None,
+        }
+    }
+
     pub fn into_samples(self) -> Vec<f32> {
         self.collect()
     }
@@ -1,4 +1,7 @@
-use crate::{buffer::DataSource, error::SoundError};
+use crate::{
+    buffer::{DataSource, LoopRegion},
+    error::SoundError,
+};
 use hound::WavReader;
 use std::{
     fmt::{Debug, Formatter},
@@ -10,6 +13,7 @@ use std::{
 /// Wav decoder
 pub(crate) struct WavDecoder {
     reader: WavReader<DataSource>,
+    loop_region: Option<LoopRegion>,
 }
 
 impl Debug for WavDecoder {
@@ -51,14 +55,20 @@ impl WavDecoder {
             data_source: Arc::new(Mutex::new(source)),
         };
 
-        let reader = match WavReader::new(wrapped_source.clone()) {
+        let (reader, loop_region) = match WavReader::new(wrapped_source.clone()) {
             Ok(old_reader) => {
                 drop(old_reader);
+                // `hound` does not read `smpl` chunk, so we have to extract loop points manually.
+                wrapped_source.seek(SeekFrom::Start(pos)).unwrap();
+                let loop_region = read_loop_region(&mut wrapped_source);
                 // Once we ensure that this is correct WAV source we need to re-create reader
                 // with inner value of WrappedDataSource to eliminate mutex locking overhead.
                 // This is some sort of a hack to bypass design flaws of the `hound` crate.
                 wrapped_source.seek(SeekFrom::Start(pos)).unwrap();
-                WavReader::new(wrapped_source.into_inner()).unwrap()
+                (
+                    WavReader::new(wrapped_source.into_inner()).unwrap(),
+                    loop_region,
+                )
             }
             Err(_) => {
                 wrapped_source.seek(SeekFrom::Start(pos)).unwrap();
@@ -66,7 +76,14 @@ impl WavDecoder {
             }
         };
 
-        Ok(Self { reader })
+        Ok(Self {
+            reader,
+            loop_region,
+        })
+    }
+
+    pub fn loop_region(&self) -> Option<LoopRegion> {
+        self.loop_region
     }
 
     pub fn rewind(&mut self) -> Result<(), SoundError> {
@@ -95,6 +112,57 @@ impl WavDecoder {
     }
 }
 
+fn read_u32<R: Read>(reader: &mut R) -> Option<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(u32::from_le_bytes(bytes))
+}
+
+/// Searches for `smpl` chunk in a RIFF/WAVE stream and extracts first loop from it. Stream
+/// must be positioned at the beginning of RIFF header.
+fn read_loop_region<R: Read + Seek>(reader: &mut R) -> Option<LoopRegion> {
+    let mut id = [0; 4];
+    reader.read_exact(&mut id).ok()?;
+    if &id != b"RIFF" {
+        return None;
+    }
+    let _riff_size = read_u32(reader)?;
+    reader.read_exact(&mut id).ok()?;
+    if &id != b"WAVE" {
+        return None;
+    }
+
+    loop {
+        reader.read_exact(&mut id).ok()?;
+        let size = read_u32(reader)?;
+        if &id == b"smpl" {
+            // Skip manufacturer, product, sample period, MIDI unity note, MIDI pitch fraction,
+            // SMPTE format and SMPTE offset.
+            reader.seek(SeekFrom::Current(7 * 4)).ok()?;
+            let loop_count = read_u32(reader)?;
+            let _sampler_data = read_u32(reader)?;
+            if loop_count == 0 {
+                return None;
+            }
+            // Skip cue point id and loop type.
+            reader.seek(SeekFrom::Current(2 * 4)).ok()?;
+            let start = read_u32(reader)?;
+            // End point in the chunk is inclusive.
+            let end = read_u32(reader)?;
+            return Some(LoopRegion {
+                start: start as usize,
+                end: end as usize + 1,
+            });
+        } else {
+            // Chunks are aligned to 2 bytes.
+            let size = u64::from(size);
+            reader
+                .seek(SeekFrom::Current((size + size % 2) as i64))
+                .ok()?;
+        }
+    }
+}
+
 impl Iterator for WavDecoder {
     type Item = f32;
 
@@ -129,3 +197,89 @@ impl Iterator for WavDecoder {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{generic::GenericBuffer, DataSource, LoopRegion},
+        decoder::wav::read_loop_region,
+    };
+    use hound::{SampleFormat, WavSpec, WavWriter};
+    use std::io::Cursor;
+
+    fn make_wav_with_loop(frame_count: usize, loop_start: u32, loop_end: u32) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::new(&mut cursor, spec).unwrap();
+        for i in 0..frame_count {
+            writer.write_sample(i as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut bytes = cursor.into_inner();
+
+        let mut smpl = Vec::new();
+        // Manufacturer, product, sample period, MIDI unity note, MIDI pitch fraction,
+        // SMPTE format, SMPTE offset.
+        for value in [0u32, 0, 22675, 60, 0, 0, 0] {
+            smpl.extend_from_slice(&value.to_le_bytes());
+        }
+        // Loop count and sampler data.
+        smpl.extend_from_slice(&1u32.to_le_bytes());
+        smpl.extend_from_slice(&0u32.to_le_bytes());
+        // Cue point id, type, start, end, fraction, play count.
+        for value in [0u32, 0, loop_start, loop_end, 0, 0] {
+            smpl.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(b"smpl");
+        bytes.extend_from_slice(&(smpl.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&smpl);
+
+        // Fix RIFF chunk size.
+        let riff_size = (bytes.len() - 8) as u32;
+        bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+        bytes
+    }
+
+    #[test]
+    fn test_wav_loop_region() {
+        let bytes = make_wav_with_loop(100, 10, 49);
+
+        let buffer = GenericBuffer::new(DataSource::from_memory(bytes)).unwrap();
+
+        assert_eq!(buffer.frame_count(), 100);
+        assert_eq!(
+            buffer.loop_region(),
+            Some(LoopRegion { start: 10, end: 50 })
+        );
+    }
+
+    #[test]
+    fn test_wav_invalid_loop_region_is_discarded() {
+        let bytes = make_wav_with_loop(100, 10, 200);
+
+        let buffer = GenericBuffer::new(DataSource::from_memory(bytes)).unwrap();
+
+        assert_eq!(buffer.loop_region(), None);
+    }
+
+    #[test]
+    fn test_wav_huge_chunk_is_skipped() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        // Odd size of the chunk must not overflow when it is aligned.
+        bytes.extend_from_slice(b"junk");
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_eq!(read_loop_region(&mut Cursor::new(bytes)), None);
+    }
+}
@@ -1,26 +1,102 @@
-use crate::device::{Device, MixContext};
+use crate::{
+    context::SAMPLE_RATE,
+    device::{Device, FeedCallback, MixContext, NativeSample},
+};
+use std::time::Duration;
 
-pub struct DummySoundDevice;
+/// Silent device that does not output anything, but still calls the feed callback with the same
+/// rate as a real device would. It is used in headless mode and as a fallback when a platform
+/// device cannot be initialized.
+pub struct DummySoundDevice {
+    callback: Box<FeedCallback>,
+    mix_buffer: Vec<(f32, f32)>,
+    buffer_duration: Duration,
+}
 
 impl DummySoundDevice {
     pub fn new<F: FnMut(&mut [(f32, f32)]) + Send + 'static>(
-        _buffer_len_bytes: u32,
-        _callback: F,
+        buffer_len_bytes: u32,
+        callback: F,
     ) -> Self {
-        Self
+        let samples_per_channel = buffer_len_bytes as usize / std::mem::size_of::<NativeSample>();
+        Self {
+            callback: Box::new(callback),
+            mix_buffer: vec![(0.0, 0.0); samples_per_channel],
+            buffer_duration: Duration::from_secs_f64(
+                samples_per_channel as f64 / SAMPLE_RATE as f64,
+            ),
+        }
+    }
+
+    /// Returns amount of time that is covered by a single mix.
+    pub fn buffer_duration(&self) -> Duration {
+        self.buffer_duration
     }
 }
 
 impl Device for DummySoundDevice {
     fn get_mix_context(&mut self) -> Option<MixContext> {
+        // There is no output data, samples are discarded right after the mix.
         None
     }
 
     fn run(&mut self) {
+        // Blocking the main thread is not allowed on WASM, there is nothing to drive the loop.
+        #[cfg(not(target_arch = "wasm32"))]
         loop {
+            let start = std::time::Instant::now();
+
             self.mix();
 
-            //std::thread::sleep(std::time::Duration::from_millis(100));
+            // Sleep for the rest of the buffer duration to mimic the rate of a real device.
+            if let Some(remaining) = self.buffer_duration.checked_sub(start.elapsed()) {
+                std::thread::sleep(remaining);
+            }
+        }
+    }
+
+    fn mix(&mut self) {
+        for (left, right) in self.mix_buffer.iter_mut() {
+            *left = 0.0;
+            *right = 0.0;
         }
+
+        (self.callback)(&mut self.mix_buffer);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        context::{SoundContext, SAMPLE_RATE},
+        device::{dummy::DummySoundDevice, Device},
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    #[test]
+    fn test_dummy_device_calls_callback_with_full_buffer() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut device = DummySoundDevice::new(4 * SoundContext::SAMPLES_PER_CHANNEL as u32, {
+            let calls = calls.clone();
+            move |buf| {
+                assert_eq!(buf.len(), SoundContext::SAMPLES_PER_CHANNEL);
+                calls.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        device.mix();
+        device.mix();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        assert_eq!(
+            device.buffer_duration(),
+            Duration::from_secs_f64(SoundContext::SAMPLES_PER_CHANNEL as f64 / SAMPLE_RATE as f64)
+        );
     }
 }
@@ -21,6 +21,9 @@ mod coreaudio;
 // The dummy target works on all platforms
 mod dummy;
 
+use crate::error::SoundError;
+use std::sync::{Arc, Mutex};
+
 #[cfg(target_arch = "wasm32")]
 mod web;
 
@@ -73,12 +76,59 @@ trait Device {
     }
 }
 
+/// Shares a single feed callback between a platform device and its fallback, so the callback is
+/// not lost if the platform device fails to initialize.
+fn share_callback<F>(callback: F) -> impl Fn() -> Box<FeedCallback>
+where
+    F: FnMut(&mut [(f32, f32)]) + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(callback));
+    move || -> Box<FeedCallback> {
+        let shared = shared.clone();
+        Box::new(move |buf: &mut [(f32, f32)]| {
+            if let Ok(mut callback) = shared.lock() {
+                (*callback)(buf)
+            }
+        })
+    }
+}
+
+/// Tries to create a device using given constructor. If the constructor fails, a silent dummy
+/// device is created instead, so the sound engine continues to work (without any audible output)
+/// on machines without an audio device. The error of the constructor is returned along with the
+/// fallback device, so it could be reported to the user.
+fn create_device_or_fallback<F, D, C>(
+    buffer_len_bytes: u32,
+    callback: F,
+    constructor: C,
+) -> (Box<dyn Device>, Option<SoundError>)
+where
+    F: FnMut(&mut [(f32, f32)]) + Send + 'static,
+    D: Device + 'static,
+    C: FnOnce(u32, Box<FeedCallback>) -> Result<D, SoundError>,
+{
+    let make_callback = share_callback(callback);
+    match constructor(buffer_len_bytes, make_callback()) {
+        Ok(device) => (Box::new(device), None),
+        Err(err) => (
+            Box::new(dummy::DummySoundDevice::new(
+                buffer_len_bytes,
+                make_callback(),
+            )),
+            Some(err),
+        ),
+    }
+}
+
 /// Transfer ownership of device to separate mixer thread. It will
 /// call the callback with a specified rate to get data to send to a physical device.
+/// If there is no physical device available, a silent dummy device will be used instead and
+/// `on_fallback` will be called (from the mixer thread) with the reason.
 #[allow(unused_variables)]
-pub(crate) fn run_device<F>(headless: bool, buffer_len_bytes: u32, callback: F)
+pub(crate) fn run_device<F, E>(headless: bool, buffer_len_bytes: u32, callback: F, on_fallback: E)
 where
     F: FnMut(&mut [(f32, f32)]) + Send + 'static,
+    E: FnOnce(SoundError) + Send + 'static,
 {
     #[cfg(not(target_arch = "wasm32"))]
     std::thread::spawn(move || {
@@ -87,14 +137,28 @@ where
             device.run();
         } else {
             #[cfg(target_os = "windows")]
-            let mut device = dsound::DirectSoundDevice::new(buffer_len_bytes, callback).unwrap();
+            let (mut device, error) = create_device_or_fallback(
+                buffer_len_bytes,
+                callback,
+                dsound::DirectSoundDevice::new,
+            );
             #[cfg(target_os = "linux")]
-            let mut device = alsa::AlsaSoundDevice::new(buffer_len_bytes, callback).unwrap();
+            let (mut device, error) =
+                create_device_or_fallback(buffer_len_bytes, callback, alsa::AlsaSoundDevice::new);
             #[cfg(target_os = "macos")]
-            let mut device =
-                coreaudio::CoreaudioSoundDevice::new(buffer_len_bytes, callback).unwrap();
+            let (mut device, error) = create_device_or_fallback(
+                buffer_len_bytes,
+                callback,
+                coreaudio::CoreaudioSoundDevice::new,
+            );
             #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
-            let mut device = dummy::DummySoundDevice::new(buffer_len_bytes, callback);
+            let (mut device, error) = (
+                dummy::DummySoundDevice::new(buffer_len_bytes, callback),
+                None,
+            );
+            if let Some(error) = error {
+                on_fallback(error);
+            }
             device.run()
         }
     });
@@ -112,3 +176,52 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        context::SoundContext,
+        device::{create_device_or_fallback, dummy::DummySoundDevice, FeedCallback},
+        engine::SoundEngine,
+        error::SoundError,
+    };
+
+    #[test]
+    fn test_fallback_to_dummy_device() {
+        let engine = SoundEngine::without_device();
+        let context = SoundContext::new();
+        engine.lock().unwrap().add_context(context.clone());
+
+        let (mut device, error) = create_device_or_fallback(
+            4 * SoundContext::SAMPLES_PER_CHANNEL as u32,
+            {
+                let engine = engine.clone();
+                move |buf| {
+                    if let Ok(mut engine) = engine.lock() {
+                        engine.render(buf);
+                    }
+                }
+            },
+            |_, _: Box<FeedCallback>| -> Result<DummySoundDevice, SoundError> {
+                Err(SoundError::FailedToInitializeDevice(
+                    "Simulated device failure".to_string(),
+                ))
+            },
+        );
+
+        // Dummy device does not produce any output, the reason of the fallback is returned.
+        assert!(device.get_mix_context().is_none());
+        assert!(matches!(
+            error,
+            Some(SoundError::FailedToInitializeDevice(ref reason)) if reason == "Simulated device failure"
+        ));
+
+        // Context must be still alive and must be able to tick.
+        let mut buf = vec![(0.0, 0.0); SoundContext::SAMPLES_PER_CHANNEL];
+        for _ in 0..4 {
+            engine.lock().unwrap().render(&mut buf);
+        }
+        assert!(buf.iter().all(|(l, r)| *l == 0.0 && *r == 0.0));
+        assert!(engine.lock().unwrap().has_context(&context));
+    }
+}
@@ -0,0 +1,93 @@
+//! Fade curves module.
+//!
+//! # Overview
+//!
+//! Fade curve defines the shape of a transition of some value (gain, send level, spatial blend, etc.)
+//! over time. Linear transitions sound unnatural for volume, because perceived loudness is
+//! logarithmic, so other curves should be preferred for fades of the volume.
+
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// See module docs.
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Reflect, Visit, AsRefStr, EnumString, EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum FadeCurve {
+    /// `w = t`
+    Linear = 0,
+
+    /// Change of the value is linear in decibels (60 dB range), normalized to pass through `0` and `1`:
+    ///
+    /// `w = (1000 ^ t - 1) / 999`
+    ///
+    /// It sounds like a smooth, even change of volume.
+    Exponential = 1,
+
+    /// Smooth start and end of the transition:
+    ///
+    /// `w = t * t * (3 - 2 * t)`
+    SCurve = 2,
+
+    /// `w = sin(t * PI / 2)`
+    ///
+    /// If two signals are crossfaded with weights `w(t)` and `w(1 - t)`, total power of the mix stays
+    /// constant.
+    EqualPower = 3,
+}
+
+impl Default for FadeCurve {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl FadeCurve {
+    /// Calculates the weight of the transition at normalized time `t` (clamped to `[0; 1]` range).
+    /// Every curve starts at `0.0` and ends at `1.0`.
+    pub fn evaluate(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => t,
+            FadeCurve::Exponential => (1000.0f32.powf(t) - 1.0) / 999.0,
+            FadeCurve::SCurve => t * t * (3.0 - 2.0 * t),
+            FadeCurve::EqualPower => (t * std::f32::consts::FRAC_PI_2).sin(),
+        }
+    }
+
+    /// Interpolates between `from` and `to` at normalized time `t` using the curve.
+    pub fn interpolate(self, from: f32, to: f32, t: f32) -> f32 {
+        from + (to - from) * self.evaluate(t)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dsp::fade::FadeCurve;
+
+    #[test]
+    fn test_fade_curves() {
+        for curve in [
+            FadeCurve::Linear,
+            FadeCurve::Exponential,
+            FadeCurve::SCurve,
+            FadeCurve::EqualPower,
+        ] {
+            assert!(curve.evaluate(0.0).abs() < 1.0e-6);
+            assert!((curve.evaluate(1.0) - 1.0).abs() < 1.0e-6);
+        }
+
+        // Fade-in of the same duration: exponential curve stays quiet much longer.
+        let linear = FadeCurve::Linear.interpolate(0.0, 1.0, 0.5);
+        let exponential = FadeCurve::Exponential.interpolate(0.0, 1.0, 0.5);
+        assert_eq!(linear, 0.5);
+        assert!(exponential < 0.05);
+
+        // Equal power crossfade keeps total power.
+        let t = 0.3;
+        let a = FadeCurve::EqualPower.evaluate(t);
+        let b = FadeCurve::EqualPower.evaluate(1.0 - t);
+        assert!((a * a + b * b - 1.0).abs() < 1.0e-5);
+    }
+}
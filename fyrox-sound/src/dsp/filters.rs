@@ -6,7 +6,7 @@
 //! Since this is very specific theme with lots of background, every filter has link to source with good
 //! description of each filter. There is no need to describe them all here.
 
-use crate::dsp::DelayLine;
+use crate::dsp::{fade::FadeCurve, DelayLine};
 use fyrox_core::{
     reflect::prelude::*,
     visitor::{Visit, VisitResult, Visitor},
@@ -121,6 +121,17 @@ impl LpfComb {
         self.delay_line.feed(result);
         result
     }
+
+    /// Processes single sample, the delay of the filter is shortened by `delay_offset` samples (it
+    /// could be fractional). Smoothly changing offset breaks up resonances of the filter.
+    pub fn feed_modulated(&mut self, sample: f32, delay_offset: f32) -> f32 {
+        let delayed = self
+            .delay_line
+            .tap(self.delay_line.len() as f32 - delay_offset);
+        let result = sample + self.feedback * self.low_pass.feed(delayed);
+        self.delay_line.feed(result);
+        result
+    }
 }
 
 /// Allpass Filter - <https://ccrma.stanford.edu/~jos/pasp/Allpass_Filters.html>
@@ -192,6 +203,13 @@ pub enum BiquadKind {
     /// Reduces amplitude of frequencies in a shape like this _/̅  where location of center of /
     /// defined by F_center.
     HighShelf,
+
+    /// Reduces amplitude of frequencies in some narrow band around F_center giving ̅ \/ ̅ shape.
+    Notch,
+
+    /// Boosts or cuts amplitude of frequencies in some band around F_center, the rest of frequencies
+    /// are passed unchanged. Amount of boost is defined by `gain`.
+    Peaking,
 }
 
 /// Generic second order digital filter.
@@ -241,6 +259,41 @@ impl Biquad {
         }
     }
 
+    /// Creates new low pass filter, where `sample_rate` and `cutoff` are in Hz and `q` is the
+    /// quality of the filter (`1/sqrt(2)` gives maximally flat response).
+    pub fn low_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        Self::new(BiquadKind::LowPass, cutoff / sample_rate, 1.0, q)
+    }
+
+    /// Creates new high pass filter, where `sample_rate` and `cutoff` are in Hz and `q` is the
+    /// quality of the filter (`1/sqrt(2)` gives maximally flat response).
+    pub fn high_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        Self::new(BiquadKind::HighPass, cutoff / sample_rate, 1.0, q)
+    }
+
+    /// Creates new band pass filter with constant skirt gain, where `sample_rate` and `center` are
+    /// in Hz and `q` defines width of the band.
+    pub fn band_pass(sample_rate: f32, center: f32, q: f32) -> Self {
+        Self::new(BiquadKind::BandPass, center / sample_rate, 1.0, q)
+    }
+
+    /// Creates new notch (band reject) filter, where `sample_rate` and `center` are in Hz and `q`
+    /// defines width of the rejected band.
+    pub fn notch(sample_rate: f32, center: f32, q: f32) -> Self {
+        Self::new(BiquadKind::Notch, center / sample_rate, 1.0, q)
+    }
+
+    /// Creates new peaking EQ filter, where `sample_rate` and `center` are in Hz, `q` defines width
+    /// of the band and `gain_db` is the amount of boost (or cut, if negative) at `center` in decibels.
+    pub fn peaking(sample_rate: f32, center: f32, q: f32, gain_db: f32) -> Self {
+        Self::new(
+            BiquadKind::Peaking,
+            center / sample_rate,
+            10.0f32.powf(gain_db / 40.0),
+            q,
+        )
+    }
+
     /// Tunes filter using specified parameters.
     /// `kind` - new kind of filter.
     /// `fc` - normalized frequency
@@ -310,6 +363,24 @@ impl Biquad {
                 let a2 = (gain + 1.0) - (gain - 1.0) * w0_cos - sq;
                 (b0, b1, b2, a0, a1, a2)
             }
+            BiquadKind::Notch => {
+                let b0 = 1.0;
+                let b1 = -2.0 * w0_cos;
+                let b2 = 1.0;
+                let a0 = 1.0 + alpha;
+                let a1 = -2.0 * w0_cos;
+                let a2 = 1.0 - alpha;
+                (b0, b1, b2, a0, a1, a2)
+            }
+            BiquadKind::Peaking => {
+                let b0 = 1.0 + alpha * gain;
+                let b1 = -2.0 * w0_cos;
+                let b2 = 1.0 - alpha * gain;
+                let a0 = 1.0 + alpha / gain;
+                let a1 = -2.0 * w0_cos;
+                let a2 = 1.0 - alpha / gain;
+                (b0, b1, b2, a0, a1, a2)
+            }
         };
 
         self.b0 = b0 / a0;
@@ -341,3 +412,211 @@ impl Default for Biquad {
         }
     }
 }
+
+/// Low pass filter which cutoff frequency does not change instantly, instead it moves to a target
+/// cutoff along a fade curve in given time. It is useful when the cutoff is driven by something that
+/// changes abruptly (for example, occlusion of a sound source changes when geometry moves in or out
+/// of the path between a source and a listener) - a sudden change of the cutoff produces audible
+/// "zipper" noise, a smooth transition does not. The cutoff is interpolated in logarithmic scale,
+/// because pitch perception is logarithmic.
+#[derive(Clone, Debug, Visit, PartialEq)]
+pub struct SmoothLowPass {
+    filter: Biquad,
+    sample_rate: f32,
+    quality: f32,
+    cutoff: f32,
+    start_cutoff: f32,
+    target_cutoff: f32,
+    transition_time: f32,
+    elapsed: f32,
+    curve: FadeCurve,
+}
+
+impl SmoothLowPass {
+    /// Creates new filter, where `sample_rate` and `cutoff` are in Hz, `quality` is the quality of
+    /// the filter (see [`Biquad::low_pass`]) and `transition_time` is the time (in seconds) in
+    /// which the cutoff reaches a new target. Transitions use [`FadeCurve::SCurve`] by default.
+    pub fn new(sample_rate: f32, cutoff: f32, quality: f32, transition_time: f32) -> Self {
+        Self {
+            filter: Biquad::low_pass(sample_rate, cutoff, quality),
+            sample_rate,
+            quality,
+            cutoff,
+            start_cutoff: cutoff,
+            target_cutoff: cutoff,
+            transition_time: transition_time.max(0.0),
+            elapsed: 0.0,
+            curve: FadeCurve::SCurve,
+        }
+    }
+
+    /// Sets a new cutoff frequency (in Hz) the filter will move to. The transition starts from the
+    /// current cutoff.
+    pub fn set_target_cutoff(&mut self, cutoff: f32) {
+        self.start_cutoff = self.cutoff;
+        self.target_cutoff = cutoff;
+        self.elapsed = 0.0;
+    }
+
+    /// Returns the cutoff frequency (in Hz) the filter moves to.
+    pub fn target_cutoff(&self) -> f32 {
+        self.target_cutoff
+    }
+
+    /// Returns current cutoff frequency (in Hz) of the filter.
+    pub fn cutoff(&self) -> f32 {
+        self.cutoff
+    }
+
+    /// Sets new time (in seconds) of the cutoff transition. Zero means that the cutoff will snap to
+    /// the target on next update.
+    pub fn set_transition_time(&mut self, transition_time: f32) {
+        self.transition_time = transition_time.max(0.0);
+    }
+
+    /// Returns current time (in seconds) of the cutoff transition.
+    pub fn transition_time(&self) -> f32 {
+        self.transition_time
+    }
+
+    /// Sets the shape of the cutoff transition.
+    pub fn set_curve(&mut self, curve: FadeCurve) {
+        self.curve = curve;
+    }
+
+    /// Returns the shape of the cutoff transition.
+    pub fn curve(&self) -> FadeCurve {
+        self.curve
+    }
+
+    /// Moves current cutoff towards the target and re-tunes the filter. `dt` is the time (in
+    /// seconds) passed since last update, it should be called before processing each block of
+    /// samples.
+    pub fn update(&mut self, dt: f32) {
+        if self.cutoff == self.target_cutoff {
+            return;
+        }
+
+        self.elapsed += dt;
+        let t = if self.transition_time > 0.0 {
+            self.elapsed / self.transition_time
+        } else {
+            1.0
+        };
+        self.cutoff = if t >= 1.0 {
+            self.target_cutoff
+        } else {
+            self.curve
+                .interpolate(self.start_cutoff.ln(), self.target_cutoff.ln(), t)
+                .exp()
+        };
+
+        // Re-tune coefficients only, the state of the filter must be preserved to not produce clicks.
+        self.filter.tune(
+            BiquadKind::LowPass,
+            self.cutoff / self.sample_rate,
+            1.0,
+            self.quality,
+        );
+    }
+
+    /// Processes single sample.
+    pub fn feed(&mut self, sample: f32) -> f32 {
+        self.filter.feed(sample)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dsp::{
+        fade::FadeCurve,
+        filters::{Biquad, SmoothLowPass},
+    };
+    use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    // Feeds a sine wave of given frequency to the filter and returns peak amplitude of the output
+    // after the filter has settled.
+    fn response(mut filter: Biquad, frequency: f32) -> f32 {
+        let mut peak = 0.0f32;
+        for i in 0..SAMPLE_RATE as usize {
+            let sample = (2.0 * PI * frequency * i as f32 / SAMPLE_RATE).sin();
+            let output = filter.feed(sample);
+            if i > SAMPLE_RATE as usize / 2 {
+                peak = peak.max(output.abs());
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn test_biquad_presets() {
+        let low_pass = Biquad::low_pass(SAMPLE_RATE, 1000.0, FRAC_1_SQRT_2);
+        assert!((response(low_pass.clone(), 100.0) - 1.0).abs() < 0.01);
+        assert!((response(low_pass.clone(), 1000.0) - FRAC_1_SQRT_2).abs() < 0.01);
+        assert!(response(low_pass, 10000.0) < 0.02);
+
+        let high_pass = Biquad::high_pass(SAMPLE_RATE, 1000.0, FRAC_1_SQRT_2);
+        assert!(response(high_pass.clone(), 100.0) < 0.02);
+        assert!((response(high_pass, 10000.0) - 1.0).abs() < 0.01);
+
+        let band_pass = Biquad::band_pass(SAMPLE_RATE, 1000.0, 1.0);
+        assert!(response(band_pass.clone(), 1000.0) > 0.99);
+        assert!(response(band_pass, 10000.0) < 0.15);
+
+        let notch = Biquad::notch(SAMPLE_RATE, 1000.0, 1.0);
+        assert!(response(notch.clone(), 1000.0) < 0.01);
+        assert!(response(notch, 10000.0) > 0.95);
+
+        let peaking = Biquad::peaking(SAMPLE_RATE, 1000.0, 1.0, 6.0);
+        assert!((response(peaking.clone(), 1000.0) - 10.0f32.powf(6.0 / 20.0)).abs() < 0.01);
+        assert!((response(peaking, 20.0) - 1.0).abs() < 0.01);
+
+        // Zero gain peaking filter must not change the signal.
+        let flat = Biquad::peaking(SAMPLE_RATE, 1000.0, 1.0, 0.0);
+        assert!((response(flat.clone(), 1000.0) - 1.0).abs() < 0.01);
+        assert!((response(flat, 10000.0) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_smooth_low_pass_cutoff_transition() {
+        let mut filter = SmoothLowPass::new(SAMPLE_RATE, 20000.0, FRAC_1_SQRT_2, 0.5);
+
+        // Sudden occlusion.
+        filter.set_target_cutoff(1000.0);
+
+        let mut prev_cutoff = filter.cutoff();
+        for _ in 0..5 {
+            filter.update(1.0 / 60.0);
+            let cutoff = filter.cutoff();
+            // The cutoff moves towards the target, but it does not jump to it.
+            assert!(cutoff < prev_cutoff);
+            assert!(cutoff > 1000.0);
+            // Each step is only a fraction of the whole transition.
+            assert!(prev_cutoff - cutoff < (20000.0 - 1000.0) * 0.1);
+            prev_cutoff = cutoff;
+        }
+
+        // It reaches the target once the transition time has passed.
+        for _ in 0..24 {
+            filter.update(1.0 / 60.0);
+        }
+        assert!(filter.cutoff() > 1000.0);
+        filter.update(1.0 / 30.0);
+        assert_eq!(filter.cutoff(), 1000.0);
+
+        // The transition follows the curve in logarithmic scale - halfway of the linear transition
+        // is the geometric mean of the cutoffs.
+        filter.set_curve(FadeCurve::Linear);
+        filter.set_target_cutoff(4000.0);
+        filter.update(0.25);
+        assert!((filter.cutoff() - 2000.0).abs() < 1.0);
+
+        // Zero transition time snaps instantly.
+        filter.set_transition_time(0.0);
+        filter.set_target_cutoff(5000.0);
+        filter.update(1.0 / 60.0);
+        assert_eq!(filter.cutoff(), 5000.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Look-ahead brick-wall limiter, it is used to prevent clipping of the final mix.
+//!
+//! # Latency
+//!
+//! The limiter delays the signal by the look-ahead window (see [`LimiterParams::lookahead`]), this
+//! is the price for being able to reduce the gain *before* a peak arrives. For example, 64 samples
+//! of look-ahead at 44100 Hz adds ~1.45 ms of latency.
+
+use crate::context::SAMPLE_RATE;
+use std::collections::VecDeque;
+
+/// Parameters of the [`Limiter`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LimiterParams {
+    /// Maximum allowed absolute amplitude of the output signal.
+    pub threshold: f32,
+    /// Time (in seconds) that is needed for the gain to get back to unity after a peak.
+    pub release_time: f32,
+    /// Length of the look-ahead window in samples. The signal will be delayed by this amount of
+    /// samples.
+    pub lookahead: usize,
+}
+
+impl Default for LimiterParams {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            release_time: 0.1,
+            lookahead: 64,
+        }
+    }
+}
+
+/// See module docs.
+#[derive(Clone, Debug)]
+pub struct Limiter {
+    params: LimiterParams,
+    release_coefficient: f32,
+    gain: f32,
+    delay: Vec<(f32, f32)>,
+    position: usize,
+    time: usize,
+    // Monotonic queue of required gains over the look-ahead window, it is used to get the
+    // minimum gain over the window in constant amortized time.
+    window: VecDeque<(usize, f32)>,
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl Limiter {
+    /// Creates new limiter with given parameters.
+    pub fn new(params: LimiterParams) -> Self {
+        let params = LimiterParams {
+            threshold: params.threshold.max(f32::EPSILON),
+            release_time: params.release_time.max(0.0),
+            lookahead: params.lookahead,
+        };
+
+        let release_samples = params.release_time * SAMPLE_RATE as f32;
+        let release_coefficient = if release_samples > 0.0 {
+            (-1.0 / release_samples).exp()
+        } else {
+            0.0
+        };
+
+        Self {
+            params,
+            release_coefficient,
+            gain: 1.0,
+            delay: vec![(0.0, 0.0); params.lookahead],
+            position: 0,
+            time: 0,
+            window: VecDeque::with_capacity(params.lookahead + 1),
+        }
+    }
+
+    /// Returns parameters of the limiter.
+    pub fn params(&self) -> LimiterParams {
+        self.params
+    }
+
+    /// Returns latency (in samples) added by the limiter.
+    pub fn latency(&self) -> usize {
+        self.params.lookahead
+    }
+
+    /// Processes single stereo sample.
+    pub fn feed(&mut self, sample: (f32, f32)) -> (f32, f32) {
+        let peak = sample.0.abs().max(sample.1.abs());
+        let required_gain = if peak > self.params.threshold {
+            self.params.threshold / peak
+        } else {
+            1.0
+        };
+
+        while self
+            .window
+            .back()
+            .map_or(false, |(_, gain)| *gain >= required_gain)
+        {
+            self.window.pop_back();
+        }
+        self.window.push_back((self.time, required_gain));
+        // The window covers the delayed (outgoing) sample and every sample after it.
+        while self
+            .window
+            .front()
+            .map_or(false, |(time, _)| time + self.params.lookahead < self.time)
+        {
+            self.window.pop_front();
+        }
+        self.time += 1;
+
+        let target = self.window.front().map_or(1.0, |(_, gain)| *gain);
+        let released = 1.0 - (1.0 - self.gain) * self.release_coefficient;
+        self.gain = target.min(released);
+
+        let delayed = if self.delay.is_empty() {
+            sample
+        } else {
+            let delayed = std::mem::replace(&mut self.delay[self.position], sample);
+            self.position = (self.position + 1) % self.delay.len();
+            delayed
+        };
+
+        (delayed.0 * self.gain, delayed.1 * self.gain)
+    }
+
+    /// Processes given buffer in-place.
+    pub fn process(&mut self, buf: &mut [(f32, f32)]) {
+        for sample in buf {
+            *sample = self.feed(*sample);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dsp::limiter::{Limiter, LimiterParams};
+
+    #[test]
+    fn test_limiter_never_exceeds_threshold() {
+        let mut limiter = Limiter::new(LimiterParams {
+            threshold: 0.5,
+            release_time: 0.01,
+            lookahead: 16,
+        });
+
+        for i in 0..4096 {
+            let x = (i as f32 * 0.05).sin() * if i % 512 < 256 { 3.0 } else { 0.25 };
+            let (left, right) = limiter.feed((x, -x));
+            assert!(left.abs() <= 0.5 + f32::EPSILON);
+            assert!(right.abs() <= 0.5 + f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_limiter_latency() {
+        let lookahead = 8;
+        let mut limiter = Limiter::new(LimiterParams {
+            threshold: 1.0,
+            release_time: 0.1,
+            lookahead,
+        });
+        assert_eq!(limiter.latency(), lookahead);
+
+        let output = (0..32)
+            .map(|i| limiter.feed(if i == 0 { (0.5, 0.25) } else { (0.0, 0.0) }))
+            .collect::<Vec<_>>();
+
+        // Quiet signal must pass unchanged, but delayed by the look-ahead window.
+        for (i, sample) in output.into_iter().enumerate() {
+            if i == lookahead {
+                assert_eq!(sample, (0.5, 0.25));
+            } else {
+                assert_eq!(sample, (0.0, 0.0));
+            }
+        }
+    }
+}
@@ -10,7 +10,9 @@
 
 use fyrox_core::visitor::{Visit, VisitResult, Visitor};
 
+pub mod fade;
 pub mod filters;
+pub mod limiter;
 
 /// See more info here <https://ccrma.stanford.edu/~jos/pasp/Delay_Lines.html>
 #[derive(Debug, Clone, Visit)]
@@ -50,6 +52,23 @@ impl DelayLine {
     pub fn last(&self) -> f32 {
         self.last
     }
+
+    /// Returns a sample that was fed `delay` samples ago (relative to the next call of
+    /// [`Self::feed`]), fractional delays are linearly interpolated. The delay is clamped to
+    /// `[1; len]` range, `len` gives the same sample as [`Self::feed`] will return.
+    pub fn tap(&self, delay: f32) -> f32 {
+        let len = self.samples.len();
+        let delay = delay.clamp(1.0, len as f32);
+        let whole = delay.floor() as usize;
+        let fraction = delay - whole as f32;
+        let at = |d: usize| self.samples[(self.pos as usize + len - d % len) % len];
+        let a = at(whole);
+        if fraction > 0.0 {
+            a + (at(whole + 1) - a) * fraction
+        } else {
+            a
+        }
+    }
 }
 
 impl Default for DelayLine {
@@ -0,0 +1,158 @@
+//! Delay module
+//!
+//! # Overview
+//!
+//! Delay (echo) effect repeats input signal after some time with decreasing amplitude. Every repeat
+//! is fed back into the delay, so the amount of echoes is defined by feedback coefficient.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::context::SoundContext;
+//! use fyrox_sound::effects::delay::Delay;
+//! use fyrox_sound::effects::{Effect, BaseEffect};
+//!
+//! fn add_delay(context: &mut SoundContext) {
+//!     let mut delay = Delay::new(BaseEffect::default());
+//!     delay.set_delay_time(0.25);
+//!     delay.set_feedback(0.5);
+//!     context.state().add_effect(Effect::Delay(delay));
+//! }
+//! ```
+
+use crate::{
+    context::{DistanceModel, SAMPLE_RATE},
+    effects::{BaseEffect, EffectRenderTrait},
+    listener::Listener,
+    source::SoundSource,
+};
+use fyrox_core::{
+    pool::Pool,
+    visitor::{Visit, VisitResult, Visitor},
+};
+use std::ops::{Deref, DerefMut};
+
+/// See module docs.
+#[derive(Debug, Clone, Visit)]
+pub struct Delay {
+    base: BaseEffect,
+    delay_time: f32,
+    feedback: f32,
+    mix: f32,
+    #[visit(skip)]
+    buffer: Vec<(f32, f32)>,
+    #[visit(skip)]
+    position: usize,
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl Delay {
+    /// Creates new instance of delay effect with 0.5 seconds delay time, 0.5 feedback and equal
+    /// mix of dry and wet signals.
+    pub fn new(base: BaseEffect) -> Self {
+        Self {
+            base,
+            delay_time: 0.5,
+            feedback: 0.5,
+            mix: 0.5,
+            buffer: Default::default(),
+            position: 0,
+        }
+    }
+
+    /// Sets time (in seconds) between echoes.
+    pub fn set_delay_time(&mut self, delay_time: f32) {
+        self.delay_time = delay_time.max(0.0);
+    }
+
+    /// Returns time (in seconds) between echoes.
+    pub fn delay_time(&self) -> f32 {
+        self.delay_time
+    }
+
+    /// Sets how much of the delayed signal is fed back into the delay, it defines how fast echoes
+    /// will decay. 0.0 - single echo, 1.0 - infinite echoes. Values are clamped to `[0.0; 1.0]` range.
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(0.0, 1.0);
+    }
+
+    /// Returns feedback coefficient.
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /// Sets mixing of dry (input) and wet (delayed) signals. 0.0 - dry signal only, 1.0 - wet
+    /// signal only. Values are clamped to `[0.0; 1.0]` range.
+    pub fn set_mix(&mut self, mix: f32) {
+        self.mix = mix.clamp(0.0, 1.0);
+    }
+
+    /// Returns mixing coefficient of dry and wet signals.
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    fn delay_len(&self) -> usize {
+        ((self.delay_time * SAMPLE_RATE as f32).round() as usize).max(1)
+    }
+}
+
+impl EffectRenderTrait for Delay {
+    fn render(
+        &mut self,
+        sources: &Pool<SoundSource>,
+        listener: &Listener,
+        distance_model: DistanceModel,
+        mix_buf: &mut [(f32, f32)],
+    ) {
+        self.base
+            .render(sources, listener, distance_model, mix_buf.len());
+
+        // Delay time could be changed at any time, so the ring buffer must be resized.
+        let len = self.delay_len();
+        if self.buffer.len() != len {
+            self.buffer = vec![(0.0, 0.0); len];
+            self.position = 0;
+        }
+
+        let wet = self.mix;
+        let dry = 1.0 - self.mix;
+
+        for ((out_left, out_right), &(left, right)) in
+            mix_buf.iter_mut().zip(self.base.frame_samples.iter())
+        {
+            let (delayed_left, delayed_right) = self.buffer[self.position];
+
+            self.buffer[self.position] = (
+                left + delayed_left * self.feedback,
+                right + delayed_right * self.feedback,
+            );
+            self.position += 1;
+            if self.position >= self.buffer.len() {
+                self.position = 0;
+            }
+
+            *out_left += self.base.gain * (dry * left + wet * delayed_left);
+            *out_right += self.base.gain * (dry * right + wet * delayed_right);
+        }
+    }
+}
+
+impl Deref for Delay {
+    type Target = BaseEffect;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Delay {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
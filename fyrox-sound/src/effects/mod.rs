@@ -7,7 +7,7 @@
 use crate::{
     context::DistanceModel,
     dsp::filters::Biquad,
-    effects::reverb::Reverb,
+    effects::{delay::Delay, reverb::Reverb},
     listener::Listener,
     source::{SoundSource, Status},
 };
@@ -18,6 +18,7 @@ use fyrox_core::{
 };
 use std::ops::{Deref, DerefMut};
 
+pub mod delay;
 pub mod reverb;
 
 /// Stub effect that does nothing.
@@ -58,6 +59,8 @@ pub enum Effect {
     Stub(StubEffect),
     /// Reverberation effect. See corresponding module for more info.
     Reverb(Reverb),
+    /// Delay (echo) effect. See corresponding module for more info.
+    Delay(Delay),
 }
 
 impl Default for Effect {
@@ -76,14 +79,35 @@ pub(crate) trait EffectRenderTrait {
     );
 }
 
+/// Defines what will be written to the output by an effect. It could be useful for debugging
+/// purposes, to hear what exactly an effect adds to the signal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Visit)]
+pub enum MonitorMode {
+    /// Output of the effect is passed to the output as is.
+    Normal,
+    /// Only the difference between processed and dry (input) signals is passed to the output. For
+    /// example, a reverb in this mode will output its tail only.
+    Difference,
+}
+
+impl Default for MonitorMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
 /// Base effect for all other kinds of effects. It contains set of inputs (direct
 /// or filtered), provides some basic methods to control them.
 #[derive(Debug, Clone, Visit)]
 pub struct BaseEffect {
     gain: f32,
     inputs: Vec<EffectInput>,
+    #[visit(optional)] // Backward compatibility
+    monitor: MonitorMode,
     #[visit(skip)]
     frame_samples: Vec<(f32, f32)>,
+    #[visit(skip)]
+    monitor_samples: Vec<(f32, f32)>,
 }
 
 impl Default for BaseEffect {
@@ -91,7 +115,9 @@ impl Default for BaseEffect {
         Self {
             gain: 1.0,
             inputs: Default::default(),
+            monitor: Default::default(),
             frame_samples: Default::default(),
+            monitor_samples: Default::default(),
         }
     }
 }
@@ -104,6 +130,20 @@ impl BaseEffect {
         distance_model: DistanceModel,
         amount: usize,
     ) {
+        self.render_with_send_gain(sources, listener, distance_model, amount, |_, gain| gain)
+    }
+
+    // Same as `render`, but allows to modify the gain of each input using source's distance gain.
+    pub(crate) fn render_with_send_gain<F>(
+        &mut self,
+        sources: &Pool<SoundSource>,
+        listener: &Listener,
+        distance_model: DistanceModel,
+        amount: usize,
+        send_gain: F,
+    ) where
+        F: Fn(&SoundSource, f32) -> f32,
+    {
         // First of all check that inputs are still lead to valid sound sources.
         // We use some sort of weak coupling here - it is ok to leave sound source
         // connected to effect and delete source, such "dangling" inputs will be
@@ -128,12 +168,18 @@ impl BaseEffect {
                 continue;
             }
 
-            let distance_gain = source.calculate_distance_gain(listener, distance_model);
+            let distance_gain = input.gain
+                * send_gain(
+                    source,
+                    source.calculate_distance_gain(listener, distance_model),
+                );
 
             let prev_distance_gain = input.last_distance_gain.unwrap_or(distance_gain);
 
             input.last_distance_gain = Some(distance_gain);
 
+            let (pan_left, pan_right) = input.pan_gains();
+
             let mut k = 0.0;
             let step = 1.0 / amount as f32;
 
@@ -143,8 +189,8 @@ impl BaseEffect {
                         self.frame_samples.iter_mut().zip(source.frame_samples())
                     {
                         let g = math::lerpf(prev_distance_gain, distance_gain, k);
-                        *accum_left += input_left * g;
-                        *accum_right += input_right * g;
+                        *accum_left += input_left * g * pan_left;
+                        *accum_right += input_right * g * pan_right;
                         k += step;
                     }
                 }
@@ -154,8 +200,8 @@ impl BaseEffect {
                     {
                         let (filtered_left, filtered_right) = filter.feed(input_left, input_right);
                         let g = math::lerpf(prev_distance_gain, distance_gain, k);
-                        *accum_left += filtered_left * g;
-                        *accum_right += filtered_right * g;
+                        *accum_left += filtered_left * g * pan_left;
+                        *accum_right += filtered_right * g * pan_right;
                         k += step;
                     }
                 }
@@ -163,6 +209,28 @@ impl BaseEffect {
         }
     }
 
+    // Adds the difference between processed samples and the dry (input) samples of the effect
+    // to the given mix buffer.
+    fn mix_difference(&self, processed: &[(f32, f32)], mix_buf: &mut [(f32, f32)]) {
+        for (i, ((out_left, out_right), &(processed_left, processed_right))) in
+            mix_buf.iter_mut().zip(processed).enumerate()
+        {
+            let (dry_left, dry_right) = self.frame_samples.get(i).cloned().unwrap_or_default();
+            *out_left += processed_left - dry_left;
+            *out_right += processed_right - dry_right;
+        }
+    }
+
+    /// Sets new monitor mode of the effect. See [`MonitorMode`] docs for more info.
+    pub fn set_monitor(&mut self, monitor: MonitorMode) {
+        self.monitor = monitor;
+    }
+
+    /// Returns current monitor mode of the effect.
+    pub fn monitor(&self) -> MonitorMode {
+        self.monitor
+    }
+
     /// Returns current gain of effect.
     pub fn gain(&self) -> f32 {
         self.gain
@@ -195,6 +263,15 @@ impl BaseEffect {
         self.inputs.remove(index)
     }
 
+    /// Removes first effect input that uses the given sound source. Returns `None` if there is
+    /// no such input.
+    pub fn remove_input_by_source(&mut self, source: Handle<SoundSource>) -> Option<EffectInput> {
+        self.inputs
+            .iter()
+            .position(|input| input.source == source)
+            .map(|index| self.inputs.remove(index))
+    }
+
     /// Removes all inputs.
     pub fn clear_inputs(&mut self) {
         self.inputs.clear()
@@ -217,6 +294,31 @@ impl InputFilter {
             right: biquad,
         }
     }
+
+    /// Creates new low pass input filter. See [`Biquad::low_pass`] for more info.
+    pub fn low_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        Self::new(Biquad::low_pass(sample_rate, cutoff, q))
+    }
+
+    /// Creates new high pass input filter. See [`Biquad::high_pass`] for more info.
+    pub fn high_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        Self::new(Biquad::high_pass(sample_rate, cutoff, q))
+    }
+
+    /// Creates new band pass input filter. See [`Biquad::band_pass`] for more info.
+    pub fn band_pass(sample_rate: f32, center: f32, q: f32) -> Self {
+        Self::new(Biquad::band_pass(sample_rate, center, q))
+    }
+
+    /// Creates new notch input filter. See [`Biquad::notch`] for more info.
+    pub fn notch(sample_rate: f32, center: f32, q: f32) -> Self {
+        Self::new(Biquad::notch(sample_rate, center, q))
+    }
+
+    /// Creates new peaking EQ input filter. See [`Biquad::peaking`] for more info.
+    pub fn peaking(sample_rate: f32, center: f32, q: f32, gain_db: f32) -> Self {
+        Self::new(Biquad::peaking(sample_rate, center, q, gain_db))
+    }
 }
 
 impl InputFilter {
@@ -228,7 +330,7 @@ impl InputFilter {
 /// Input is a "reference" to a sound source. Samples of sound source will be
 /// either passed directly to effect or will be transformed by filter if one
 /// is set.
-#[derive(Default, Debug, Clone, Visit)]
+#[derive(Debug, Clone, Visit)]
 pub struct EffectInput {
     /// Handle of source from which effect will take samples each render frame.
     source: Handle<SoundSource>,
@@ -237,18 +339,41 @@ pub struct EffectInput {
     /// filtering is needed.
     filter: Option<InputFilter>,
 
+    /// Send gain of the input, it defines how much of the source's signal will be passed
+    /// to the effect.
+    #[visit(optional)] // Backward compatibility
+    gain: f32,
+
+    /// Stereo panning of the input signal, -1.0 - left, 0.0 - center, 1.0 - right.
+    #[visit(optional)] // Backward compatibility
+    pan: f32,
+
     /// Distance gain from last frame, it is used to interpolate distance gain from
     /// frame to frame to prevent clicks in output signal.
     #[visit(skip)]
     last_distance_gain: Option<f32>,
 }
 
+impl Default for EffectInput {
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+            filter: None,
+            gain: 1.0,
+            pan: 0.0,
+            last_distance_gain: None,
+        }
+    }
+}
+
 impl EffectInput {
     /// Creates new effect input using specified handle of sound source.
     pub fn direct(source: Handle<SoundSource>) -> Self {
         Self {
             source,
             filter: None,
+            gain: 1.0,
+            pan: 0.0,
             last_distance_gain: None,
         }
     }
@@ -263,6 +388,8 @@ impl EffectInput {
         Self {
             source,
             filter: Some(filter),
+            gain: 1.0,
+            pan: 0.0,
             last_distance_gain: None,
         }
     }
@@ -281,6 +408,51 @@ impl EffectInput {
     pub fn filter_mut(&mut self) -> Option<&mut InputFilter> {
         self.filter.as_mut()
     }
+
+    /// Sets desired send gain of the input. See [`Self::set_gain`] for more info.
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.set_gain(gain);
+        self
+    }
+
+    /// Sets new send gain of the input. It defines how much of the source's signal will be passed
+    /// to the effect, 0.0 - nothing, 1.0 - full signal (default).
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.max(0.0);
+    }
+
+    /// Returns current send gain of the input.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Sets desired stereo panning of the input. See [`Self::set_pan`] for more info.
+    pub fn with_pan(mut self, pan: f32) -> Self {
+        self.set_pan(pan);
+        self
+    }
+
+    /// Sets new stereo panning of the input, it places the signal passed to the effect in the
+    /// stereo field independently of the panning of the source. -1.0 - left, 0.0 - center
+    /// (default), 1.0 - right. Values are clamped to `[-1.0; 1.0]` range.
+    pub fn set_pan(&mut self, pan: f32) {
+        self.pan = pan.clamp(-1.0, 1.0);
+    }
+
+    /// Returns current stereo panning of the input.
+    pub fn pan(&self) -> f32 {
+        self.pan
+    }
+
+    // Calculates left and right gains using equal-power pan law. The gains are normalized so
+    // the center position does not change the signal.
+    fn pan_gains(&self) -> (f32, f32) {
+        let angle = (self.pan + 1.0) * std::f32::consts::FRAC_PI_4;
+        (
+            std::f32::consts::SQRT_2 * angle.cos(),
+            std::f32::consts::SQRT_2 * angle.sin(),
+        )
+    }
 }
 
 macro_rules! static_dispatch {
@@ -288,6 +460,7 @@ macro_rules! static_dispatch {
         match $self {
             Effect::Stub(v) => v.$func($($args),*),
             Effect::Reverb(v) => v.$func($($args),*),
+            Effect::Delay(v) => v.$func($($args),*),
         }
     };
 }
@@ -300,7 +473,29 @@ impl EffectRenderTrait for Effect {
         distance_model: DistanceModel,
         mix_buf: &mut [(f32, f32)],
     ) {
-        static_dispatch!(self, render, sources, listener, distance_model, mix_buf)
+        match self.monitor {
+            MonitorMode::Normal => {
+                static_dispatch!(self, render, sources, listener, distance_model, mix_buf)
+            }
+            MonitorMode::Difference => {
+                let mut processed = std::mem::take(&mut self.monitor_samples);
+                processed.clear();
+                processed.resize(mix_buf.len(), (0.0, 0.0));
+
+                static_dispatch!(
+                    self,
+                    render,
+                    sources,
+                    listener,
+                    distance_model,
+                    &mut processed
+                );
+
+                self.mix_difference(&processed, mix_buf);
+
+                self.monitor_samples = processed;
+            }
+        }
     }
 }
 
@@ -311,6 +506,7 @@ impl Deref for Effect {
         match self {
             Effect::Stub(v) => v,
             Effect::Reverb(v) => v,
+            Effect::Delay(v) => v,
         }
     }
 }
@@ -320,6 +516,240 @@ impl DerefMut for Effect {
         match self {
             Effect::Stub(v) => v,
             Effect::Reverb(v) => v,
+            Effect::Delay(v) => v,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource},
+        context::{DistanceModel, SAMPLE_RATE},
+        effects::{
+            delay::Delay, reverb::Reverb, BaseEffect, Effect, EffectInput, EffectRenderTrait,
+            InputFilter, MonitorMode,
+        },
+        listener::Listener,
+        source::{SoundSource, SoundSourceBuilder, Status},
+    };
+    use fyrox_core::{
+        pool::{Handle, Pool},
+        visitor::{Visit, Visitor},
+    };
+
+    // Must be longer than the shortest comb filter of the reverb to get some tail.
+    const FRAME_LEN: usize = 4096;
+
+    fn impulse_source(sources: &mut Pool<SoundSource>) -> Handle<SoundSource> {
+        let mut samples = vec![0.0; 44100];
+        samples[0] = 1.0;
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples,
+        })
+        .unwrap();
+        let mut source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_status(Status::Playing)
+            .build()
+            .unwrap();
+        source.render(FRAME_LEN);
+        sources.spawn(source)
+    }
+
+    fn render_effect(effect: &mut Effect, sources: &Pool<SoundSource>) -> Vec<(f32, f32)> {
+        let mut buf = vec![(0.0, 0.0); FRAME_LEN];
+        effect.render(
+            sources,
+            &Listener::new(),
+            DistanceModel::InverseDistance,
+            &mut buf,
+        );
+        buf
+    }
+
+    #[test]
+    fn test_difference_monitor_of_passthrough_is_silent() {
+        let mut sources = Pool::new();
+        let source = impulse_source(&mut sources);
+
+        // Delay without wet signal passes the input through as is.
+        let mut base = BaseEffect::default();
+        base.add_input(EffectInput::direct(source));
+        let mut delay = Delay::new(base);
+        delay.set_mix(0.0);
+        let mut normal_effect = Effect::Delay(delay);
+        let mut difference_effect = normal_effect.clone();
+        difference_effect.set_monitor(MonitorMode::Difference);
+
+        let normal = render_effect(&mut normal_effect, &sources);
+        assert_eq!(normal, normal_effect.frame_samples);
+        assert!(normal[0].0.abs() > 0.0);
+
+        let difference = render_effect(&mut difference_effect, &sources);
+        assert!(difference.iter().all(|(l, r)| *l == 0.0 && *r == 0.0));
+    }
+
+    #[test]
+    fn test_difference_monitor_of_reverb_outputs_tail_only() {
+        let mut sources = Pool::new();
+        let source = impulse_source(&mut sources);
+
+        let mut base = BaseEffect::default();
+        base.add_input(EffectInput::direct(source));
+        let mut normal_effect = Effect::Reverb(Reverb::new(base));
+        let mut difference_effect = normal_effect.clone();
+        difference_effect.set_monitor(MonitorMode::Difference);
+
+        let normal = render_effect(&mut normal_effect, &sources);
+        let difference = render_effect(&mut difference_effect, &sources);
+        let dry = &difference_effect.frame_samples;
+
+        // Difference must contain everything the reverb added, except the dry signal.
+        for ((normal, difference), dry) in normal.iter().zip(difference.iter()).zip(dry) {
+            assert!((normal.0 - dry.0 - difference.0).abs() < 1.0e-6);
+            assert!((normal.1 - dry.1 - difference.1).abs() < 1.0e-6);
+        }
+
+        // And there must be some tail after the impulse.
+        assert!(difference[1..]
+            .iter()
+            .any(|(l, r)| l.abs() > 0.0 || r.abs() > 0.0));
+    }
+
+    #[test]
+    fn test_delay_echoes_impulse() {
+        const DELAY_LEN: usize = 1000;
+
+        let mut sources = Pool::new();
+        let source = impulse_source(&mut sources);
+
+        let mut base = BaseEffect::default();
+        base.add_input(EffectInput::direct(source));
+        let mut delay = Delay::new(base);
+        delay.set_delay_time(DELAY_LEN as f32 / SAMPLE_RATE as f32);
+        delay.set_feedback(0.5);
+        delay.set_mix(1.0);
+        let mut effect = Effect::Delay(delay);
+
+        let buf = render_effect(&mut effect, &sources);
+        let (left, right) = effect.frame_samples[0];
+        assert!(left.abs() > 0.0);
+
+        for (i, (out_left, out_right)) in buf.iter().enumerate() {
+            // Mix is 1.0, so there is no dry signal at all, only echoes with decreasing amplitude.
+            let k = match i {
+                DELAY_LEN => 1.0,
+                i if i == 2 * DELAY_LEN => 0.5,
+                i if i == 3 * DELAY_LEN => 0.25,
+                i if i == 4 * DELAY_LEN => 0.125,
+                _ => 0.0,
+            };
+            assert!((out_left - k * left).abs() < 1.0e-6, "{}", i);
+            assert!((out_right - k * right).abs() < 1.0e-6, "{}", i);
+        }
+    }
+
+    #[test]
+    fn test_effect_input_gain() {
+        let mut sources = Pool::new();
+        let source = impulse_source(&mut sources);
+
+        let render_input = |input: EffectInput| {
+            let mut base = BaseEffect::default();
+            base.add_input(input);
+            base.render(&sources, &Listener::new(), Default::default(), FRAME_LEN);
+            base.frame_samples[0]
+        };
+
+        let (left, right) = render_input(EffectInput::direct(source));
+        assert!(left.abs() > 0.0);
+
+        // Unfiltered input.
+        let (direct_left, direct_right) = render_input(EffectInput::direct(source).with_gain(0.5));
+        assert!((direct_left - 0.5 * left).abs() < 1.0e-6);
+        assert!((direct_right - 0.5 * right).abs() < 1.0e-6);
+
+        // Filtered input, default filter passes the signal as is.
+        let (filtered_left, filtered_right) = render_input(
+            EffectInput::filtered(source, InputFilter::new(Default::default())).with_gain(0.25),
+        );
+        assert!((filtered_left - 0.25 * left).abs() < 1.0e-6);
+        assert!((filtered_right - 0.25 * right).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_effect_input_gain_backward_compatibility() {
+        // Simulate an input saved before the gain was added.
+        let mut visitor = Visitor::new();
+        {
+            let mut region = visitor.enter_region("Input").unwrap();
+            let mut source = Handle::<SoundSource>::NONE;
+            source.visit("Source", &mut region).unwrap();
+            let mut filter: Option<InputFilter> = None;
+            filter.visit("Filter", &mut region).unwrap();
+        }
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        let mut input = EffectInput::default();
+        input.visit("Input", &mut visitor).unwrap();
+        assert_eq!(input.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_effect_input_pan() {
+        let mut sources = Pool::new();
+        let source = impulse_source(&mut sources);
+
+        let render_input = |input: EffectInput| {
+            let mut base = BaseEffect::default();
+            base.add_input(input);
+            base.render(&sources, &Listener::new(), Default::default(), FRAME_LEN);
+            base.frame_samples[0]
+        };
+
+        let (left, right) = render_input(EffectInput::direct(source));
+        assert!(left.abs() > 0.0 && right.abs() > 0.0);
+
+        // Center pan does not change the signal.
+        let (center_left, center_right) = render_input(EffectInput::direct(source).with_pan(0.0));
+        assert!((center_left - left).abs() < 1.0e-6);
+        assert!((center_right - right).abs() < 1.0e-6);
+
+        // Fully left pan routes energy to the left channel only.
+        let (left_left, left_right) = render_input(EffectInput::direct(source).with_pan(-1.0));
+        assert!(left_left.abs() > left.abs());
+        assert!(left_right.abs() < 1.0e-6);
+
+        // Same for filtered inputs and the right channel.
+        let (right_left, right_right) = render_input(
+            EffectInput::filtered(source, InputFilter::new(Default::default())).with_pan(1.0),
+        );
+        assert!(right_left.abs() < 1.0e-6);
+        assert!(right_right.abs() > right.abs());
+    }
+
+    #[test]
+    fn test_remove_input_by_source() {
+        let mut sources = Pool::new();
+        let a = impulse_source(&mut sources);
+        let b = impulse_source(&mut sources);
+
+        let mut base = BaseEffect::default();
+        base.add_input(EffectInput::direct(a));
+        base.add_input(EffectInput::direct(b).with_gain(0.5));
+
+        let removed = base.remove_input_by_source(b).unwrap();
+        assert_eq!(removed.source(), b);
+        assert_eq!(removed.gain(), 0.5);
+        assert_eq!(base.inputs_ref().len(), 1);
+        assert_eq!(base.inputs_ref()[0].source(), a);
+
+        assert!(base.remove_input_by_source(b).is_none());
+        assert!(base.remove_input_by_source(Handle::NONE).is_none());
+        assert_eq!(base.inputs_ref().len(), 1);
+    }
+}
@@ -23,10 +23,11 @@
 //! # Known problems
 //!
 //! This reverberator has little "metallic" tone, but since this is one of the simplest reverberators this
-//! is acceptable. To remove this effect, more complex reverberator should be implemented.
+//! is acceptable. The tone could be reduced by modulation of the internal delay lines, see
+//! [`Reverb::set_modulation`].
 
 use crate::{
-    context::DistanceModel,
+    context::{DistanceModel, SAMPLE_RATE},
     dsp::filters::{AllPass, LpfComb},
     effects::{BaseEffect, EffectRenderTrait},
     listener::Listener,
@@ -51,6 +52,8 @@ struct ChannelReverb {
     lp_fb_comb_filters: Vec<LpfComb>,
     #[visit(rename = "APFilters")]
     all_pass_filters: Vec<AllPass>,
+    #[visit(skip)]
+    lfo_phase: f32,
 }
 
 /// 60 decibels
@@ -59,6 +62,9 @@ const DB60: f32 = 0.001;
 /// Sample rate for which this reverb was designed.
 const DESIGN_SAMPLE_RATE: u32 = 44100;
 
+/// Maximum change of the length of comb filters (in samples) at full modulation depth.
+const MAX_MODULATION_SAMPLES: f32 = 12.0;
+
 fn calculate_decay(len: usize, sample_rate: u32, decay_time: Duration) -> f32 {
     let time_len = len as f32 / sample_rate as f32;
     // Asymptotically goes to 1.0 by exponential law
@@ -83,6 +89,7 @@ impl ChannelReverb {
                 .iter()
                 .map(|len| AllPass::new(*len + stereo_spread as usize, 0.5))
                 .collect(),
+            lfo_phase: 0.0,
         }
     }
 
@@ -127,10 +134,22 @@ impl ChannelReverb {
         }
     }
 
-    fn feed(&mut self, sample: f32) -> f32 {
+    fn feed(&mut self, sample: f32, modulation_depth: f32, modulation_rate: f32) -> f32 {
         let mut result = 0.0;
-        for comb in self.lp_fb_comb_filters.iter_mut() {
-            result += comb.feed(sample);
+        if modulation_depth > 0.0 {
+            let amplitude = 0.5 * modulation_depth * MAX_MODULATION_SAMPLES;
+            let count = self.lp_fb_comb_filters.len() as f32;
+            for (i, comb) in self.lp_fb_comb_filters.iter_mut().enumerate() {
+                // Each filter has its own phase of the LFO, so the resonances won't move together.
+                let phase = self.lfo_phase + i as f32 / count;
+                let offset = amplitude * (1.0 + (std::f32::consts::TAU * phase).sin());
+                result += comb.feed_modulated(sample, offset);
+            }
+            self.lfo_phase = (self.lfo_phase + modulation_rate / self.sample_rate as f32).fract();
+        } else {
+            for comb in self.lp_fb_comb_filters.iter_mut() {
+                result += comb.feed(sample);
+            }
         }
         for allpass in self.all_pass_filters.iter_mut() {
             result = allpass.feed(result);
@@ -139,12 +158,73 @@ impl ChannelReverb {
     }
 }
 
+/// Reverb parameters for a typical environment. Presets are meant to be a starting point, which
+/// could be tweaked by ear later. See [`Reverb::from_preset`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverbPreset {
+    /// Reverberation time (RT60) - a time in which the reflections decay by 60 dB.
+    pub decay_time: Duration,
+    /// Cutoff frequency (in Hz) of the lowpass filter in comb filters.
+    pub cutoff_frequency: f32,
+    /// Stereo mixing of processed signal, see [`Reverb::set_wet`].
+    pub wet: f32,
+    /// Part of the input signal that is passed to the output without processing.
+    pub dry: f32,
+}
+
+impl ReverbPreset {
+    /// Small room with furniture, RT60 is ~0.4 seconds. Soft surfaces absorb high frequencies.
+    pub const SMALL_ROOM: Self = Self {
+        decay_time: Duration::from_millis(400),
+        cutoff_frequency: 6000.0,
+        wet: 0.9,
+        dry: 1.0,
+    };
+
+    /// Large concert hall, RT60 is ~2.5 seconds.
+    pub const LARGE_HALL: Self = Self {
+        decay_time: Duration::from_millis(2500),
+        cutoff_frequency: 8000.0,
+        wet: 1.0,
+        dry: 0.9,
+    };
+
+    /// Large cave with rock walls, RT60 is ~4 seconds. Distant reflections are dark and the
+    /// direct signal is quieter.
+    pub const CAVE: Self = Self {
+        decay_time: Duration::from_millis(4000),
+        cutoff_frequency: 4000.0,
+        wet: 0.75,
+        dry: 0.8,
+    };
+
+    /// Small tiled room, RT60 is ~1.2 seconds. Hard surfaces reflect high frequencies well.
+    pub const BATHROOM: Self = Self {
+        decay_time: Duration::from_millis(1200),
+        cutoff_frequency: 12000.0,
+        wet: 0.8,
+        dry: 1.0,
+    };
+
+    /// Returns cutoff frequency normalized by the sample rate of the sound context, see
+    /// [`Reverb::set_fc`].
+    pub fn fc(&self) -> f32 {
+        self.cutoff_frequency / SAMPLE_RATE as f32
+    }
+}
+
 /// See module docs.
 #[derive(Debug, Clone, Visit)]
 pub struct Reverb {
     base: BaseEffect,
     dry: f32,
     wet: f32,
+    #[visit(optional)]
+    decay_time: Duration,
+    #[visit(optional)]
+    modulation_depth: f32,
+    #[visit(optional)]
+    modulation_rate: f32,
     left: ChannelReverb,
     right: ChannelReverb,
 }
@@ -176,11 +256,44 @@ impl Reverb {
             base,
             dry: 1.0,
             wet: 1.0,
+            decay_time: Duration::from_secs(5),
+            modulation_depth: 0.0,
+            modulation_rate: 0.5,
             left: ChannelReverb::new(0, fc, feedback),
             right: ChannelReverb::new(23, fc, feedback),
         }
     }
 
+    /// Creates new instance of reverb effect with the parameters of the given preset.
+    pub fn from_preset(base: BaseEffect, preset: ReverbPreset) -> Self {
+        let mut reverb = Self::new(base);
+        reverb.set_decay_time(preset.decay_time);
+        reverb.set_fc(preset.fc());
+        reverb.set_wet(preset.wet);
+        reverb.set_dry(preset.dry);
+        reverb
+    }
+
+    /// Creates new reverb of a small room. See [`ReverbPreset::SMALL_ROOM`].
+    pub fn small_room(base: BaseEffect) -> Self {
+        Self::from_preset(base, ReverbPreset::SMALL_ROOM)
+    }
+
+    /// Creates new reverb of a large hall. See [`ReverbPreset::LARGE_HALL`].
+    pub fn large_hall(base: BaseEffect) -> Self {
+        Self::from_preset(base, ReverbPreset::LARGE_HALL)
+    }
+
+    /// Creates new reverb of a cave. See [`ReverbPreset::CAVE`].
+    pub fn cave(base: BaseEffect) -> Self {
+        Self::from_preset(base, ReverbPreset::CAVE)
+    }
+
+    /// Creates new reverb of a bathroom. See [`ReverbPreset::BATHROOM`].
+    pub fn bathroom(base: BaseEffect) -> Self {
+        Self::from_preset(base, ReverbPreset::BATHROOM)
+    }
+
     /// Sets how much of input signal should be passed to output without any processing.
     /// Default value is 1.0.
     pub fn set_dry(&mut self, dry: f32) {
@@ -192,6 +305,11 @@ impl Reverb {
         self.dry
     }
 
+    /// Returns dry part. Same as [`Self::get_dry`].
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
     /// Sets stereo mixing of processed signal.
     /// 0.0 - left is left, right is right
     /// 1.0 - right is left, left is right.
@@ -206,6 +324,11 @@ impl Reverb {
         self.wet
     }
 
+    /// Returns stereo mixing coefficient. Same as [`Self::get_wet`].
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
     /// Sets actual sample rate of effect. It was designed to 44100 Hz sampling rate.
     /// TODO: This shouldn't be in public API.
     pub fn set_sample_rate(&mut self, sample_rate: usize) {
@@ -216,10 +339,16 @@ impl Reverb {
     /// Sets desired duration of reverberation, the more size your environment has,
     /// the larger duration of reverberation should be.
     pub fn set_decay_time(&mut self, decay_time: Duration) {
+        self.decay_time = decay_time;
         self.left.set_decay_time(decay_time);
         self.right.set_decay_time(decay_time)
     }
 
+    /// Returns current duration of reverberation.
+    pub fn decay_time(&self) -> Duration {
+        self.decay_time
+    }
+
     /// Sets cutoff frequency for lowpass filter in comb filters. Basically this parameter defines
     /// "tone" of reflections, when frequency is higher - then more high frequencies will be in
     /// output signal, and vice versa. For example if you have environment with high absorption of
@@ -235,6 +364,31 @@ impl Reverb {
         self.left.set_fc(fc);
         self.right.set_fc(fc);
     }
+
+    /// Returns normalized cutoff frequency of lowpass filter in comb filters.
+    pub fn fc(&self) -> f32 {
+        self.left.fc
+    }
+
+    /// Sets modulation of the internal delay lines, slow modulation breaks up resonances of the
+    /// reverb and reduces its "metallic" tone. `depth` defines how much the delay lines are
+    /// modulated, it is clamped to `[0; 1]` range (0.0 - no modulation, default). `rate` is the
+    /// frequency (in Hz) of the modulation, it should be low (0.1 - 2 Hz) to not produce audible
+    /// pitch changes.
+    pub fn set_modulation(&mut self, depth: f32, rate: f32) {
+        self.modulation_depth = depth.clamp(0.0, 1.0);
+        self.modulation_rate = rate.max(0.0);
+    }
+
+    /// Returns modulation depth of the internal delay lines.
+    pub fn modulation_depth(&self) -> f32 {
+        self.modulation_depth
+    }
+
+    /// Returns modulation rate (in Hz) of the internal delay lines.
+    pub fn modulation_rate(&self) -> f32 {
+        self.modulation_rate
+    }
 }
 
 impl EffectRenderTrait for Reverb {
@@ -245,8 +399,13 @@ impl EffectRenderTrait for Reverb {
         distance_model: DistanceModel,
         mix_buf: &mut [(f32, f32)],
     ) {
-        self.base
-            .render(sources, listener, distance_model, mix_buf.len());
+        self.base.render_with_send_gain(
+            sources,
+            listener,
+            distance_model,
+            mix_buf.len(),
+            |source, distance_gain| source.calculate_reverb_send_gain(distance_gain),
+        );
 
         let wet1 = self.wet;
         let wet2 = 1.0 - self.wet;
@@ -257,8 +416,12 @@ impl EffectRenderTrait for Reverb {
             let mid = (left + right) * 0.5;
             let input = mid * Self::GAIN;
 
-            let processed_left = self.left.feed(input);
-            let processed_right = self.right.feed(input);
+            let processed_left = self
+                .left
+                .feed(input, self.modulation_depth, self.modulation_rate);
+            let processed_right =
+                self.right
+                    .feed(input, self.modulation_depth, self.modulation_rate);
 
             *out_left +=
                 self.gain * (processed_left * wet1 + processed_right * wet2 + self.dry * left);
@@ -281,3 +444,100 @@ impl DerefMut for Reverb {
         &mut self.base
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{
+        reverb::{Reverb, ReverbPreset},
+        BaseEffect,
+    };
+    use std::time::Duration;
+
+    // Feeds an impulse to the reverb and returns its response.
+    fn impulse_response(reverb: &mut Reverb, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let input = if i == 0 { 1.0 } else { 0.0 };
+                reverb
+                    .left
+                    .feed(input, reverb.modulation_depth, reverb.modulation_rate)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reverb_parameters_round_trip() {
+        let mut reverb = Reverb::new(BaseEffect::default());
+
+        reverb.set_decay_time(Duration::from_secs_f32(2.5));
+        reverb.set_fc(0.125);
+        reverb.set_wet(0.75);
+        reverb.set_dry(0.25);
+
+        assert_eq!(reverb.decay_time(), Duration::from_secs_f32(2.5));
+        assert_eq!(reverb.fc(), 0.125);
+        assert_eq!(reverb.wet(), 0.75);
+        assert_eq!(reverb.dry(), 0.25);
+    }
+
+    #[test]
+    fn test_reverb_presets() {
+        for (reverb, preset) in [
+            (
+                Reverb::small_room(Default::default()),
+                ReverbPreset::SMALL_ROOM,
+            ),
+            (
+                Reverb::large_hall(Default::default()),
+                ReverbPreset::LARGE_HALL,
+            ),
+            (Reverb::cave(Default::default()), ReverbPreset::CAVE),
+            (Reverb::bathroom(Default::default()), ReverbPreset::BATHROOM),
+        ] {
+            assert_eq!(reverb.decay_time(), preset.decay_time);
+            assert_eq!(reverb.fc(), preset.fc());
+            assert_eq!(reverb.wet(), preset.wet);
+            assert_eq!(reverb.dry(), preset.dry);
+        }
+
+        // Larger environments must reverberate longer.
+        assert!(ReverbPreset::SMALL_ROOM.decay_time < ReverbPreset::BATHROOM.decay_time);
+        assert!(ReverbPreset::BATHROOM.decay_time < ReverbPreset::LARGE_HALL.decay_time);
+        assert!(ReverbPreset::LARGE_HALL.decay_time < ReverbPreset::CAVE.decay_time);
+    }
+
+    #[test]
+    fn test_reverb_modulation() {
+        const LEN: usize = 4096;
+
+        // Returns impulse responses of the reverb at two moments of time, the time is defined by
+        // the phase of the modulation.
+        let responses = |depth: f32| {
+            let mut early = Reverb::new(BaseEffect::default());
+            early.set_modulation(depth, 1.0);
+            let mut late = early.clone();
+            late.left.lfo_phase = 0.25;
+            (
+                impulse_response(&mut early, LEN),
+                impulse_response(&mut late, LEN),
+            )
+        };
+
+        // Without modulation the response is static.
+        let (early, late) = responses(0.0);
+        assert_eq!(early, late);
+
+        // With modulation it varies over time.
+        let (early, late) = responses(1.0);
+        assert_ne!(early, late);
+
+        // But only slightly.
+        let energy = early.iter().map(|s| s * s).sum::<f32>();
+        let difference = early
+            .iter()
+            .zip(late.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>();
+        assert!(difference < 0.5 * energy);
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! Sound engine manages contexts, feeds output device with data.
 
-use crate::{context::SoundContext, device};
+use crate::{context::SoundContext, device, error::SoundError};
 use fyrox_core::visitor::{Visit, VisitResult, Visitor};
 use std::sync::{Arc, Mutex};
 
@@ -13,6 +13,8 @@ use std::sync::{Arc, Mutex};
 pub struct SoundEngine {
     contexts: Vec<SoundContext>,
     master_gain: f32,
+    // An error of the output device, that caused the fallback to silent dummy device.
+    device_error: Option<SoundError>,
 }
 
 impl SoundEngine {
@@ -33,19 +35,32 @@ impl SoundEngine {
         let engine = Arc::new(Mutex::new(Self {
             contexts: Default::default(),
             master_gain: 1.0,
+            device_error: None,
         }));
 
         // Run the default output device. Internally it creates separate thread, so we have
         // to share sound engine instance with it, this is the only reason why it is wrapped
         // in Arc<Mutex<>>
-        device::run_device(headless, 4 * SoundContext::SAMPLES_PER_CHANNEL as u32, {
-            let state = engine.clone();
-            move |buf| {
-                if let Ok(mut state) = state.lock() {
-                    state.render_inner(buf);
+        device::run_device(
+            headless,
+            4 * SoundContext::SAMPLES_PER_CHANNEL as u32,
+            {
+                let state = engine.clone();
+                move |buf| {
+                    if let Ok(mut state) = state.lock() {
+                        state.render_inner(buf);
+                    }
                 }
-            }
-        });
+            },
+            {
+                let state = engine.clone();
+                move |error| {
+                    if let Ok(mut state) = state.lock() {
+                        state.device_error = Some(error);
+                    }
+                }
+            },
+        );
 
         engine
     }
@@ -56,6 +71,7 @@ impl SoundEngine {
         Arc::new(Mutex::new(Self {
             contexts: Default::default(),
             master_gain: 1.0,
+            device_error: None,
         }))
     }
 
@@ -99,6 +115,14 @@ impl SoundEngine {
         self.master_gain
     }
 
+    /// Takes an error of the output device, that caused the engine to fall back to silent dummy
+    /// device. The device is created on a separate thread, so the error may appear some time after
+    /// the engine was created. Returns `None` if the output device works fine (or if the error was
+    /// already taken).
+    pub fn take_device_error(&mut self) -> Option<SoundError> {
+        self.device_error.take()
+    }
+
     /// Returns the length of buf to be passed to [`Self::render()`].
     pub fn render_buffer_len() -> usize {
         SoundContext::SAMPLES_PER_CHANNEL
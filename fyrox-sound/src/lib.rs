@@ -67,6 +67,7 @@
 extern crate winapi;
 
 pub mod buffer;
+pub mod bus;
 pub mod context;
 
 pub mod dsp;
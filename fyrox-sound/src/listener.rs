@@ -14,6 +14,8 @@ use fyrox_core::visitor::{Visit, VisitResult, Visitor};
 pub struct Listener {
     basis: Matrix3<f32>,
     position: Vector3<f32>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
 }
 
 impl Default for Listener {
@@ -27,6 +29,7 @@ impl Listener {
         Self {
             basis: Matrix3::identity(),
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
         }
     }
 
@@ -83,6 +86,17 @@ impl Listener {
         self.position
     }
 
+    /// Sets current velocity (in units per second) of the listener in world space. It is used to
+    /// calculate doppler effect, see [`crate::source::SoundSource::set_doppler_factor`].
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) {
+        self.velocity = velocity;
+    }
+
+    /// Returns current velocity of the listener.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
     /// Returns up axis from basis.
     pub fn up_axis(&self) -> Vector3<f32> {
         self.basis.up()
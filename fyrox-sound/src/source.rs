@@ -30,18 +30,43 @@
 #![allow(clippy::float_cmp)]
 
 use crate::{
-    buffer::{streaming::StreamingBuffer, SoundBufferResource, SoundBufferState},
-    context::DistanceModel,
+    buffer::{streaming::StreamingBuffer, LoopRegion, SoundBufferResource, SoundBufferState},
+    bus::BusGraph,
+    context::{DistanceModel, SAMPLE_RATE},
+    dsp::fade::FadeCurve,
     error::SoundError,
     listener::Listener,
 };
 use fyrox_core::{
     algebra::Vector3,
+    curve::Curve,
     reflect::prelude::*,
     visitor::{Visit, VisitResult, Visitor},
 };
 use fyrox_resource::ResourceState;
-use std::time::Duration;
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Speed of sound (in meters per second) in the air, it is used to calculate doppler effect.
+pub const SPEED_OF_SOUND: f32 = 343.3;
+
+/// Amount of samples (per channel) used to cross-fade between old and new playback positions when
+/// smooth seek is enabled, it is 10 ms at 44100 Hz.
+pub const SMOOTH_SEEK_SAMPLE_COUNT: usize = 441;
+
+/// Amount of samples (per channel) in which a source fades out when it is virtualized by the audio
+/// budget of a context (and fades in when it becomes audible again), it is 10 ms at 44100 Hz.
+pub const VIRTUALIZATION_FADE_SAMPLE_COUNT: usize = 441;
+
+// Shared between all contexts, so instance stamps are always unique.
+static INSTANCE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn next_instance_stamp() -> u64 {
+    INSTANCE_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
 
 /// Status (state) of sound source.
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Reflect, Visit)]
@@ -121,6 +146,18 @@ pub struct SoundSource {
     max_distance: f32,
     #[reflect(min_value = 0.0, step = 0.05)]
     rolloff_factor: f32,
+    // Emission direction of the source in world space, it is the axis of the emission cone.
+    #[visit(optional)]
+    direction: Vector3<f32>,
+    #[reflect(min_value = 0.0, max_value = 6.28318, step = 0.05)]
+    #[visit(optional)]
+    cone_inner_angle: f32,
+    #[reflect(min_value = 0.0, max_value = 6.28318, step = 0.05)]
+    #[visit(optional)]
+    cone_outer_angle: f32,
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[visit(optional)]
+    cone_outer_gain: f32,
     // Some data that needed for iterative overlap-save convolution.
     #[reflect(hidden)]
     #[visit(skip)]
@@ -134,6 +171,93 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    // A curve that maps distance attenuation (0 - no attenuation, 1 - full attenuation) to
+    // the gain of the signal sent to reverb effects. `None` means that the send is not automatic.
+    #[reflect(hidden)]
+    #[visit(optional)]
+    auto_reverb_send: Option<Curve>,
+    // Name of an audio bus to which the source is routed.
+    #[visit(optional)]
+    bus: String,
+    // True if the source must be re-routed by the context, because its bus has changed.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) bus_changed: bool,
+    // Effective gain of the bus of the source, it is cached by the context on routing.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) bus_gain: f32,
+    // A region (in samples per channel) of the buffer that should be repeated while looping. It
+    // overrides the loop region of the buffer.
+    #[reflect(hidden)]
+    #[visit(optional)]
+    loop_region: Option<Range<u64>>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[visit(optional)]
+    doppler_factor: f32,
+    // Pitch multiplier caused by doppler effect, it is updated right before rendering.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) doppler_pitch: f64,
+    #[visit(optional)]
+    smooth_seek: bool,
+    // Samples rendered from the playback position before the last seek, they're faded out while
+    // the samples from the new position are faded in.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    seek_fade: Vec<(f32, f32)>,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    seek_fade_pos: usize,
+    // Muted source is silenced at the mix stage, its gain stays untouched.
+    #[visit(optional)]
+    muted: bool,
+    // Priority of the source for the audio budget of the context, higher priority sources are
+    // less likely to be virtualized.
+    #[visit(optional)]
+    priority: u8,
+    // Virtual source continues to play, but it is not spatialized and mixed, it is set by the
+    // audio budget of the context.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) is_virtual: bool,
+    // Position of the virtualization fade in `[0; 1]` range, where 0 is silence and 1 is full gain.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    virtual_fade: f32,
+    // Sequential number of the moment when the source was started (resuming after pause does not
+    // change it), it is used by the context to find the oldest instances of a buffer. `None` if the
+    // source was never started.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) instance_stamp: Option<u64>,
+    // Duration of the gain ramp from silence to full gain when the source starts playing.
+    #[visit(optional)]
+    fade_in: Duration,
+    // Duration of the gain ramp from full gain to silence when the source is stopped.
+    #[visit(optional)]
+    fade_out: Duration,
+    // Shape of the fade in and fade out ramps.
+    #[visit(optional)]
+    fade_curve: FadeCurve,
+    // Current position of the fade envelope on the fade curve in `[0; 1]` range, where 0 is silence
+    // and 1 is full gain.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    envelope: f32,
+    // True if the source was asked to stop, but its fade out is not finished yet.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) fading_out: bool,
+    // Source without direct output is not mixed to the output, its signal is passed to the effects
+    // only.
+    #[visit(optional)]
+    direct_output: bool,
+    // Distance model that is used instead of the distance model of the context.
+    #[visit(optional)]
+    distance_model_override: Option<DistanceModel>,
 }
 
 impl Default for SoundSource {
@@ -159,10 +283,37 @@ impl Default for SoundSource {
             position: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            direction: Vector3::new(0.0, 0.0, 1.0),
+            cone_inner_angle: std::f32::consts::TAU,
+            cone_outer_angle: std::f32::consts::TAU,
+            cone_outer_gain: 0.0,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            auto_reverb_send: None,
+            bus: BusGraph::PRIMARY_BUS.to_owned(),
+            bus_changed: true,
+            bus_gain: 1.0,
+            loop_region: None,
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            doppler_factor: 0.0,
+            doppler_pitch: 1.0,
+            smooth_seek: false,
+            seek_fade: Default::default(),
+            seek_fade_pos: 0,
+            muted: false,
+            priority: 0,
+            is_virtual: false,
+            virtual_fade: 1.0,
+            instance_stamp: None,
+            fade_in: Default::default(),
+            fade_out: Default::default(),
+            fade_curve: Default::default(),
+            envelope: 1.0,
+            fading_out: false,
+            direct_output: true,
+            distance_model_override: None,
         }
     }
 }
@@ -183,6 +334,22 @@ impl SoundSource {
         self.name.to_owned()
     }
 
+    /// Sets the name of an audio bus to which the source will be routed. If there is no bus with
+    /// such name, the source will be routed to the primary bus. See [`crate::bus`] docs for more
+    /// info.
+    pub fn set_bus<N: AsRef<str>>(&mut self, bus: N) {
+        let bus = bus.as_ref();
+        if self.bus != bus {
+            self.bus = bus.to_owned();
+            self.bus_changed = true;
+        }
+    }
+
+    /// Returns the name of an audio bus to which the source is routed.
+    pub fn bus(&self) -> &str {
+        &self.bus
+    }
+
     /// Sets spatial blend factor. It defines how much the source will be 2D and 3D sound at the same
     /// time. Set it to 0.0 to make the sound fully 2D and 1.0 to make it fully 3D. Middle values
     /// will make sound proportionally 2D and 3D at the same time.
@@ -291,8 +458,17 @@ impl SoundSource {
         self.status
     }
 
-    /// Changes status to `Playing`.
+    /// Changes status to `Playing`. If the source has fade in (see [`Self::set_fade_in`]) and it
+    /// was not playing, its gain will ramp up from silence.
     pub fn play(&mut self) -> &mut Self {
+        if self.status != Status::Playing {
+            self.envelope = if self.fade_in.is_zero() { 1.0 } else { 0.0 };
+        }
+        if self.status == Status::Stopped || self.instance_stamp.is_none() {
+            self.instance_stamp = Some(next_instance_stamp());
+        }
+        // Cancel pending fade out, the gain ramps back up from the current level.
+        self.fading_out = false;
         self.status = Status::Playing;
         self
     }
@@ -315,6 +491,30 @@ impl SoundSource {
         self.looping
     }
 
+    /// Sets a region `[start_sample, end_sample)` (in samples per channel) of the buffer that will be
+    /// repeated while the source is looping, it overrides the loop region of the buffer (see
+    /// [`crate::buffer::generic::GenericBuffer::loop_region`]). It is useful to loop a sustain part of
+    /// a sound, for example an engine idle. The sound plays from the beginning and wraps within the
+    /// region once it reaches the region's end. `None` resets the region.
+    ///
+    /// # Notes
+    ///
+    /// The region is clamped to the bounds of the buffer when rendering, so it always contains at
+    /// least two samples. Loop regions are respected only by generic buffers, streaming buffers
+    /// ignore them.
+    pub fn set_loop_region(&mut self, region: Option<(usize, usize)>) -> &mut Self {
+        self.loop_region = region.map(|(start, end)| start as u64..end as u64);
+        self
+    }
+
+    /// Returns the loop region of the source (if any), the region is returned as is, without any
+    /// clamping.
+    pub fn loop_region(&self) -> Option<(usize, usize)> {
+        self.loop_region
+            .as_ref()
+            .map(|r| (r.start as usize, r.end as usize))
+    }
+
     /// Sets sound pitch. Defines "tone" of sounds. Default value is 1.0
     pub fn set_pitch(&mut self, pitch: f64) -> &mut Self {
         self.pitch = pitch.abs();
@@ -326,9 +526,62 @@ impl SoundSource {
         self.pitch
     }
 
-    /// Stops sound source. Automatically rewinds streaming buffers.
+    /// Stops sound source. Automatically rewinds streaming buffers. If the source has fade out (see
+    /// [`Self::set_fade_out`]) and it is playing, it continues to play while its gain ramps down and
+    /// only then it is stopped.
     pub fn stop(&mut self) -> Result<(), SoundError> {
+        if self.status == Status::Playing && !self.fade_out.is_zero() {
+            self.fading_out = true;
+            Ok(())
+        } else {
+            self.stop_immediately()
+        }
+    }
+
+    /// Sets duration of the gain ramp from silence to full gain, that is applied when the source
+    /// starts playing. It prevents clicks when a sound starts in the middle of a loud signal. Zero
+    /// duration disables fade in.
+    pub fn set_fade_in(&mut self, fade_in: Duration) -> &mut Self {
+        self.fade_in = fade_in;
+        self
+    }
+
+    /// Returns duration of fade in.
+    pub fn fade_in(&self) -> Duration {
+        self.fade_in
+    }
+
+    /// Sets duration of the gain ramp from full gain to silence, that is applied when the source is
+    /// stopped (see [`Self::stop`]). Zero duration disables fade out.
+    pub fn set_fade_out(&mut self, fade_out: Duration) -> &mut Self {
+        self.fade_out = fade_out;
+        self
+    }
+
+    /// Returns duration of fade out.
+    pub fn fade_out(&self) -> Duration {
+        self.fade_out
+    }
+
+    /// Sets the shape of the fade in and fade out ramps. Fade out follows the same curve backwards,
+    /// see [`FadeCurve`] docs for available shapes. Default is [`FadeCurve::Linear`].
+    pub fn set_fade_curve(&mut self, fade_curve: FadeCurve) -> &mut Self {
+        self.fade_curve = fade_curve;
+        self
+    }
+
+    /// Returns the shape of the fade in and fade out ramps.
+    pub fn fade_curve(&self) -> FadeCurve {
+        self.fade_curve
+    }
+
+    /// Stops sound source right away, ignoring its fade out. Automatically rewinds streaming
+    /// buffers.
+    pub fn stop_immediately(&mut self) -> Result<(), SoundError> {
+        self.fading_out = false;
+        self.envelope = 1.0;
         self.status = Status::Stopped;
+        self.seek_fade.clear();
 
         self.buf_read_pos = 0.0;
         self.playback_pos = 0.0;
@@ -353,6 +606,142 @@ impl SoundSource {
         self.position
     }
 
+    /// Sets current velocity (in units per second) of the source in world space. It is used to
+    /// calculate doppler effect, see [`Self::set_doppler_factor`].
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) -> &mut Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Returns current velocity of the source.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Sets doppler factor of the source. Doppler effect changes pitch of the source depending on
+    /// relative velocity of the source and the listener: the pitch is higher when they are moving
+    /// towards each other and lower when they're moving away. The factor scales both velocities,
+    /// so values larger than 1.0 exaggerate the effect. 0.0 disables the effect (default).
+    pub fn set_doppler_factor(&mut self, factor: f32) -> &mut Self {
+        self.doppler_factor = factor.max(0.0);
+        self
+    }
+
+    /// Returns doppler factor of the source.
+    pub fn doppler_factor(&self) -> f32 {
+        self.doppler_factor
+    }
+
+    /// Enables or disables smooth seek. Instant change of playback position (see
+    /// [`Self::set_playback_time`]) of a playing source usually causes a discontinuity in the
+    /// output signal which is heard as a click. When smooth seek is enabled, the source cross-fades
+    /// the signal at the old position with the signal at the new position over
+    /// [`SMOOTH_SEEK_SAMPLE_COUNT`] samples. It is useful for scrubbing and frequent seeking.
+    /// Disabled by default.
+    pub fn set_smooth_seek(&mut self, smooth_seek: bool) -> &mut Self {
+        self.smooth_seek = smooth_seek;
+        if !smooth_seek {
+            self.seek_fade.clear();
+        }
+        self
+    }
+
+    /// Returns true if smooth seek is enabled, false - otherwise.
+    pub fn is_smooth_seek(&self) -> bool {
+        self.smooth_seek
+    }
+
+    /// Mutes or unmutes the source. Muted source continues to play, but its signal is silenced at
+    /// the mix stage (it won't be passed to the output and effects). Unlike setting gain to zero,
+    /// muting preserves the gain of the source, so the actual gain will be used once the source is
+    /// unmuted.
+    pub fn set_muted(&mut self, muted: bool) -> &mut Self {
+        self.muted = muted;
+        self
+    }
+
+    /// Returns true if the source is muted, false - otherwise.
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Sets priority of the source, it is used by the audio budget of the context (see
+    /// [`crate::context::AudioBudget`]) to decide which sources should be played when there are
+    /// more playing sources than the budget allows. Default is 0.
+    pub fn set_priority(&mut self, priority: u8) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns priority of the source.
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// Enables or disables direct output of the source. When disabled, the signal of the source
+    /// is not mixed to the output directly, but it is still passed to the effects the source is
+    /// attached to. It could be used to make pure effect sends (for example, reverb return without
+    /// dry signal). Default is true.
+    pub fn set_direct_output(&mut self, direct_output: bool) -> &mut Self {
+        self.direct_output = direct_output;
+        self
+    }
+
+    /// Returns true if the source is mixed to the output directly, false - otherwise.
+    pub fn is_direct_output(&self) -> bool {
+        self.direct_output
+    }
+
+    /// Sets distance model of the source, that will be used instead of the distance model of the
+    /// context (see [`crate::context::State::set_distance_model`]). It allows mixing sounds with
+    /// different falloff (for example, 2D and 3D sounds) in a single context. `None` means that the
+    /// distance model of the context will be used (default).
+    pub fn set_distance_model_override(
+        &mut self,
+        distance_model: Option<DistanceModel>,
+    ) -> &mut Self {
+        self.distance_model_override = distance_model;
+        self
+    }
+
+    /// Returns distance model of the source that overrides the distance model of the context.
+    pub fn distance_model_override(&self) -> Option<DistanceModel> {
+        self.distance_model_override
+    }
+
+    /// Returns true if the source was virtualized by the audio budget of the context. Virtual source
+    /// continues to play (its playback position is advanced), but it is not audible after a short
+    /// fade out (see [`VIRTUALIZATION_FADE_SAMPLE_COUNT`]).
+    pub fn is_virtual(&self) -> bool {
+        self.is_virtual
+    }
+
+    /// Calculates pitch multiplier caused by doppler effect for the given listener. Returns 1.0 if
+    /// doppler effect is disabled for the source. The multiplier is scaled by the spatial blend
+    /// factor, so fully 2D sources are not affected.
+    pub fn calculate_doppler_pitch(&self, listener: &Listener) -> f64 {
+        if self.doppler_factor == 0.0 {
+            return 1.0;
+        }
+
+        let direction = match (listener.position() - self.position).try_normalize(f32::EPSILON) {
+            Some(direction) => direction,
+            None => return 1.0,
+        };
+
+        // Clamp projected velocities, so the multiplier will be finite even if something is moving
+        // faster than sound.
+        let max_speed = SPEED_OF_SOUND * 0.5;
+        let source_speed =
+            (self.velocity.dot(&direction) * self.doppler_factor).clamp(-max_speed, max_speed);
+        let listener_speed = (listener.velocity().dot(&direction) * self.doppler_factor)
+            .clamp(-max_speed, max_speed);
+
+        let doppler = (SPEED_OF_SOUND - listener_speed) / (SPEED_OF_SOUND - source_speed);
+
+        (1.0 + (doppler - 1.0) * self.spatial_blend) as f64
+    }
+
     /// Sets radius of imaginable sphere around source in which no distance attenuation is applied.
     pub fn set_radius(&mut self, radius: f32) -> &mut Self {
         self.radius = radius;
@@ -391,27 +780,134 @@ impl SoundSource {
         self.max_distance
     }
 
+    /// Sets emission direction of the source in world space. The direction is the axis of the
+    /// emission cone (see [`Self::set_cone_inner_angle`]), it does not need to be normalized.
+    pub fn set_direction(&mut self, direction: Vector3<f32>) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns emission direction of the source.
+    pub fn direction(&self) -> Vector3<f32> {
+        self.direction
+    }
+
+    /// Sets full angle (in radians) of the inner emission cone. The listener inside the inner cone
+    /// hears the source without any directional attenuation. Default is `2π`, which makes the
+    /// source omnidirectional.
+    pub fn set_cone_inner_angle(&mut self, angle: f32) -> &mut Self {
+        self.cone_inner_angle = angle.clamp(0.0, std::f32::consts::TAU);
+        self
+    }
+
+    /// Returns full angle (in radians) of the inner emission cone.
+    pub fn cone_inner_angle(&self) -> f32 {
+        self.cone_inner_angle
+    }
+
+    /// Sets full angle (in radians) of the outer emission cone. Between the inner and the outer
+    /// cones the gain is interpolated from 1.0 to [`Self::cone_outer_gain`], outside of the outer
+    /// cone the outer gain is used.
+    pub fn set_cone_outer_angle(&mut self, angle: f32) -> &mut Self {
+        self.cone_outer_angle = angle.clamp(0.0, std::f32::consts::TAU);
+        self
+    }
+
+    /// Returns full angle (in radians) of the outer emission cone.
+    pub fn cone_outer_angle(&self) -> f32 {
+        self.cone_outer_angle
+    }
+
+    /// Sets gain that is applied to the source when the listener is outside of the outer emission
+    /// cone. The value is clamped to `[0.0; 1.0]` range.
+    pub fn set_cone_outer_gain(&mut self, gain: f32) -> &mut Self {
+        self.cone_outer_gain = gain.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns gain that is applied outside of the outer emission cone.
+    pub fn cone_outer_gain(&self) -> f32 {
+        self.cone_outer_gain
+    }
+
+    /// Calculates directional attenuation of the source for the given listener. Returns 1.0 if the
+    /// listener is inside the inner cone (or if the source is omnidirectional) and the outer gain if
+    /// the listener is outside of the outer cone.
+    pub fn calculate_cone_gain(&self, listener: &Listener) -> f32 {
+        let inner_half_angle = self.cone_inner_angle * 0.5;
+        let outer_half_angle = self.cone_outer_angle.max(self.cone_inner_angle) * 0.5;
+        if inner_half_angle >= std::f32::consts::PI {
+            return 1.0;
+        }
+
+        let (direction, to_listener) = match (
+            self.direction.try_normalize(f32::EPSILON),
+            (listener.position() - self.position).try_normalize(f32::EPSILON),
+        ) {
+            (Some(direction), Some(to_listener)) => (direction, to_listener),
+            _ => return 1.0,
+        };
+
+        let angle = direction.dot(&to_listener).clamp(-1.0, 1.0).acos();
+        if angle <= inner_half_angle {
+            1.0
+        } else if angle >= outer_half_angle {
+            self.cone_outer_gain
+        } else {
+            let t = (angle - inner_half_angle) / (outer_half_angle - inner_half_angle);
+            1.0 + (self.cone_outer_gain - 1.0) * t
+        }
+    }
+
     // Distance models were taken from OpenAL Specification because it looks like they're
     // standard in industry and there is no need to reinvent it.
     // https://www.openal.org/documentation/openal-1.1-specification.pdf
+    //
+    // The distance model of the context is used only if the source does not override it.
+    // Directional attenuation of the emission cone is included as well, so every consumer of the
+    // distance gain (renderers, effects, audio budget) takes the cone into account.
     pub(crate) fn calculate_distance_gain(
         &self,
         listener: &Listener,
         distance_model: DistanceModel,
     ) -> f32 {
-        let distance = self
-            .position
-            .metric_distance(&listener.position())
-            .clamp(self.radius, self.max_distance);
-        match distance_model {
-            DistanceModel::None => 1.0,
-            DistanceModel::InverseDistance => {
-                self.radius / (self.radius + self.rolloff_factor * (distance - self.radius))
-            }
-            DistanceModel::LinearDistance => {
-                1.0 - self.radius * (distance - self.radius) / (self.max_distance - self.radius)
+        let distance_model = self.distance_model_override.unwrap_or(distance_model);
+        distance_model.attenuation(
+            self.position.metric_distance(&listener.position()),
+            self.radius,
+            self.rolloff_factor,
+            self.max_distance,
+        ) * self.calculate_cone_gain(listener)
+    }
+
+    /// Enables or disables automatic reverb send. When enabled, the amount of signal sent to reverb
+    /// effects depends on the distance between the source and the listener: distant sources will
+    /// send more signal to reverb (more reflections) than close ones. Given curve maps distance
+    /// attenuation (0.0 - no attenuation, 1.0 - full attenuation) to the send gain. Empty curve
+    /// means linear mapping.
+    pub fn set_auto_reverb_send(&mut self, enabled: bool, curve: Curve) -> &mut Self {
+        self.auto_reverb_send = if enabled { Some(curve) } else { None };
+        self
+    }
+
+    /// Returns automatic reverb send curve if automatic reverb send is enabled.
+    pub fn auto_reverb_send(&self) -> Option<&Curve> {
+        self.auto_reverb_send.as_ref()
+    }
+
+    /// Calculates gain of the signal sent to reverb effects using given distance gain. If
+    /// automatic reverb send is disabled, the distance gain is returned unchanged.
+    pub(crate) fn calculate_reverb_send_gain(&self, distance_gain: f32) -> f32 {
+        match self.auto_reverb_send.as_ref() {
+            Some(curve) => {
+                let attenuation = (1.0 - distance_gain).clamp(0.0, 1.0);
+                if curve.is_empty() {
+                    attenuation
+                } else {
+                    curve.value_at(attenuation)
+                }
             }
-            DistanceModel::ExponentDistance => (distance / self.radius).powf(-self.rolloff_factor),
+            None => distance_gain,
         }
     }
 
@@ -446,8 +942,11 @@ impl SoundSource {
 
     /// Sets playback duration.
     pub fn set_playback_time(&mut self, time: Duration) {
-        if let Some(buffer) = self.buffer.as_ref() {
+        if let Some(buffer) = self.buffer.clone() {
             let mut buffer = buffer.data_ref();
+            if self.smooth_seek && self.status == Status::Playing && !buffer.is_empty() {
+                self.render_seek_fade(&mut *buffer);
+            }
             if let SoundBufferState::Streaming(ref mut streaming) = *buffer {
                 // Make sure decoder is at right position.
                 streaming.time_seek(time);
@@ -488,15 +987,135 @@ impl SoundSource {
         }
         // Fill the remaining part of frame_samples.
         self.frame_samples.resize(amount, (0.0, 0.0));
+
+        self.apply_seek_fade();
+        self.apply_envelope();
+    }
+
+    fn envelope_step(duration: Duration) -> f32 {
+        1.0 / (duration.as_secs_f32() * SAMPLE_RATE as f32).max(1.0)
+    }
+
+    // Applies fade in / fade out envelope to the rendered samples.
+    fn apply_envelope(&mut self) {
+        if self.fading_out {
+            let step = Self::envelope_step(self.fade_out);
+            for (left, right) in self.frame_samples.iter_mut() {
+                self.envelope = (self.envelope - step).max(0.0);
+                let gain = self.fade_curve.evaluate(self.envelope);
+                *left *= gain;
+                *right *= gain;
+            }
+            if self.envelope <= 0.0 {
+                // Source is silent now, it can be stopped.
+                let _ = self.stop_immediately();
+            }
+        } else if self.envelope < 1.0 {
+            let step = Self::envelope_step(self.fade_in);
+            for (left, right) in self.frame_samples.iter_mut() {
+                self.envelope = (self.envelope + step).min(1.0);
+                let gain = self.fade_curve.evaluate(self.envelope);
+                *left *= gain;
+                *right *= gain;
+            }
+        }
+    }
+
+    // Ramps the gain of the rendered samples to silence if the source is virtual, or back to full
+    // gain otherwise. Returns false if the source is completely silent because of virtualization.
+    pub(crate) fn apply_virtualization_fade(&mut self) -> bool {
+        let target = if self.is_virtual { 0.0 } else { 1.0 };
+        if self.virtual_fade == target {
+            return !self.is_virtual;
+        }
+
+        let step = 1.0 / VIRTUALIZATION_FADE_SAMPLE_COUNT as f32;
+        for (left, right) in self.frame_samples.iter_mut() {
+            self.virtual_fade = if self.is_virtual {
+                (self.virtual_fade - step).max(0.0)
+            } else {
+                (self.virtual_fade + step).min(1.0)
+            };
+            let gain = FadeCurve::SCurve.evaluate(self.virtual_fade);
+            *left *= gain;
+            *right *= gain;
+        }
+
+        true
+    }
+
+    // Renders a portion of the signal at current playback position, it will be faded out after
+    // the seek. Pending fade (if any) is mixed in, so rapid seeking is smooth too.
+    fn render_seek_fade(&mut self, buffer: &mut SoundBufferState) {
+        let frame_samples = std::mem::take(&mut self.frame_samples);
+        self.render_playing(buffer, SMOOTH_SEEK_SAMPLE_COUNT);
+        // The source could reach the end of the buffer, the signal is faded out to silence then.
+        self.status = Status::Playing;
+        self.frame_samples
+            .resize(SMOOTH_SEEK_SAMPLE_COUNT, (0.0, 0.0));
+        self.apply_seek_fade();
+        self.seek_fade = std::mem::replace(&mut self.frame_samples, frame_samples);
+        self.seek_fade_pos = 0;
+    }
+
+    // Cross-fades rendered samples with the samples at the playback position before the seek. The
+    // signals at the old and the new positions are usually unrelated, so equal power cross-fade is
+    // used to keep the loudness during the transition.
+    fn apply_seek_fade(&mut self) {
+        let len = self.seek_fade.len();
+        for sample in self.frame_samples.iter_mut() {
+            if self.seek_fade_pos >= len {
+                break;
+            }
+            let old = self.seek_fade[self.seek_fade_pos];
+            self.seek_fade_pos += 1;
+            let t = self.seek_fade_pos as f32 / (len + 1) as f32;
+            let new_weight = FadeCurve::EqualPower.evaluate(t);
+            let old_weight = FadeCurve::EqualPower.evaluate(1.0 - t);
+            sample.0 = sample.0 * new_weight + old.0 * old_weight;
+            sample.1 = sample.1 * new_weight + old.1 * old_weight;
+        }
+        if self.seek_fade_pos >= len {
+            self.seek_fade.clear();
+            self.seek_fade_pos = 0;
+        }
+    }
+
+    // Returns loop region of the buffer if it should be used for current playback position.
+    fn active_loop_region(&self, buffer: &SoundBufferState) -> Option<LoopRegion> {
+        match buffer {
+            SoundBufferState::Generic(generic) if self.looping => {
+                let region = match self.loop_region {
+                    Some(ref region) => {
+                        // The region must have at least two samples, otherwise resampling will
+                        // not be able to advance.
+                        let end = (region.end as usize).min(generic.frame_count());
+                        let start = (region.start as usize).min(end.saturating_sub(2));
+                        Some(LoopRegion { start, end }).filter(|r| r.start + 1 < r.end)
+                    }
+                    None => generic.loop_region(),
+                };
+                region.filter(|region| self.buf_read_pos < region.end as f64)
+            }
+            _ => None,
+        }
     }
 
     fn render_playing(&mut self, buffer: &mut SoundBufferState, amount: usize) {
         let mut count = 0;
         loop {
-            count += self.render_until_block_end(buffer, amount - count);
+            let loop_region = self.active_loop_region(buffer);
+            let block_end =
+                loop_region.map_or(buffer.samples.len() / buffer.channel_count, |r| r.end);
+            count += self.render_until_block_end(buffer, amount - count, block_end);
             if count == amount {
                 break;
             }
+            if let Some(loop_region) = loop_region {
+                self.buf_read_pos = loop_region.start as f64;
+                self.playback_pos = loop_region.start as f64;
+                continue;
+            }
 
             let channel_count = buffer.channel_count();
             let len = buffer.samples().len();
@@ -530,8 +1149,9 @@ impl SoundSource {
         &mut self,
         buffer: &mut SoundBufferState,
         mut amount: usize,
+        block_end: usize,
     ) -> usize {
-        let step = self.pitch * self.resampling_multiplier;
+        let step = self.pitch * self.resampling_multiplier * self.doppler_pitch;
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -541,8 +1161,7 @@ impl SoundSource {
             }
             // Fast-path for common case when there is no resampling and no pitch change.
             let from = self.buf_read_pos as usize;
-            let buffer_len = buffer.samples.len() / buffer.channel_count;
-            let rendered = (buffer_len - from).min(amount);
+            let rendered = block_end.saturating_sub(from).min(amount);
             if buffer.channel_count == 2 {
                 for i in from..from + rendered {
                     self.frame_samples
@@ -558,7 +1177,7 @@ impl SoundSource {
             self.playback_pos += rendered as f64;
             rendered
         } else {
-            self.render_until_block_end_resample(buffer, amount, step)
+            self.render_until_block_end_resample(buffer, amount, step, block_end)
         }
     }
 
@@ -568,6 +1187,7 @@ impl SoundSource {
         buffer: &mut SoundBufferState,
         amount: usize,
         step: f64,
+        block_end: usize,
     ) -> usize {
         let mut rendered = 0;
 
@@ -598,7 +1218,7 @@ impl SoundSource {
         let rel_step = step as f32;
         // We skip one last element because the hot loop resampling between current and next
         // element. Last elements are appended after the hot loop.
-        let buffer_last = buffer.samples.len() / buffer.channel_count - 1;
+        let buffer_last = block_end.saturating_sub(1);
         if buffer.channel_count == 2 {
             while rendered < amount {
                 let (idx, w) = {
@@ -701,7 +1321,21 @@ pub struct SoundSourceBuilder {
     position: Vector3<f32>,
     max_distance: f32,
     rolloff_factor: f32,
+    direction: Vector3<f32>,
+    cone_inner_angle: f32,
+    cone_outer_angle: f32,
+    cone_outer_gain: f32,
     spatial_blend: f32,
+    bus: String,
+    loop_region: Option<(usize, usize)>,
+    doppler_factor: f32,
+    smooth_seek: bool,
+    priority: u8,
+    fade_in: Duration,
+    fade_out: Duration,
+    fade_curve: FadeCurve,
+    direct_output: bool,
+    distance_model_override: Option<DistanceModel>,
 }
 
 impl Default for SoundSourceBuilder {
@@ -727,7 +1361,21 @@ impl SoundSourceBuilder {
             position: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            direction: Vector3::new(0.0, 0.0, 1.0),
+            cone_inner_angle: std::f32::consts::TAU,
+            cone_outer_angle: std::f32::consts::TAU,
+            cone_outer_gain: 0.0,
             spatial_blend: 1.0,
+            bus: BusGraph::PRIMARY_BUS.to_owned(),
+            loop_region: None,
+            doppler_factor: 0.0,
+            smooth_seek: false,
+            priority: 0,
+            fade_in: Default::default(),
+            fade_out: Default::default(),
+            fade_curve: Default::default(),
+            direct_output: true,
+            distance_model_override: None,
         }
     }
 
@@ -785,6 +1433,66 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_bus`]
+    pub fn with_bus<N: AsRef<str>>(mut self, bus: N) -> Self {
+        self.bus = bus.as_ref().to_owned();
+        self
+    }
+
+    /// See [`SoundSource::set_loop_region`]
+    pub fn with_loop_region(mut self, region: Option<(usize, usize)>) -> Self {
+        self.loop_region = region;
+        self
+    }
+
+    /// See [`SoundSource::set_doppler_factor`]
+    pub fn with_doppler_factor(mut self, factor: f32) -> Self {
+        self.doppler_factor = factor.max(0.0);
+        self
+    }
+
+    /// See [`SoundSource::set_smooth_seek`]
+    pub fn with_smooth_seek(mut self, smooth_seek: bool) -> Self {
+        self.smooth_seek = smooth_seek;
+        self
+    }
+
+    /// See [`SoundSource::set_priority`]
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// See [`SoundSource::set_fade_in`]
+    pub fn with_fade_in(mut self, fade_in: Duration) -> Self {
+        self.fade_in = fade_in;
+        self
+    }
+
+    /// See [`SoundSource::set_fade_out`]
+    pub fn with_fade_out(mut self, fade_out: Duration) -> Self {
+        self.fade_out = fade_out;
+        self
+    }
+
+    /// See [`SoundSource::set_fade_curve`]
+    pub fn with_fade_curve(mut self, fade_curve: FadeCurve) -> Self {
+        self.fade_curve = fade_curve;
+        self
+    }
+
+    /// See [`SoundSource::set_direct_output`]
+    pub fn with_direct_output(mut self, direct_output: bool) -> Self {
+        self.direct_output = direct_output;
+        self
+    }
+
+    /// See [`SoundSource::set_distance_model_override`]
+    pub fn with_distance_model_override(mut self, distance_model: Option<DistanceModel>) -> Self {
+        self.distance_model_override = distance_model;
+        self
+    }
+
     /// Sets desired name of the source.
     pub fn with_name<N: AsRef<str>>(mut self, name: N) -> Self {
         self.name = name.as_ref().to_owned();
@@ -821,6 +1529,30 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_direction`]
+    pub fn with_direction(mut self, direction: Vector3<f32>) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// See [`SoundSource::set_cone_inner_angle`]
+    pub fn with_cone_inner_angle(mut self, angle: f32) -> Self {
+        self.cone_inner_angle = angle;
+        self
+    }
+
+    /// See [`SoundSource::set_cone_outer_angle`]
+    pub fn with_cone_outer_angle(mut self, angle: f32) -> Self {
+        self.cone_outer_angle = angle;
+        self
+    }
+
+    /// See [`SoundSource::set_cone_outer_gain`]
+    pub fn with_cone_outer_gain(mut self, gain: f32) -> Self {
+        self.cone_outer_gain = gain;
+        self
+    }
+
     /// Creates new instance of generic sound source. May fail if buffer is invalid.
     pub fn build(self) -> Result<SoundSource, SoundError> {
         let mut source = SoundSource {
@@ -837,15 +1569,332 @@ impl SoundSourceBuilder {
             position: self.position,
             max_distance: self.max_distance,
             rolloff_factor: self.rolloff_factor,
+            direction: self.direction,
             spatial_blend: self.spatial_blend,
+            auto_reverb_send: None,
+            bus: self.bus,
+            loop_region: self
+                .loop_region
+                .map(|(start, end)| start as u64..end as u64),
+            doppler_factor: self.doppler_factor,
+            direct_output: self.direct_output,
+            distance_model_override: self.distance_model_override,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
+            seek_fade: Default::default(),
             ..Default::default()
         };
 
         source.set_buffer(self.buffer)?;
         source.set_playback_time(self.playback_time);
+        // Initial playback position must not be faded in.
+        source.smooth_seek = self.smooth_seek;
+        source.priority = self.priority;
+        source.fade_in = self.fade_in;
+        source.fade_out = self.fade_out;
+        source.fade_curve = self.fade_curve;
+        if source.status == Status::Playing {
+            source.instance_stamp = Some(next_instance_stamp());
+            if !self.fade_in.is_zero() {
+                // The source starts playing right away, so it must be faded in too.
+                source.envelope = 0.0;
+            }
+        }
+        source.set_cone_inner_angle(self.cone_inner_angle);
+        source.set_cone_outer_angle(self.cone_outer_angle);
+        source.set_cone_outer_gain(self.cone_outer_gain);
 
         Ok(source)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource},
+        context::DistanceModel,
+        dsp::fade::FadeCurve,
+        listener::Listener,
+        source::{SoundSource, SoundSourceBuilder, Status},
+    };
+    use fyrox_core::{
+        algebra::Vector3,
+        curve::{Curve, CurveKey, CurveKeyKind},
+    };
+    use std::time::Duration;
+
+    fn reverb_send_at(distance: f32, curve: Curve) -> f32 {
+        let mut source = SoundSourceBuilder::new()
+            .with_position(Vector3::new(distance, 0.0, 0.0))
+            .with_radius(1.0)
+            .with_max_distance(100.0)
+            .build()
+            .unwrap();
+        source.set_auto_reverb_send(true, curve);
+        let distance_gain =
+            source.calculate_distance_gain(&Listener::new(), DistanceModel::InverseDistance);
+        source.calculate_reverb_send_gain(distance_gain)
+    }
+
+    #[test]
+    fn test_distance_model_override() {
+        let make_source = |distance_model: Option<DistanceModel>| {
+            SoundSourceBuilder::new()
+                .with_position(Vector3::new(10.0, 0.0, 0.0))
+                .with_radius(1.0)
+                .with_max_distance(100.0)
+                .with_distance_model_override(distance_model)
+                .build()
+                .unwrap()
+        };
+
+        let listener = Listener::new();
+        let context_model = DistanceModel::InverseDistance;
+
+        let inherited = make_source(None);
+        let overridden = make_source(Some(DistanceModel::LinearDistance));
+        assert_eq!(
+            overridden.distance_model_override(),
+            Some(DistanceModel::LinearDistance)
+        );
+
+        let inherited_gain = inherited.calculate_distance_gain(&listener, context_model);
+        let overridden_gain = overridden.calculate_distance_gain(&listener, context_model);
+        assert!((inherited_gain - 0.1).abs() < 1.0e-6);
+        assert!((overridden_gain - (1.0 - 9.0 / 99.0)).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_cone_gain() {
+        let source = SoundSourceBuilder::new()
+            .with_position(Vector3::new(0.0, 0.0, 0.0))
+            .with_direction(Vector3::new(0.0, 0.0, 1.0))
+            .with_cone_inner_angle(90.0f32.to_radians())
+            .with_cone_outer_angle(180.0f32.to_radians())
+            .with_cone_outer_gain(0.2)
+            .build()
+            .unwrap();
+
+        let gain_at = |position: Vector3<f32>| {
+            let mut listener = Listener::new();
+            listener.set_position(position);
+            source.calculate_cone_gain(&listener)
+        };
+
+        // In front of the source - inside of the inner cone.
+        assert_eq!(gain_at(Vector3::new(0.0, 0.0, 5.0)), 1.0);
+        // Behind the source - outside of the outer cone.
+        assert!((gain_at(Vector3::new(0.0, 0.0, -5.0)) - 0.2).abs() < 1.0e-6);
+        // 67.5 degrees off the axis - halfway between the cones.
+        let angle = 67.5f32.to_radians();
+        let halfway = gain_at(Vector3::new(angle.sin(), 0.0, angle.cos()));
+        assert!((halfway - 0.6).abs() < 1.0e-4);
+
+        // Omnidirectional source is not attenuated at all.
+        let omni = SoundSourceBuilder::new().build().unwrap();
+        let mut listener = Listener::new();
+        listener.set_position(Vector3::new(0.0, 0.0, -5.0));
+        assert_eq!(omni.calculate_cone_gain(&listener), 1.0);
+    }
+
+    #[test]
+    fn test_auto_reverb_send_grows_with_distance() {
+        let curve = Curve::from(vec![
+            CurveKey::new(0.0, 0.1, CurveKeyKind::Linear),
+            CurveKey::new(1.0, 0.9, CurveKeyKind::Linear),
+        ]);
+
+        let near = reverb_send_at(1.5, curve.clone());
+        let far = reverb_send_at(50.0, curve);
+        assert!(far > near);
+
+        // Empty curve means linear mapping.
+        let near = reverb_send_at(1.5, Curve::default());
+        let far = reverb_send_at(50.0, Curve::default());
+        assert!(far > near);
+    }
+
+    #[test]
+    fn test_reverb_send_without_auto_mode() {
+        let source = SoundSource::default();
+        assert_eq!(source.calculate_reverb_send_gain(0.25), 0.25);
+    }
+
+    fn render_ramp(region: Option<(usize, usize)>, amount: usize) -> Vec<f32> {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: (0..10).map(|i| i as f32).collect(),
+        })
+        .unwrap();
+        let mut source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_looping(true)
+            .with_status(Status::Playing)
+            .with_loop_region(region)
+            .build()
+            .unwrap();
+        source.render(amount);
+        source.frame_samples().iter().map(|(l, _)| *l).collect()
+    }
+
+    #[test]
+    fn test_source_loop_region() {
+        assert_eq!(
+            render_ramp(Some((4, 8)), 16),
+            [0., 1., 2., 3., 4., 5., 6., 7., 4., 5., 6., 7., 4., 5., 6., 7.]
+        );
+
+        // Without a region the whole buffer is repeated.
+        assert_eq!(
+            render_ramp(None, 12),
+            [0., 1., 2., 3., 4., 5., 6., 7., 8., 9., 0., 1.]
+        );
+
+        // Out-of-range end is clamped to the end of the buffer.
+        assert_eq!(
+            render_ramp(Some((6, 100)), 14),
+            [0., 1., 2., 3., 4., 5., 6., 7., 8., 9., 6., 7., 8., 9.]
+        );
+
+        // Start after end is clamped, so the region still has two samples.
+        assert_eq!(render_ramp(Some((9, 3)), 7), [0., 1., 2., 1., 2., 1., 2.]);
+    }
+
+    #[test]
+    fn test_doppler_pitch() {
+        let listener = Listener::new();
+
+        let doppler_pitch = |velocity: Vector3<f32>, factor: f32| {
+            let mut source = SoundSourceBuilder::new()
+                .with_position(Vector3::new(10.0, 0.0, 0.0))
+                .with_doppler_factor(factor)
+                .build()
+                .unwrap();
+            source.set_velocity(velocity);
+            source.calculate_doppler_pitch(&listener)
+        };
+
+        let towards = Vector3::new(-20.0, 0.0, 0.0);
+        assert!(doppler_pitch(towards, 1.0) > 1.0);
+        assert!(doppler_pitch(-towards, 1.0) < 1.0);
+        assert!(doppler_pitch(towards, 2.0) > doppler_pitch(towards, 1.0));
+        // Perpendicular movement does not change the pitch.
+        assert_eq!(doppler_pitch(Vector3::new(0.0, 0.0, 20.0), 1.0), 1.0);
+        // Zero factor disables the effect.
+        assert_eq!(doppler_pitch(towards, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_source_fade_in_and_out() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![1.0; 44100],
+        })
+        .unwrap();
+        let mut source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_looping(true)
+            .with_fade_in(Duration::from_millis(10))
+            .with_fade_out(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        // 10 ms at 44100 Hz is 441 samples.
+        source.play();
+        source.render(882);
+        let samples = source
+            .frame_samples()
+            .iter()
+            .map(|(l, _)| *l)
+            .collect::<Vec<_>>();
+        assert!(samples[0] < 0.01);
+        assert!(samples.windows(2).take(440).all(|w| w[1] > w[0]));
+        assert!(samples[220] < 1.0);
+        assert!(samples[441..].iter().all(|s| *s == 1.0));
+
+        // Stop must not be immediate.
+        source.stop().unwrap();
+        assert_eq!(source.status(), Status::Playing);
+        source.render(220);
+        let samples = source
+            .frame_samples()
+            .iter()
+            .map(|(l, _)| *l)
+            .collect::<Vec<_>>();
+        assert!(samples.windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(source.status(), Status::Playing);
+        source.render(441);
+        assert_eq!(source.status(), Status::Stopped);
+        assert_eq!(source.frame_samples().last().unwrap().0, 0.0);
+
+        // Without fade out the source stops immediately.
+        source.set_fade_out(Duration::default());
+        source.play();
+        source.stop().unwrap();
+        assert_eq!(source.status(), Status::Stopped);
+    }
+
+    #[test]
+    fn test_source_fade_curve() {
+        let fade_in_sample = |fade_curve: FadeCurve| {
+            let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+                sample_rate: 44100,
+                channel_count: 1,
+                samples: vec![1.0; 44100],
+            })
+            .unwrap();
+            let mut source = SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_fade_in(Duration::from_millis(10))
+                .with_fade_curve(fade_curve)
+                .build()
+                .unwrap();
+            source.play();
+            source.render(441);
+            // Middle of the fade in.
+            source.frame_samples()[219].0
+        };
+
+        assert!((fade_in_sample(FadeCurve::Linear) - 0.5).abs() < 0.01);
+        assert!(fade_in_sample(FadeCurve::Exponential) < 0.05);
+        assert!(
+            (fade_in_sample(FadeCurve::EqualPower) - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01
+        );
+    }
+
+    #[test]
+    fn test_smooth_seek() {
+        let max_step = |smooth_seek: bool| {
+            let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+                sample_rate: 44100,
+                channel_count: 1,
+                samples: (0..44100).map(|i| i as f32 / 44100.0).collect(),
+            })
+            .unwrap();
+            let mut source = SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_status(Status::Playing)
+                .with_smooth_seek(smooth_seek)
+                .build()
+                .unwrap();
+
+            let mut samples = Vec::new();
+            source.render(1000);
+            samples.extend(source.frame_samples().iter().map(|(l, _)| *l));
+            source.set_playback_time(Duration::from_secs_f32(0.5));
+            source.render(1000);
+            samples.extend(source.frame_samples().iter().map(|(l, _)| *l));
+
+            samples
+                .windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(0.0f32, f32::max)
+        };
+
+        // Instant seek jumps from ~0.02 to 0.5 in a single sample.
+        assert!(max_step(false) > 0.4);
+        assert!(max_step(true) < 0.01);
+    }
+}
@@ -13,9 +13,10 @@ use crate::{
         PropertyChanged,
     },
     message::{MessageDirection, UiMessage},
+    numeric::{NumericUpDownBuilder, NumericUpDownMessage},
     stack_panel::StackPanelBuilder,
     widget::{Widget, WidgetBuilder, WidgetMessage},
-    BuildContext, Control, HorizontalAlignment, Thickness, UiNode, UserInterface,
+    BuildContext, Control, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
     VerticalAlignment,
 };
 use fyrox_core::reflect::Reflect;
@@ -41,6 +42,7 @@ impl<T: Clone + Reflect + Debug + Default + 'static> CollectionItem for T {}
 pub struct CollectionEditor<T: CollectionItem> {
     pub widget: Widget,
     pub add: Handle<UiNode>,
+    pub length: Handle<UiNode>,
     pub items: Vec<Item>,
     pub panel: Handle<UiNode>,
     pub layer_index: usize,
@@ -52,6 +54,7 @@ impl<T: CollectionItem> Clone for CollectionEditor<T> {
         Self {
             widget: self.widget.clone(),
             add: self.add,
+            length: self.length,
             items: self.items.clone(),
             panel: self.panel,
             layer_index: self.layer_index,
@@ -142,6 +145,14 @@ impl<T: CollectionItem> Control for CollectionEditor<T> {
                         }
 
                         self.items = items.clone();
+
+                        if self.length.is_some() {
+                            ui.send_message(NumericUpDownMessage::value(
+                                self.length,
+                                MessageDirection::ToWidget,
+                                self.items.len(),
+                            ));
+                        }
                     }
                 }
             }
@@ -159,6 +170,20 @@ impl<T: CollectionItem> Control for CollectionEditor<T> {
                     },
                 ))
             }
+        } else if let Some(NumericUpDownMessage::Value(length)) =
+            message.data::<NumericUpDownMessage<usize>>()
+        {
+            // Ignore the response to the length sync (see `CollectionEditorMessage::Items`).
+            if message.destination() == self.length
+                && message.direction() == MessageDirection::FromWidget
+                && *length != self.items.len()
+            {
+                ui.send_message(CollectionChanged::resize(
+                    self.handle,
+                    MessageDirection::FromWidget,
+                    *length,
+                ))
+            }
         }
     }
 }
@@ -173,6 +198,7 @@ where
     environment: Option<Rc<dyn InspectorEnvironment>>,
     definition_container: Option<Rc<PropertyEditorDefinitionContainer>>,
     add: Handle<UiNode>,
+    length: Handle<UiNode>,
     layer_index: usize,
     generate_property_string_values: bool,
 }
@@ -256,6 +282,7 @@ where
             environment: None,
             definition_container: None,
             add: Default::default(),
+            length: Default::default(),
             layer_index: 0,
             generate_property_string_values: false,
         }
@@ -276,6 +303,11 @@ where
         self
     }
 
+    pub fn with_length(mut self, length: Handle<UiNode>) -> Self {
+        self.length = length;
+        self
+    }
+
     pub fn with_definition_container(
         mut self,
         definition_container: Rc<PropertyEditorDefinitionContainer>,
@@ -332,6 +364,7 @@ where
                 .with_child(panel)
                 .build(),
             add: self.add,
+            length: self.length,
             items,
             panel,
             layer_index: self.layer_index,
@@ -384,28 +417,47 @@ where
     ) -> Result<PropertyEditorInstance, InspectorError> {
         let value = ctx.property_info.cast_value::<Vec<T>>()?;
 
+        let length = NumericUpDownBuilder::new(
+            WidgetBuilder::new()
+                .with_width(50.0)
+                .with_height(16.0)
+                .with_margin(Thickness::uniform(1.0)),
+        )
+        .with_precision(0)
+        .with_value(value.len())
+        .build(ctx.build_context);
+
         let add = ButtonBuilder::new(
             WidgetBuilder::new()
-                .with_horizontal_alignment(HorizontalAlignment::Right)
                 .with_width(16.0)
                 .with_height(16.0)
-                .on_column(1)
                 .with_margin(Thickness::uniform(1.0)),
         )
         .with_text("+")
         .build(ctx.build_context);
 
+        let header = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_horizontal_alignment(HorizontalAlignment::Right)
+                .on_column(1)
+                .with_child(length)
+                .with_child(add),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx.build_context);
+
         let editor;
         let container = make_expander_container(
             ctx.layer_index,
             ctx.property_info.display_name,
             ctx.property_info.description,
-            add,
+            header,
             {
                 editor = CollectionEditorBuilder::new(
                     WidgetBuilder::new().with_margin(Thickness::uniform(1.0)),
                 )
                 .with_add(add)
+                .with_length(length)
                 .with_collection(value.iter())
                 .with_environment(ctx.environment.clone())
                 .with_layer_index(ctx.layer_index + 1)
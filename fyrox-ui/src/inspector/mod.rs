@@ -4,7 +4,7 @@ use crate::{
     core::{
         algebra::Vector2,
         pool::Handle,
-        reflect::{CastError, Reflect, ResolvePath},
+        reflect::{default_registry::DefaultRegistry, CastError, Reflect, ResolvePath},
     },
     define_constructor,
     expander::ExpanderBuilder,
@@ -40,6 +40,9 @@ pub enum CollectionChanged {
     Add(ObjectValue),
     /// An item in the collection should be removed.
     Remove(usize),
+    /// The collection should be resized to the given length. New items (if any) should be
+    /// created with default values.
+    Resize(usize),
     /// An item in the collection has changed one of its properties.
     ItemChanged {
         /// Index of an item in the collection.
@@ -51,6 +54,7 @@ pub enum CollectionChanged {
 impl CollectionChanged {
     define_constructor!(CollectionChanged:Add => fn add(ObjectValue), layout: false);
     define_constructor!(CollectionChanged:Remove => fn remove(usize), layout: false);
+    define_constructor!(CollectionChanged:Resize => fn resize(usize), layout: false);
     define_constructor!(CollectionChanged:ItemChanged => fn item_changed(index: usize, property: PropertyChanged), layout: false);
 }
 
@@ -85,6 +89,11 @@ pub enum PropertyAction {
         /// Index of an item.
         index: usize,
     },
+    /// A collection property needs to be resized.
+    ResizeCollection {
+        /// New length of the collection.
+        new_len: usize,
+    },
     /// Revert value to parent.
     Revert,
 }
@@ -103,6 +112,7 @@ impl PropertyAction {
                     value: value.clone().into_box_reflect(),
                 },
                 CollectionChanged::Remove(index) => Self::RemoveItem { index },
+                CollectionChanged::Resize(new_len) => Self::ResizeCollection { new_len },
                 CollectionChanged::ItemChanged { ref property, .. } => {
                     Self::from_field_kind(&property.value)
                 }
@@ -178,6 +188,30 @@ impl PropertyAction {
                     result_callback(Err(Self::RemoveItem { index }))
                 }
             }),
+            PropertyAction::ResizeCollection { new_len } => {
+                target.resolve_path_mut(path, &mut |result| {
+                    if let Ok(field) = result {
+                        field.as_list_mut(&mut |result| {
+                            if let Some(list) = result {
+                                // There's no access to user-defined default values here, so only
+                                // built-in types could be used for new items.
+                                if list
+                                    .reflect_resize(new_len, &DefaultRegistry::new())
+                                    .is_some()
+                                {
+                                    result_callback(Ok(None))
+                                } else {
+                                    result_callback(Err(Self::ResizeCollection { new_len }))
+                                }
+                            } else {
+                                result_callback(Err(Self::ResizeCollection { new_len }))
+                            }
+                        })
+                    } else {
+                        result_callback(Err(Self::ResizeCollection { new_len }))
+                    }
+                })
+            }
             PropertyAction::Revert => {
                 // Unsupported due to lack of context (a reference to parent entity).
                 result_callback(Err(Self::Revert))
@@ -302,6 +336,7 @@ impl PropertyChanged {
             FieldKind::Collection(ref collection_changed) => match **collection_changed {
                 CollectionChanged::Add(_) => false,
                 CollectionChanged::Remove(_) => false,
+                CollectionChanged::Resize(_) => false,
                 CollectionChanged::ItemChanged { ref property, .. } => property.is_inheritable(),
             },
             FieldKind::Inspectable(ref inspectable) => inspectable.is_inheritable(),
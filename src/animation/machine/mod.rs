@@ -19,6 +19,7 @@ pub use layer::MachineLayer;
 pub use mask::LayerMask;
 pub use node::{
     blend::{BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput},
+    blendspace::{BlendSpace, BlendSpacePoint},
     play::PlayAnimation,
     EvaluatePose, PoseNode,
 };
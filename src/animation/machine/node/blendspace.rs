@@ -0,0 +1,398 @@
+//! Blend space is a 2D space where each point is associated with an animation pose, it is used to
+//! blend animations using a 2D parameter. See [`BlendSpace`] docs for more info.
+
+use crate::{
+    animation::{
+        machine::{
+            node::{BasePoseNode, EvaluatePose},
+            Parameter, ParameterContainer, PoseNode,
+        },
+        AnimationContainer, AnimationPose,
+    },
+    core::{
+        algebra::Vector2,
+        math::get_barycentric_coords_2d,
+        pool::{Handle, Pool},
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+};
+use std::{
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    ops::{Deref, DerefMut},
+};
+
+/// A point of a blend space, it associates a position in the space with a pose source.
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct BlendSpacePoint {
+    /// Position of the point in the blend space.
+    pub position: Vector2<f32>,
+
+    /// A handle to pose node source.
+    #[reflect(hidden)]
+    pub pose_source: Handle<PoseNode>,
+}
+
+impl BlendSpacePoint {
+    /// Creates new blend space point at the given position with the given pose source.
+    pub fn new(position: Vector2<f32>, pose_source: Handle<PoseNode>) -> Self {
+        Self {
+            position,
+            pose_source,
+        }
+    }
+}
+
+/// Blend space is a 2D space, where each point is associated with an animation pose. The space is
+/// sampled by a sampling point (taken from a [`Parameter::SamplingPoint`] parameter) and the final
+/// pose is a weighted sum of the poses of the points around it. It is the standard tool for
+/// locomotion blending - for example, you can put idle animation in the center, walk forward
+/// animation above it, strafe left and right animations at the sides and so on, then you just set
+/// the sampling point from your character's velocity and the node will blend the animations
+/// accordingly.
+///
+/// Points of the space are triangulated (using Delaunay triangulation) and the poses are blended
+/// using barycentric coordinates of the sampling point in a triangle that contains it. If the
+/// sampling point is outside of the triangulation, it is projected on the closest edge.
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct BlendSpace {
+    /// Base node.
+    pub base: BasePoseNode,
+
+    /// A name of a sampling point parameter (see [`Parameter::SamplingPoint`]).
+    pub sampling_parameter: String,
+
+    /// A set of points of the blend space.
+    pub points: Vec<BlendSpacePoint>,
+
+    /// Triangles of the blend space, they're updated automatically when positions of the points
+    /// change.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub triangles: RefCell<BlendSpaceTriangulation>,
+
+    /// Output pose of the node.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub output_pose: RefCell<AnimationPose>,
+}
+
+/// Cached triangulation of blend space points.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct BlendSpaceTriangulation {
+    positions: Vec<Vector2<f32>>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl Deref for BlendSpace {
+    type Target = BasePoseNode;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for BlendSpace {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+// Returns true if the point `d` is strictly inside of the circumcircle of the counter-clockwise
+// triangle `abc`. `eps` must be relative to the fourth power of the scale of the points.
+fn in_circumcircle(
+    a: Vector2<f32>,
+    b: Vector2<f32>,
+    c: Vector2<f32>,
+    d: Vector2<f32>,
+    eps: f32,
+) -> bool {
+    let (ad, bd, cd) = (a - d, b - d, c - d);
+    let det = ad.norm_squared() * (bd.x * cd.y - cd.x * bd.y)
+        - bd.norm_squared() * (ad.x * cd.y - cd.x * ad.y)
+        + cd.norm_squared() * (ad.x * bd.y - bd.x * ad.y);
+    det > eps
+}
+
+fn signed_area(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)
+}
+
+// Returns true if interiors of two counter-clockwise triangles overlap. Triangles are convex, so
+// they do not overlap if there is an edge of one of them that has the other one on its outer side.
+// `eps` must be relative to the square of the scale of the points.
+fn triangles_overlap(a: [Vector2<f32>; 3], b: [Vector2<f32>; 3], eps: f32) -> bool {
+    let separated_by_edge_of = |t: &[Vector2<f32>; 3], other: &[Vector2<f32>; 3]| {
+        (0..3).any(|i| {
+            let (p, q) = (t[i], t[(i + 1) % 3]);
+            other.iter().all(|v| signed_area(p, q, *v) <= eps)
+        })
+    };
+    !separated_by_edge_of(&a, &b) && !separated_by_edge_of(&b, &a)
+}
+
+// Brute-force Delaunay triangulation. Blend spaces usually have a handful of points, so it is fast
+// enough and it does not suffer from precision issues of incremental algorithms. Cocircular points
+// (for example, corners of a grid cell) have multiple valid triangulations, so every candidate that
+// overlaps already accepted triangle is rejected to get only one of them.
+fn triangulate(positions: &[Vector2<f32>]) -> Vec<[usize; 3]> {
+    // Tolerances must be relative to the size of the space, otherwise they're either too strict
+    // for large spaces or too loose for small ones.
+    let scale = positions
+        .iter()
+        .flat_map(|p| positions.iter().map(move |q| (p - q).amax()))
+        .fold(0.0f32, f32::max);
+    let area_eps = scale * scale * 1.0e-6;
+    let circle_eps = area_eps * scale * scale;
+
+    let mut triangles: Vec<[usize; 3]> = Vec::new();
+    for i in 0..positions.len() {
+        for j in (i + 1)..positions.len() {
+            for k in (j + 1)..positions.len() {
+                let (a, b, c) = (positions[i], positions[j], positions[k]);
+                let area = signed_area(a, b, c);
+                if area.abs() <= area_eps {
+                    // Degenerated triangle.
+                    continue;
+                }
+                let triangle = if area > 0.0 { [i, j, k] } else { [i, k, j] };
+                let vertices = triangle.map(|n| positions[n]);
+                let [a, b, c] = vertices;
+                if positions.iter().enumerate().all(|(n, p)| {
+                    triangle.contains(&n) || !in_circumcircle(a, b, c, *p, circle_eps)
+                }) && triangles.iter().all(|other| {
+                    !triangles_overlap(vertices, other.map(|n| positions[n]), area_eps)
+                }) {
+                    triangles.push(triangle);
+                }
+            }
+        }
+    }
+    triangles
+}
+
+// Returns the closest point on a segment as an interpolation coefficient between its ends.
+fn project_on_segment(p: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    let ab = b - a;
+    let len_sqr = ab.norm_squared();
+    if len_sqr <= f32::EPSILON {
+        0.0
+    } else {
+        ((p - a).dot(&ab) / len_sqr).clamp(0.0, 1.0)
+    }
+}
+
+impl BlendSpace {
+    /// Creates new blend space node using given sampling parameter name and a set of points.
+    pub fn new(sampling_parameter: String, points: Vec<BlendSpacePoint>) -> Self {
+        Self {
+            base: Default::default(),
+            sampling_parameter,
+            points,
+            triangles: Default::default(),
+            output_pose: Default::default(),
+        }
+    }
+
+    /// Returns a set of handles to children pose nodes.
+    pub fn children(&self) -> Vec<Handle<PoseNode>> {
+        self.points.iter().map(|p| p.pose_source).collect()
+    }
+
+    fn update_triangulation(&self) {
+        let mut triangulation = self.triangles.borrow_mut();
+        if triangulation.positions.len() != self.points.len()
+            || triangulation
+                .positions
+                .iter()
+                .zip(self.points.iter())
+                .any(|(a, b)| *a != b.position)
+        {
+            triangulation.positions = self.points.iter().map(|p| p.position).collect();
+            triangulation.triangles = triangulate(&triangulation.positions);
+        }
+    }
+
+    /// Calculates weights of the points of the blend space for the given sampling point. Returns
+    /// a list of pairs `(point_index, weight)`, the sum of the weights is always 1.0 (if there's at
+    /// least one point).
+    pub fn fetch_weights(&self, sampling_point: Vector2<f32>) -> Vec<(usize, f32)> {
+        self.update_triangulation();
+
+        let triangulation = self.triangles.borrow();
+        let positions = &triangulation.positions;
+
+        if triangulation.triangles.is_empty() {
+            // Not enough points to form a triangle, use the closest point.
+            return positions
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i, p.metric_distance(&sampling_point)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+                .map(|(i, _)| vec![(i, 1.0)])
+                .unwrap_or_default();
+        }
+
+        for &[a, b, c] in triangulation.triangles.iter() {
+            let (u, v, w) =
+                get_barycentric_coords_2d(sampling_point, positions[a], positions[b], positions[c]);
+            const EPS: f32 = -1.0e-5;
+            if u >= EPS && v >= EPS && w >= EPS {
+                return vec![(a, u), (b, v), (c, w)];
+            }
+        }
+
+        // The point is outside of the triangulation, project it on the closest edge.
+        let mut closest = (0, 0, 0.0, f32::MAX);
+        for &[a, b, c] in triangulation.triangles.iter() {
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                let t = project_on_segment(sampling_point, positions[start], positions[end]);
+                let distance = positions[start]
+                    .lerp(&positions[end], t)
+                    .metric_distance(&sampling_point);
+                if distance < closest.3 {
+                    closest = (start, end, t, distance);
+                }
+            }
+        }
+        let (start, end, t, _) = closest;
+        vec![(start, 1.0 - t), (end, t)]
+    }
+}
+
+impl EvaluatePose for BlendSpace {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer,
+        dt: f32,
+    ) -> Ref<AnimationPose> {
+        self.output_pose.borrow_mut().reset();
+
+        if let Some(&Parameter::SamplingPoint(sampling_point)) =
+            params.get(&self.sampling_parameter)
+        {
+            for (index, weight) in self.fetch_weights(sampling_point) {
+                if let Some(pose_source) = self
+                    .points
+                    .get(index)
+                    .and_then(|point| nodes.try_borrow(point.pose_source))
+                    .map(|pose_source| pose_source.eval_pose(nodes, params, animations, dt))
+                {
+                    self.output_pose
+                        .borrow_mut()
+                        .blend_with(&pose_source, weight);
+                }
+            }
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<AnimationPose> {
+        self.output_pose.borrow()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::machine::node::blendspace::{BlendSpace, BlendSpacePoint},
+        core::{algebra::Vector2, pool::Handle},
+    };
+
+    fn weights(blend_space: &BlendSpace, x: f32, y: f32) -> Vec<f32> {
+        let mut weights = vec![0.0; blend_space.points.len()];
+        for (index, weight) in blend_space.fetch_weights(Vector2::new(x, y)) {
+            weights[index] += weight;
+        }
+        weights
+    }
+
+    fn assert_weights(actual: Vec<f32>, expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, b) in actual.iter().zip(expected) {
+            assert!((a - b).abs() < 1.0e-4, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_blend_space_weights() {
+        // Idle in the center, walk forward, backward, left and right around it.
+        let blend_space = BlendSpace::new(
+            "Movement".to_string(),
+            [(0.0, 0.0), (0.0, 1.0), (0.0, -1.0), (-1.0, 0.0), (1.0, 0.0)]
+                .iter()
+                .map(|&(x, y)| BlendSpacePoint::new(Vector2::new(x, y), Handle::NONE))
+                .collect(),
+        );
+
+        // Exactly at the points.
+        assert_weights(weights(&blend_space, 0.0, 0.0), &[1.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_weights(weights(&blend_space, 0.0, 1.0), &[0.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_weights(weights(&blend_space, -1.0, 0.0), &[0.0, 0.0, 0.0, 1.0, 0.0]);
+
+        // Inside of a triangle.
+        assert_weights(
+            weights(&blend_space, 0.25, 0.25),
+            &[0.5, 0.25, 0.0, 0.0, 0.25],
+        );
+
+        // Outside, projected on the closest edge.
+        assert_weights(weights(&blend_space, 1.0, 1.0), &[0.0, 0.5, 0.0, 0.0, 0.5]);
+        assert_weights(weights(&blend_space, 0.0, 5.0), &[0.0, 1.0, 0.0, 0.0, 0.0]);
+
+        // Single point.
+        let blend_space = BlendSpace::new(
+            "Movement".to_string(),
+            vec![BlendSpacePoint::new(Vector2::new(1.0, 1.0), Handle::NONE)],
+        );
+        assert_weights(weights(&blend_space, 0.0, 0.0), &[1.0]);
+    }
+
+    #[test]
+    fn test_blend_space_grid_weights_are_continuous() {
+        let mut points = Vec::new();
+        for y in -1..=1 {
+            for x in -1..=1 {
+                points.push(BlendSpacePoint::new(
+                    Vector2::new(x as f32, y as f32),
+                    Handle::NONE,
+                ));
+            }
+        }
+        let blend_space = BlendSpace::new("Movement".to_string(), points);
+
+        // Every cell must be split by exactly one diagonal.
+        blend_space.fetch_weights(Vector2::default());
+        assert_eq!(blend_space.triangles.borrow().triangles.len(), 8);
+
+        // Weights are piecewise linear with unit slope at most, so a small step of the sampling
+        // point must give a small change of the weights, even when the step crosses a diagonal.
+        let step = 0.05;
+        let steps = (2.0 / step) as i32;
+        for iy in 0..=steps {
+            for ix in 0..=steps {
+                let x = -1.0 + ix as f32 * step;
+                let y = -1.0 + iy as f32 * step;
+                let current = weights(&blend_space, x, y);
+                assert!((current.iter().sum::<f32>() - 1.0).abs() < 1.0e-4);
+                for (dx, dy) in [(step, 0.0), (0.0, step), (step, step), (step, -step)] {
+                    let next = weights(&blend_space, x + dx, y + dy);
+                    for (a, b) in current.iter().zip(next.iter()) {
+                        assert!(
+                            (a - b).abs() <= 2.0 * step + 1.0e-4,
+                            "discontinuity at ({}, {}): {:?} -> {:?}",
+                            x,
+                            y,
+                            current,
+                            next
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -4,7 +4,11 @@
 use crate::{
     animation::{
         machine::{
-            node::{blend::BlendAnimations, play::PlayAnimation},
+            node::{
+                blend::BlendAnimations,
+                blendspace::{BlendSpace, BlendSpacePoint},
+                play::PlayAnimation,
+            },
             BlendAnimationsByIndex, BlendPose, IndexedBlendInput, ParameterContainer, State,
         },
         Animation, AnimationContainer, AnimationPose,
@@ -22,6 +26,7 @@ use std::{
 };
 
 pub mod blend;
+pub mod blendspace;
 pub mod play;
 
 /// A set of common data fields that is used in every node.
@@ -46,6 +51,9 @@ pub enum PoseNode {
 
     /// See docs for [`BlendAnimationsByIndex`].
     BlendAnimationsByIndex(BlendAnimationsByIndex),
+
+    /// See docs for [`BlendSpace`].
+    BlendSpace(BlendSpace),
 }
 
 impl Default for PoseNode {
@@ -74,6 +82,12 @@ impl PoseNode {
         Self::BlendAnimationsByIndex(BlendAnimationsByIndex::new(index_parameter, inputs))
     }
 
+    /// Creates new node that blends animations of the points of a 2D blend space using a sampling
+    /// point parameter.
+    pub fn make_blend_space(sampling_parameter: String, points: Vec<BlendSpacePoint>) -> Self {
+        Self::BlendSpace(BlendSpace::new(sampling_parameter, points))
+    }
+
     /// Returns a set of handles to children pose nodes.
     pub fn children(&self) -> Vec<Handle<PoseNode>> {
         match self {
@@ -83,6 +97,7 @@ impl PoseNode {
             }
             Self::BlendAnimations(definition) => definition.children(),
             Self::BlendAnimationsByIndex(definition) => definition.children(),
+            Self::BlendSpace(definition) => definition.children(),
         }
     }
 }
@@ -93,6 +108,7 @@ macro_rules! static_dispatch {
             PoseNode::PlayAnimation(v) => v.$func($($args),*),
             PoseNode::BlendAnimations(v) => v.$func($($args),*),
             PoseNode::BlendAnimationsByIndex(v) => v.$func($($args),*),
+            PoseNode::BlendSpace(v) => v.$func($($args),*),
         }
     };
 }
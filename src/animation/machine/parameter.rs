@@ -1,6 +1,6 @@
 //! Parameter is a name variable of a fixed type. See [`Parameter`] docs for more info.
 
-use crate::core::{reflect::prelude::*, visitor::prelude::*};
+use crate::core::{algebra::Vector2, reflect::prelude::*, visitor::prelude::*};
 use fxhash::FxHashMap;
 use std::{
     cell::{Cell, RefCell},
@@ -21,6 +21,10 @@ pub enum Parameter {
 
     /// An index of a pose.
     Index(u32),
+
+    /// A point in 2D space, it is used to sample blend spaces (see
+    /// [`crate::animation::machine::node::blendspace::BlendSpace`]).
+    SamplingPoint(Vector2<f32>),
 }
 
 impl Default for Parameter {
@@ -9,7 +9,10 @@ pub mod resource_manager;
 
 use crate::{
     asset::ResourceState,
-    core::{algebra::Vector2, futures::executor::block_on, instant, pool::Handle},
+    core::{
+        algebra::Vector2, futures::executor::block_on, instant, pool::Handle,
+        reflect::default_registry::DefaultRegistry,
+    },
     engine::{
         error::EngineError,
         resource_manager::{container::event::ResourceEvent, ResourceManager, ResourceWaitContext},
@@ -24,14 +27,14 @@ use crate::{
     resource::{model::Model, texture::TextureKind},
     scene::{
         base::NodeScriptMessage,
-        graph::GraphUpdateSwitches,
+        graph::{Graph, GraphUpdateSwitches, SubGraph},
         node::{constructor::NodeConstructorContainer, Node},
         sound::SoundEngine,
         Scene, SceneContainer,
     },
     script::{
-        constructor::ScriptConstructorContainer, RoutingStrategy, Script, ScriptContext,
-        ScriptDeinitContext, ScriptMessage, ScriptMessageContext, ScriptMessageKind,
+        constructor::ScriptConstructorContainer, RoutingStrategy, ScheduledScriptMessage, Script,
+        ScriptContext, ScriptDeinitContext, ScriptMessage, ScriptMessageContext, ScriptMessageKind,
         ScriptMessageSender,
     },
     utils::log::Log,
@@ -40,9 +43,9 @@ use crate::{
 use fxhash::{FxHashMap, FxHashSet};
 use std::{
     any::TypeId,
-    collections::{HashSet, VecDeque},
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet, VecDeque},
     fmt::{Display, Formatter},
-    ops::Deref,
     sync::{
         mpsc::{channel, Receiver},
         Arc, Mutex,
@@ -57,6 +60,8 @@ pub struct SerializationContext {
     pub node_constructors: NodeConstructorContainer,
     /// A script constructor container.
     pub script_constructors: ScriptConstructorContainer,
+    /// A container of default value constructors for reflected types.
+    pub default_registry: DefaultRegistry,
 }
 
 impl Default for SerializationContext {
@@ -71,6 +76,7 @@ impl SerializationContext {
         Self {
             node_constructors: NodeConstructorContainer::new(),
             script_constructors: ScriptConstructorContainer::new(),
+            default_registry: DefaultRegistry::new(),
         }
     }
 }
@@ -137,6 +143,12 @@ pub struct Engine {
     // Amount of time (in seconds) that passed from creation of the engine.
     elapsed_time: f32,
 
+    // Index of the current frame, it is incremented once per engine update.
+    frame_index: u64,
+
+    // Time step of the previous frame.
+    previous_dt: f32,
+
     /// A special container that is able to create nodes by their type UUID. Use a copy of this
     /// value whenever you need it as a parameter in other parts of the engine.
     pub serialization_context: Arc<SerializationContext>,
@@ -144,17 +156,55 @@ pub struct Engine {
     script_processor: ScriptProcessor,
 }
 
+/// A delayed script message that waits for its delivery time.
+struct PendingScriptMessage {
+    deliver_at: f32,
+    // Sequential index of the message, it is used to preserve sending order.
+    index: u64,
+    message: ScriptMessage,
+}
+
+impl PartialEq for PendingScriptMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingScriptMessage {}
+
+impl PartialOrd for PendingScriptMessage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingScriptMessage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed order, because binary heap is a max-heap and we need the earliest message
+        // on top of it.
+        other
+            .deliver_at
+            .partial_cmp(&self.deliver_at)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
 /// Performs dispatch of script messages.
 pub struct ScriptMessageDispatcher {
     type_groups: FxHashMap<TypeId, FxHashSet<Handle<Node>>>,
-    message_receiver: Receiver<ScriptMessage>,
+    message_receiver: Receiver<ScheduledScriptMessage>,
+    delayed_messages: BinaryHeap<PendingScriptMessage>,
+    message_counter: u64,
 }
 
 impl ScriptMessageDispatcher {
-    fn new(message_receiver: Receiver<ScriptMessage>) -> Self {
+    fn new(message_receiver: Receiver<ScheduledScriptMessage>) -> Self {
         Self {
             type_groups: Default::default(),
             message_receiver,
+            delayed_messages: Default::default(),
+            message_counter: 0,
         }
     }
 
@@ -184,7 +234,63 @@ impl ScriptMessageDispatcher {
     }
 
     fn dispatch_messages(
+        &mut self,
+        scene: &mut Scene,
+        plugins: &mut Vec<Box<dyn Plugin>>,
+        resource_manager: &ResourceManager,
+        dt: f32,
+        elapsed_time: f32,
+        message_sender: &ScriptMessageSender,
+    ) {
+        // Deliver delayed messages first. Messages, whose delay has elapsed on the same tick, are
+        // delivered in the order they were sent.
+        let mut due_messages = Vec::new();
+        while self
+            .delayed_messages
+            .peek()
+            .map_or(false, |m| m.deliver_at <= elapsed_time)
+        {
+            due_messages.extend(self.delayed_messages.pop());
+        }
+        due_messages.sort_by_key(|m| m.index);
+        for pending in due_messages {
+            self.dispatch_message(
+                pending.message,
+                scene,
+                plugins,
+                resource_manager,
+                dt,
+                elapsed_time,
+                message_sender,
+            );
+        }
+
+        while let Ok(scheduled) = self.message_receiver.try_recv() {
+            if scheduled.delay > 0.0 {
+                self.delayed_messages.push(PendingScriptMessage {
+                    deliver_at: elapsed_time + scheduled.delay,
+                    index: self.message_counter,
+                    message: scheduled.message,
+                });
+                self.message_counter += 1;
+            } else {
+                self.dispatch_message(
+                    scheduled.message,
+                    scene,
+                    plugins,
+                    resource_manager,
+                    dt,
+                    elapsed_time,
+                    message_sender,
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch_message(
         &self,
+        message: ScriptMessage,
         scene: &mut Scene,
         plugins: &mut Vec<Box<dyn Plugin>>,
         resource_manager: &ResourceManager,
@@ -192,74 +298,92 @@ impl ScriptMessageDispatcher {
         elapsed_time: f32,
         message_sender: &ScriptMessageSender,
     ) {
-        while let Ok(message) = self.message_receiver.try_recv() {
+        if let ScriptMessageKind::ScriptType(script_type) = message.kind {
+            let mut payload = message.payload;
+
+            let receivers = scene
+                .graph
+                .pair_iter()
+                .filter(|(_, node)| {
+                    node.script
+                        .as_ref()
+                        .map_or(false, |script| script.instance_type_id() == script_type)
+                })
+                .map(|(handle, _)| handle)
+                .collect::<Vec<_>>();
+
+            for node in receivers {
+                let mut context = ScriptMessageContext {
+                    dt,
+                    elapsed_time,
+                    plugins,
+                    handle: node,
+                    scene,
+                    resource_manager,
+                    message_sender,
+                };
+
+                process_node_message(&mut context, &mut |s, ctx| s.on_message(&mut *payload, ctx));
+            }
+        } else if let Some(receivers) = self.type_groups.get(&message.payload_type_id()) {
             let mut payload = message.payload;
-            if let Some(receivers) = self.type_groups.get(&payload.deref().type_id()) {
-                match message.kind {
-                    ScriptMessageKind::Targeted(target) => {
-                        if receivers.contains(&target) {
+            match message.kind {
+                ScriptMessageKind::Targeted(target) => {
+                    if receivers.contains(&target) {
+                        let mut context = ScriptMessageContext {
+                            dt,
+                            elapsed_time,
+                            plugins,
+                            handle: target,
+                            scene,
+                            resource_manager,
+                            message_sender,
+                        };
+
+                        process_node_message(&mut context, &mut |s, ctx| {
+                            s.on_message(&mut *payload, ctx)
+                        })
+                    }
+                }
+                ScriptMessageKind::Hierarchical { root, routing } => {
+                    let (up, max_depth) = match routing {
+                        RoutingStrategy::Up => (true, usize::MAX),
+                        RoutingStrategy::Down => (false, usize::MAX),
+                        RoutingStrategy::UpLimited { max_depth } => (true, max_depth),
+                        RoutingStrategy::DownLimited { max_depth } => (false, max_depth),
+                    };
+
+                    if up {
+                        let mut node = root;
+                        let mut depth = 0;
+                        while let Some(node_ref) = scene.graph.try_get(node) {
+                            if depth > max_depth {
+                                break;
+                            }
+
+                            let parent = node_ref.parent();
+
                             let mut context = ScriptMessageContext {
                                 dt,
                                 elapsed_time,
                                 plugins,
-                                handle: target,
+                                handle: node,
                                 scene,
                                 resource_manager,
                                 message_sender,
                             };
 
-                            process_node_message(&mut context, &mut |s, ctx| {
-                                s.on_message(&mut *payload, ctx)
-                            })
-                        }
-                    }
-                    ScriptMessageKind::Hierarchical { root, routing } => match routing {
-                        RoutingStrategy::Up => {
-                            let mut node = root;
-                            while let Some(node_ref) = scene.graph.try_get(node) {
-                                let parent = node_ref.parent();
-
-                                let mut context = ScriptMessageContext {
-                                    dt,
-                                    elapsed_time,
-                                    plugins,
-                                    handle: node,
-                                    scene,
-                                    resource_manager,
-                                    message_sender,
-                                };
-
-                                if receivers.contains(&node) {
-                                    process_node_message(&mut context, &mut |s, ctx| {
-                                        s.on_message(&mut *payload, ctx)
-                                    });
-                                }
-
-                                node = parent;
-                            }
-                        }
-                        RoutingStrategy::Down => {
-                            for node in scene.graph.traverse_handle_iter(root).collect::<Vec<_>>() {
-                                let mut context = ScriptMessageContext {
-                                    dt,
-                                    elapsed_time,
-                                    plugins,
-                                    handle: node,
-                                    scene,
-                                    resource_manager,
-                                    message_sender,
-                                };
-
-                                if receivers.contains(&node) {
-                                    process_node_message(&mut context, &mut |s, ctx| {
-                                        s.on_message(&mut *payload, ctx)
-                                    });
-                                }
+                            if receivers.contains(&node) {
+                                process_node_message(&mut context, &mut |s, ctx| {
+                                    s.on_message(&mut *payload, ctx)
+                                });
                             }
+
+                            node = parent;
+                            depth += 1;
                         }
-                    },
-                    ScriptMessageKind::Global => {
-                        for &node in receivers {
+                    } else {
+                        for node in collect_descendants(&scene.graph, root, max_depth) {
                             let mut context = ScriptMessageContext {
                                 dt,
                                 elapsed_time,
@@ -270,21 +394,77 @@ impl ScriptMessageDispatcher {
                                 message_sender,
                             };
 
-                            process_node_message(&mut context, &mut |s, ctx| {
-                                s.on_message(&mut *payload, ctx)
-                            });
+                            if receivers.contains(&node) {
+                                process_node_message(&mut context, &mut |s, ctx| {
+                                    s.on_message(&mut *payload, ctx)
+                                });
+                            }
                         }
                     }
                 }
+                ScriptMessageKind::Global => {
+                    for &node in receivers {
+                        let mut context = ScriptMessageContext {
+                            dt,
+                            elapsed_time,
+                            plugins,
+                            handle: node,
+                            scene,
+                            resource_manager,
+                            message_sender,
+                        };
+
+                        process_node_message(&mut context, &mut |s, ctx| {
+                            s.on_message(&mut *payload, ctx)
+                        });
+                    }
+                }
+                // Handled above.
+                ScriptMessageKind::ScriptType(_) => (),
+            }
+        }
+    }
+}
+
+// Collects handles of the given node and all its descendants that are at most `max_depth` levels
+// below it, in depth-first order.
+fn collect_descendants(graph: &Graph, root: Handle<Node>, max_depth: usize) -> Vec<Handle<Node>> {
+    let mut handles = Vec::new();
+    let mut stack = vec![(root, 0)];
+    while let Some((handle, depth)) = stack.pop() {
+        if let Some(node) = graph.try_get(handle) {
+            handles.push(handle);
+
+            if depth < max_depth {
+                stack.extend(node.children().iter().map(|child| (*child, depth + 1)));
             }
         }
     }
+    handles
 }
 
 pub(crate) struct ScriptedScene {
     handle: Handle<Scene>,
     message_sender: ScriptMessageSender,
     message_dispatcher: ScriptMessageDispatcher,
+    // Scripts that requested to be kept alive in `on_deinit`, they'll be destroyed on next frame.
+    kept_alive: Vec<(Handle<Node>, Script)>,
+    // Removed nodes that were put back to the graph, because their scripts requested keep alive.
+    pending_removal: Vec<NodeRemoval>,
+}
+
+// A node that was removed from the graph, but its scripts are not deinitialized yet. See
+// `ScriptDeinitContext::request_keep_alive` for more info.
+struct NodeRemoval {
+    node: RemovedNode,
+    scripts: Vec<(Handle<Node>, Script)>,
+}
+
+enum RemovedNode {
+    // The hierarchy is out of the graph, its handles are reserved.
+    Reserved(SubGraph),
+    // The hierarchy was put back to the graph, because some of its scripts requested keep alive.
+    KeptAlive(Handle<Node>),
 }
 
 #[derive(Default)]
@@ -312,6 +492,8 @@ impl ScriptProcessor {
             handle: scene,
             message_sender: ScriptMessageSender { sender: tx },
             message_dispatcher: ScriptMessageDispatcher::new(rx),
+            kept_alive: Default::default(),
+            pending_removal: Default::default(),
         });
 
         let graph = &mut scenes[scene].graph;
@@ -336,6 +518,8 @@ impl ScriptProcessor {
         resource_manager: &ResourceManager,
         dt: f32,
         elapsed_time: f32,
+        frame_index: u64,
+        previous_dt: f32,
     ) {
         self.wait_list
             .retain_mut(|context| !context.is_all_loaded());
@@ -365,16 +549,28 @@ impl ScriptProcessor {
                 }
             }
 
+            // Physics step is consumed here, so scripts will receive it exactly once.
+            let physics_step = scene.graph.pending_physics_step.take();
+
             // We'll gather all scripts queued for destruction and destroy them all at once at the
             // end of the frame.
             let mut destruction_queue = VecDeque::new();
 
+            // Scripts that were kept alive on previous frame must be polled again.
+            destruction_queue.extend(scripted_scene.kept_alive.drain(..));
+            let mut node_destruction_queue = scripted_scene
+                .pending_removal
+                .drain(..)
+                .collect::<VecDeque<_>>();
+
             let max_iterations = 64;
 
             'update_loop: for update_loop_iteration in 0..max_iterations {
                 let mut context = ScriptContext {
                     dt,
                     elapsed_time,
+                    frame_index,
+                    previous_dt,
                     plugins,
                     handle: Default::default(),
                     scene,
@@ -407,6 +603,14 @@ impl ScriptProcessor {
                                 // Destruction is delayed to the end of the frame.
                                 destruction_queue.push_back((handle, script));
                             }
+                            NodeScriptMessage::DestroyNode {
+                                sub_graph, scripts, ..
+                            } => {
+                                node_destruction_queue.push_back(NodeRemoval {
+                                    node: RemovedNode::Reserved(sub_graph),
+                                    scripts,
+                                });
+                            }
                         }
                     }
 
@@ -443,6 +647,25 @@ impl ScriptProcessor {
                 if update_queue.is_empty() {
                     break 'update_loop;
                 } else {
+                    // Scripts are updated in a defined order to keep the simulation deterministic.
+                    sort_update_queue(&mut update_queue, &context.scene.graph);
+
+                    // Physics update goes first, so the scripts will see the results of the physics
+                    // step in `on_update`.
+                    if let Some(physics_step) = physics_step {
+                        context.dt = physics_step;
+
+                        for &handle in update_queue.iter() {
+                            context.handle = handle;
+
+                            process_node(&mut context, &mut |script, context| {
+                                script.on_physics_update(context);
+                            });
+                        }
+
+                        context.dt = dt;
+                    }
+
                     while let Some(handle) = update_queue.pop_front() {
                         context.handle = handle;
 
@@ -480,9 +703,11 @@ impl ScriptProcessor {
                 scene,
                 node_handle: Default::default(),
                 message_sender: &scripted_scene.message_sender,
+                keep_alive: false,
             };
             while let Some((handle, mut script)) = destruction_queue.pop_front() {
                 context.node_handle = handle;
+                context.keep_alive = false;
 
                 // Unregister self in message dispatcher.
                 scripted_scene.message_dispatcher.unsubscribe(handle);
@@ -490,12 +715,54 @@ impl ScriptProcessor {
                 // `on_deinit` could also spawn new nodes, but we won't take those into account on
                 // this frame. They'll be correctly handled on next frame.
                 script.on_deinit(&mut context);
+
+                if context.keep_alive {
+                    scripted_scene.kept_alive.push((handle, script));
+                }
+            }
+
+            // Removed nodes are put back only when some of their scripts requested keep alive.
+            while let Some(removal) = node_destruction_queue.pop_front() {
+                let mut kept_scripts = Vec::new();
+                for (handle, mut script) in removal.scripts {
+                    context.node_handle = handle;
+                    context.keep_alive = false;
+
+                    scripted_scene.message_dispatcher.unsubscribe(handle);
+
+                    script.on_deinit(&mut context);
+
+                    if context.keep_alive {
+                        kept_scripts.push((handle, script));
+                    }
+                }
+
+                let graph = &mut context.scene.graph;
+                if kept_scripts.is_empty() {
+                    match removal.node {
+                        RemovedNode::Reserved(sub_graph) => graph.forget_sub_graph(sub_graph),
+                        RemovedNode::KeptAlive(handle) => graph.destroy_kept_alive_node(handle),
+                    }
+                } else {
+                    let handle = match removal.node {
+                        RemovedNode::Reserved(sub_graph) => {
+                            graph.put_kept_alive_sub_graph_back(sub_graph)
+                        }
+                        RemovedNode::KeptAlive(handle) => handle,
+                    };
+                    scripted_scene.pending_removal.push(NodeRemoval {
+                        node: RemovedNode::KeptAlive(handle),
+                        scripts: kept_scripts,
+                    });
+                }
             }
         }
 
         // Process scripts from destroyed scenes.
         for (handle, mut detached_scene) in scenes.destruction_list.drain(..) {
-            if let Some(scripted_scene) = self.scripted_scenes.iter().find(|s| s.handle == handle) {
+            if let Some(scripted_scene) =
+                self.scripted_scenes.iter_mut().find(|s| s.handle == handle)
+            {
                 let mut context = ScriptDeinitContext {
                     elapsed_time,
                     plugins,
@@ -503,8 +770,21 @@ impl ScriptProcessor {
                     scene: &mut detached_scene,
                     node_handle: Default::default(),
                     message_sender: &scripted_scene.message_sender,
+                    keep_alive: false,
                 };
 
+                // The scene is destroyed, so kept alive scripts can't be deferred anymore.
+                let pending_scripts = scripted_scene
+                    .pending_removal
+                    .drain(..)
+                    .flat_map(|removal| removal.scripts);
+                for (handle, mut script) in
+                    scripted_scene.kept_alive.drain(..).chain(pending_scripts)
+                {
+                    context.node_handle = handle;
+                    script.on_deinit(&mut context);
+                }
+
                 // Destroy every script instance from nodes that were still alive.
                 for node_index in 0..context.scene.graph.capacity() {
                     context.node_handle = context.scene.graph.handle_from_index(node_index);
@@ -661,6 +941,19 @@ macro_rules! define_process_node {
 define_process_node!(process_node, ScriptContext);
 define_process_node!(process_node_message, ScriptMessageContext);
 
+/// Sorts the queue by [`crate::script::ScriptTrait::execution_order`] of the scripts, ties are
+/// broken by node handles.
+fn sort_update_queue(update_queue: &mut VecDeque<Handle<Node>>, graph: &Graph) {
+    update_queue.make_contiguous().sort_by_key(|handle| {
+        let execution_order = graph
+            .try_get(*handle)
+            .and_then(|node| node.script.as_ref())
+            .map_or(0, |script| script.execution_order());
+
+        (execution_order, handle.index(), handle.generation())
+    });
+}
+
 pub(crate) fn process_scripts<T>(
     scene: &mut Scene,
     plugins: &mut [Box<dyn Plugin>],
@@ -669,6 +962,8 @@ pub(crate) fn process_scripts<T>(
     message_dispatcher: &mut ScriptMessageDispatcher,
     dt: f32,
     elapsed_time: f32,
+    frame_index: u64,
+    previous_dt: f32,
     mut func: T,
 ) where
     T: FnMut(&mut Script, &mut ScriptContext),
@@ -676,6 +971,8 @@ pub(crate) fn process_scripts<T>(
     let mut context = ScriptContext {
         dt,
         elapsed_time,
+        frame_index,
+        previous_dt,
         plugins,
         handle: Default::default(),
         scene,
@@ -843,6 +1140,8 @@ impl Engine {
             plugins_enabled: false,
             plugin_constructors: Default::default(),
             elapsed_time: 0.0,
+            frame_index: 0,
+            previous_dt: 0.0,
         })
     }
 
@@ -864,6 +1163,12 @@ impl Engine {
         self.elapsed_time
     }
 
+    /// Index of the current frame. It is incremented once per [`Self::update`] (or rather
+    /// [`Self::post_update`]) call.
+    pub fn frame_index(&self) -> u64 {
+        self.frame_index
+    }
+
     /// Returns reference to main window. Could be useful to set fullscreen mode, change
     /// size of window, its title, etc.
     #[inline]
@@ -922,6 +1227,7 @@ impl Engine {
         self.resource_manager.state().update(dt);
         self.renderer.update_caches(dt);
         self.handle_model_events();
+        self.report_sound_device_error();
 
         for (handle, scene) in self.scenes.pair_iter_mut().filter(|(_, s)| s.enabled) {
             let frame_size = scene.render_target.as_ref().map_or(window_size, |rt| {
@@ -943,6 +1249,21 @@ impl Engine {
         self.handle_scripts(dt);
     }
 
+    // Sound output device is created on the mixer thread, if it fails the sound engine falls back
+    // to silent device and stores the error, it is reported here. `try_lock` is used to not wait
+    // for the mixer, the error will be reported on next update.
+    fn report_sound_device_error(&self) {
+        if let Ok(mut sound_engine) = self.sound_engine.try_lock() {
+            if let Some(error) = sound_engine.take_device_error() {
+                Log::warn(format!(
+                    "Unable to initialize sound output device, falling back to silent dummy \
+                    device. Reason: {}",
+                    error
+                ));
+            }
+        }
+    }
+
     /// Performs post update for the engine.
     ///
     /// Normally, this is called from `Engine::update()`.
@@ -955,6 +1276,8 @@ impl Engine {
         self.user_interface.update(window_size, dt);
         self.performance_statistics.ui_time = instant::Instant::now() - time;
         self.elapsed_time += dt;
+        self.frame_index += 1;
+        self.previous_dt = dt;
     }
 
     /// Returns true if the scene is registered for script processing.
@@ -979,6 +1302,8 @@ impl Engine {
             &self.resource_manager,
             dt,
             self.elapsed_time,
+            self.frame_index,
+            self.previous_dt,
         );
         self.performance_statistics.scripts_time = instant::Instant::now() - time;
     }
@@ -1092,6 +1417,8 @@ impl Engine {
                     &mut scripted_scene.message_dispatcher,
                     dt,
                     self.elapsed_time,
+                    self.frame_index,
+                    self.previous_dt,
                     |script, context| {
                         if script.initialized {
                             script.on_os_event(event, context);
@@ -1248,11 +1575,20 @@ impl Drop for Engine {
 mod test {
     use crate::script::{ScriptMessageContext, ScriptMessagePayload};
     use crate::{
-        core::{pool::Handle, reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
+        animation::{Animation, AnimationContainer},
+        core::{
+            algebra::Vector2, pool::Handle, reflect::prelude::*, uuid::Uuid, visitor::prelude::*,
+        },
         engine::{resource_manager::ResourceManager, ScriptProcessor},
         impl_component_provider,
-        scene::{base::BaseBuilder, node::Node, pivot::PivotBuilder, Scene, SceneContainer},
-        script::{Script, ScriptContext, ScriptDeinitContext, ScriptTrait},
+        scene::{
+            animation::{AnimationPlayer, AnimationPlayerBuilder},
+            base::BaseBuilder,
+            node::Node,
+            pivot::PivotBuilder,
+            Scene, SceneContainer,
+        },
+        script::{RoutingStrategy, Script, ScriptContext, ScriptDeinitContext, ScriptTrait},
     };
     use std::sync::mpsc::{self, Sender, TryRecvError};
 
@@ -1392,6 +1728,8 @@ mod test {
                 &resource_manager,
                 0.0,
                 0.0,
+                0,
+                0.0,
             );
 
             match iteration {
@@ -1442,6 +1780,147 @@ mod test {
         }
     }
 
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct KeepAliveScript {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<Event>,
+        frames_to_keep_alive: u32,
+    }
+
+    impl_component_provider!(KeepAliveScript);
+
+    impl ScriptTrait for KeepAliveScript {
+        fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+            self.sender.send(Event::Destroyed(ctx.node_handle)).unwrap();
+
+            if self.frames_to_keep_alive > 0 {
+                self.frames_to_keep_alive -= 1;
+                ctx.request_keep_alive();
+            }
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_keep_alive_on_deinit() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let node_handle =
+            PivotBuilder::new(BaseBuilder::new().with_script(Script::new(KeepAliveScript {
+                sender: tx,
+                frames_to_keep_alive: 2,
+            })))
+            .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        let mut handle_scripts = |scene_container: &mut SceneContainer| {
+            script_processor.handle_scripts(
+                scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+                0,
+                0.0,
+            )
+        };
+
+        handle_scripts(&mut scene_container);
+        scene_container[scene_handle].graph.remove_node(node_handle);
+        assert!(!scene_container[scene_handle]
+            .graph
+            .is_valid_handle(node_handle));
+
+        // The script requests keep alive twice, so it is polled on three frames in total and the
+        // node is put back to the graph (hidden and disabled) until the last one.
+        for frame in 0..3 {
+            handle_scripts(&mut scene_container);
+            assert_eq!(rx.try_recv(), Ok(Event::Destroyed(node_handle)));
+            assert!(rx.try_recv().is_err());
+
+            let graph = &scene_container[scene_handle].graph;
+            assert_eq!(graph.is_valid_handle(node_handle), frame < 2);
+            if frame < 2 {
+                assert!(!graph[node_handle].visibility());
+                assert!(!graph[node_handle].is_enabled());
+            }
+        }
+
+        handle_scripts(&mut scene_container);
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn test_remove_scripted_node_without_keep_alive() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let child_handle =
+            PivotBuilder::new(BaseBuilder::new().with_script(Script::new(KeepAliveScript {
+                sender: tx,
+                frames_to_keep_alive: 0,
+            })))
+            .build(&mut scene.graph);
+        let node_handle = PivotBuilder::new(BaseBuilder::new().with_children(&[child_handle]))
+            .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        let mut handle_scripts = |scene_container: &mut SceneContainer| {
+            script_processor.handle_scripts(
+                scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+                0,
+                0.0,
+            )
+        };
+
+        handle_scripts(&mut scene_container);
+
+        // The node and its scripted child are freed right away.
+        let graph = &mut scene_container[scene_handle].graph;
+        graph.remove_node(node_handle);
+        assert!(!graph.is_valid_handle(node_handle));
+        assert!(!graph.is_valid_handle(child_handle));
+
+        handle_scripts(&mut scene_container);
+        assert_eq!(rx.try_recv(), Ok(Event::Destroyed(child_handle)));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+
+        let graph = &scene_container[scene_handle].graph;
+        assert!(!graph.is_valid_handle(node_handle));
+        assert!(!graph.is_valid_handle(child_handle));
+    }
+
     enum MyMessage {
         Foo(usize),
         Bar(String),
@@ -1559,6 +2038,8 @@ mod test {
                 &resource_manager,
                 0.0,
                 0.0,
+                0,
+                0.0,
             );
 
             match iteration {
@@ -1574,4 +2055,595 @@ mod test {
             }
         }
     }
+
+    struct DelayedMessage(u32);
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptSendingDelayedMessages {
+        sent: bool,
+    }
+
+    impl_component_provider!(ScriptSendingDelayedMessages);
+
+    impl ScriptTrait for ScriptSendingDelayedMessages {
+        fn on_update(&mut self, ctx: &mut ScriptContext) {
+            if !self.sent {
+                ctx.message_sender
+                    .send_global_delayed(DelayedMessage(1), 2.0);
+                ctx.message_sender
+                    .send_global_delayed(DelayedMessage(2), 1.5);
+                ctx.message_sender
+                    .send_global_delayed(DelayedMessage(3), 3.0);
+                self.sent = true;
+            }
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptListeningToDelayedMessages {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<u32>,
+    }
+
+    impl_component_provider!(ScriptListeningToDelayedMessages);
+
+    impl ScriptTrait for ScriptListeningToDelayedMessages {
+        fn on_start(&mut self, ctx: &mut ScriptContext) {
+            ctx.message_dispatcher
+                .subscribe_to::<DelayedMessage>(ctx.handle);
+        }
+
+        fn on_message(
+            &mut self,
+            message: &mut dyn ScriptMessagePayload,
+            _ctx: &mut ScriptMessageContext,
+        ) {
+            let DelayedMessage(value) = message.downcast_ref::<DelayedMessage>().unwrap();
+            self.sender.send(*value).unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_delayed_messages() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_script(Script::new(ScriptSendingDelayedMessages { sent: false })),
+        )
+        .build(&mut scene.graph);
+
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_script(Script::new(ScriptListeningToDelayedMessages { sender: tx })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for elapsed_time in [0.0, 1.0, 2.5, 3.5] {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                elapsed_time,
+                0,
+                0.0,
+            );
+
+            if elapsed_time == 2.5 {
+                // Both messages are due on this tick, they must be delivered in sending order.
+                assert_eq!(rx.try_recv(), Ok(1));
+                assert_eq!(rx.try_recv(), Ok(2));
+            } else if elapsed_time == 3.5 {
+                assert_eq!(rx.try_recv(), Ok(3));
+            }
+
+            assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum PhysicsEvent {
+        PhysicsUpdated(f32),
+        Updated(f32),
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct PhysicsScript {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<PhysicsEvent>,
+    }
+
+    impl_component_provider!(PhysicsScript);
+
+    impl ScriptTrait for PhysicsScript {
+        fn on_physics_update(&mut self, ctx: &mut ScriptContext) {
+            self.sender
+                .send(PhysicsEvent::PhysicsUpdated(ctx.dt))
+                .unwrap();
+        }
+
+        fn on_update(&mut self, ctx: &mut ScriptContext) {
+            self.sender.send(PhysicsEvent::Updated(ctx.dt)).unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_physics_update() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+        scene.graph.physics.integration_parameters.dt = Some(0.25);
+
+        let (tx, rx) = mpsc::channel();
+
+        PivotBuilder::new(
+            BaseBuilder::new().with_script(Script::new(PhysicsScript { sender: tx })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for iteration in 0..3u64 {
+            if iteration == 1 {
+                scene_container[scene_handle].graph.update(
+                    Vector2::new(100.0, 100.0),
+                    0.5,
+                    Default::default(),
+                );
+            }
+
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.5,
+                0.0,
+                iteration,
+                0.5,
+            );
+
+            if iteration == 1 {
+                // Physics update must be called once per physics step with physics time step and
+                // before `on_update`.
+                assert_eq!(rx.try_recv(), Ok(PhysicsEvent::PhysicsUpdated(0.25)));
+            }
+            assert_eq!(rx.try_recv(), Ok(PhysicsEvent::Updated(0.5)));
+            assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        }
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct OrderedScript {
+        execution_order: i32,
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<Handle<Node>>,
+    }
+
+    impl_component_provider!(OrderedScript);
+
+    impl ScriptTrait for OrderedScript {
+        fn on_update(&mut self, ctx: &mut ScriptContext) {
+            self.sender.send(ctx.handle).unwrap();
+        }
+
+        fn execution_order(&self) -> i32 {
+            self.execution_order
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_execution_order() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let handles = [5, 0, -3, 0, 5]
+            .into_iter()
+            .map(|execution_order| {
+                PivotBuilder::new(BaseBuilder::new().with_script(Script::new(OrderedScript {
+                    execution_order,
+                    sender: tx.clone(),
+                })))
+                .build(&mut scene.graph)
+            })
+            .collect::<Vec<_>>();
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        // Lower execution order goes first, ties are broken by handles.
+        let expected = [handles[2], handles[1], handles[3], handles[0], handles[4]];
+
+        for iteration in 0..3 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+                iteration,
+                0.0,
+            );
+
+            assert_eq!(rx.try_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    struct HierarchicalMessage;
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptListeningToHierarchicalMessages {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<Handle<Node>>,
+    }
+
+    impl_component_provider!(ScriptListeningToHierarchicalMessages);
+
+    impl ScriptTrait for ScriptListeningToHierarchicalMessages {
+        fn on_start(&mut self, ctx: &mut ScriptContext) {
+            ctx.message_dispatcher
+                .subscribe_to::<HierarchicalMessage>(ctx.handle);
+        }
+
+        fn on_message(
+            &mut self,
+            message: &mut dyn ScriptMessagePayload,
+            ctx: &mut ScriptMessageContext,
+        ) {
+            assert!(message.downcast_ref::<HierarchicalMessage>().is_some());
+            self.sender.send(ctx.handle).unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_depth_limited_hierarchical_messages() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        // Build a 4-level hierarchy: a -> b -> c -> d
+        let mut nodes = Vec::new();
+        let mut parent = Handle::NONE;
+        for _ in 0..4 {
+            let node = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(
+                ScriptListeningToHierarchicalMessages { sender: tx.clone() },
+            )))
+            .build(&mut scene.graph);
+            if parent.is_some() {
+                scene.graph.link_nodes(node, parent);
+            }
+            nodes.push(node);
+            parent = node;
+        }
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        let mut deliver = |routing: RoutingStrategy, root: Handle<Node>| {
+            script_processor.scripted_scenes[0]
+                .message_sender
+                .send_hierarchical(root, routing, HierarchicalMessage);
+
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+                0,
+                0.0,
+            );
+
+            rx.try_iter().collect::<Vec<_>>()
+        };
+
+        // The first update initializes scripts and subscribes them to the messages.
+        deliver(RoutingStrategy::DownLimited { max_depth: 0 }, Handle::NONE);
+
+        assert_eq!(
+            deliver(RoutingStrategy::DownLimited { max_depth: 0 }, nodes[0]),
+            vec![nodes[0]]
+        );
+        assert_eq!(
+            deliver(RoutingStrategy::DownLimited { max_depth: 2 }, nodes[0]),
+            vec![nodes[0], nodes[1], nodes[2]]
+        );
+        assert_eq!(
+            deliver(RoutingStrategy::Down, nodes[0]),
+            vec![nodes[0], nodes[1], nodes[2], nodes[3]]
+        );
+        assert_eq!(
+            deliver(RoutingStrategy::UpLimited { max_depth: 0 }, nodes[3]),
+            vec![nodes[3]]
+        );
+        assert_eq!(
+            deliver(RoutingStrategy::UpLimited { max_depth: 1 }, nodes[3]),
+            vec![nodes[3], nodes[2]]
+        );
+        assert_eq!(
+            deliver(RoutingStrategy::Up, nodes[3]),
+            vec![nodes[3], nodes[2], nodes[1], nodes[0]]
+        );
+    }
+
+    struct BehaviorMessage;
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct TargetedBehaviorScript {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<Handle<Node>>,
+    }
+
+    impl_component_provider!(TargetedBehaviorScript);
+
+    impl ScriptTrait for TargetedBehaviorScript {
+        fn on_message(
+            &mut self,
+            message: &mut dyn ScriptMessagePayload,
+            ctx: &mut ScriptMessageContext,
+        ) {
+            assert!(message.downcast_ref::<BehaviorMessage>().is_some());
+            self.sender.send(ctx.handle).unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct OtherBehaviorScript {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<Handle<Node>>,
+    }
+
+    impl_component_provider!(OtherBehaviorScript);
+
+    impl ScriptTrait for OtherBehaviorScript {
+        fn on_start(&mut self, ctx: &mut ScriptContext) {
+            // Subscription must not affect messages targeted by script type.
+            ctx.message_dispatcher
+                .subscribe_to::<BehaviorMessage>(ctx.handle);
+        }
+
+        fn on_message(
+            &mut self,
+            _message: &mut dyn ScriptMessagePayload,
+            ctx: &mut ScriptMessageContext,
+        ) {
+            self.sender.send(ctx.handle).unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_messages_to_script_type() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut targeted = Vec::new();
+        for i in 0..4 {
+            let script = if i % 2 == 0 {
+                Script::new(TargetedBehaviorScript { sender: tx.clone() })
+            } else {
+                Script::new(OtherBehaviorScript { sender: tx.clone() })
+            };
+            let node =
+                PivotBuilder::new(BaseBuilder::new().with_script(script)).build(&mut scene.graph);
+            if i % 2 == 0 {
+                targeted.push(node);
+            }
+        }
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        let mut update = |script_processor: &mut ScriptProcessor| {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+                0,
+                0.0,
+            );
+        };
+
+        // The first update initializes scripts.
+        update(&mut script_processor);
+
+        script_processor.scripted_scenes[0]
+            .message_sender
+            .send_to_script_type::<TargetedBehaviorScript, _>(BehaviorMessage);
+
+        update(&mut script_processor);
+
+        let mut received = rx.try_iter().collect::<Vec<_>>();
+        received.sort_by_key(|handle| handle.index());
+        assert_eq!(received, targeted);
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptPlayingAnimations {
+        iteration: u32,
+    }
+
+    impl_component_provider!(ScriptPlayingAnimations);
+
+    impl ScriptTrait for ScriptPlayingAnimations {
+        fn on_update(&mut self, ctx: &mut ScriptContext) {
+            match self.iteration {
+                0 => {
+                    assert!(ctx.play_animation("Idle"));
+                    assert!(ctx.set_animation_speed("Idle", 2.0));
+                    assert!(!ctx.play_animation("Foobar"));
+                }
+                1 => {
+                    assert!(ctx.stop_animation("Idle"));
+                }
+                _ => (),
+            }
+            self.iteration += 1;
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_script_context_animations() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let mut animations = AnimationContainer::new();
+        let mut animation = Animation::default();
+        animation.set_name("Idle");
+        animation.set_time_slice(0.0..1.0);
+        animation.set_enabled(false);
+        let idle = animations.add(animation);
+
+        let animation_player = AnimationPlayerBuilder::new(BaseBuilder::new())
+            .with_animations(animations)
+            .build(&mut scene.graph);
+
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[animation_player])
+                .with_script(Script::new(ScriptPlayingAnimations { iteration: 0 })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        let idle_animation = |scene_container: &SceneContainer| {
+            scene_container[scene_handle].graph[animation_player]
+                .query_component_ref::<AnimationPlayer>()
+                .unwrap()
+                .animations()
+                .get(idle)
+                .clone()
+        };
+
+        for iteration in 0..2 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                0.0,
+                0.0,
+                iteration,
+                0.0,
+            );
+
+            let animation = idle_animation(&scene_container);
+            match iteration {
+                0 => {
+                    assert!(animation.is_enabled());
+                    assert_eq!(animation.speed(), 2.0);
+                }
+                1 => {
+                    assert!(!animation.is_enabled());
+                    assert_eq!(animation.time_position(), 0.0);
+                }
+                _ => (),
+            }
+        }
+    }
 }
@@ -136,6 +136,35 @@ impl AnimationBlendingStateMachine {
     pub fn animation_player(&self) -> Handle<Node> {
         *self.animation_player
     }
+
+    /// Sets weight of a layer with the given index. The weight defines how much the pose of the layer contributes
+    /// to the final pose of the machine, so it can be used to blend layers at runtime (for example to fade an
+    /// upper-body aiming layer in and out over a locomotion layer). Returns `false` if there is no such layer.
+    ///
+    /// # Notes
+    ///
+    /// This method does not mark the machine as modified, because layer weights are considered runtime state.
+    pub fn set_layer_weight(&mut self, layer_index: usize, weight: f32) -> bool {
+        if let Some(layer) = self
+            .machine
+            .get_value_mut_silent()
+            .layers_mut()
+            .get_mut(layer_index)
+        {
+            layer.set_weight(weight);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns weight of a layer with the given index, or [`None`] if there is no such layer.
+    pub fn layer_weight(&self, layer_index: usize) -> Option<f32> {
+        self.machine
+            .layers()
+            .get(layer_index)
+            .map(|layer| layer.weight())
+    }
 }
 
 impl TypeUuidProvider for AnimationBlendingStateMachine {
@@ -253,3 +282,112 @@ impl AnimationBlendingStateMachineBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::{
+            container::{TrackDataContainer, TrackValueKind},
+            machine::{Machine, MachineLayer, PlayAnimation, PoseNode, State},
+            track::Track,
+            value::ValueBinding,
+            Animation, AnimationContainer,
+        },
+        core::{
+            algebra::{Vector2, Vector3},
+            curve::{Curve, CurveKey, CurveKeyKind},
+            pool::Handle,
+        },
+        scene::{
+            animation::{
+                absm::{AnimationBlendingStateMachine, AnimationBlendingStateMachineBuilder},
+                AnimationPlayerBuilder,
+            },
+            base::BaseBuilder,
+            graph::{Graph, GraphUpdateSwitches},
+            node::Node,
+            pivot::PivotBuilder,
+        },
+    };
+
+    fn constant_position_animation(node: Handle<Node>, position: Vector3<f32>) -> Animation {
+        let mut frames = TrackDataContainer::new(TrackValueKind::Vector3);
+        for (curve, value) in frames.curves_mut().iter_mut().zip(position.iter()) {
+            *curve = Curve::from(vec![CurveKey::new(0.0, *value, CurveKeyKind::Constant)]);
+        }
+        let mut track = Track::new(frames, ValueBinding::Position);
+        track.set_target(node);
+
+        let mut animation = Animation::default();
+        animation.add_track(track);
+        animation.set_time_slice(0.0..1.0);
+        animation
+    }
+
+    fn add_layer(machine: &mut Machine, animation: Handle<Animation>) {
+        let mut layer = MachineLayer::new();
+        let node = layer.add_node(PoseNode::PlayAnimation(PlayAnimation::new(animation)));
+        let state = layer.add_state(State::new("State", node));
+        layer.set_entry_state(state);
+        machine.add_layer(layer);
+    }
+
+    #[test]
+    fn test_layer_weight_blending() {
+        let mut graph = Graph::new();
+
+        let animated_node = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let mut animations = AnimationContainer::new();
+        let locomotion = animations.add(constant_position_animation(
+            animated_node,
+            Vector3::new(1.0, 0.0, 0.0),
+        ));
+        let aiming = animations.add(constant_position_animation(
+            animated_node,
+            Vector3::new(0.0, 2.0, 0.0),
+        ));
+
+        let animation_player = AnimationPlayerBuilder::new(BaseBuilder::new())
+            .with_animations(animations)
+            .build(&mut graph);
+
+        let mut machine = Machine::new();
+        machine.layers_mut()[0].set_name("Locomotion");
+        add_layer(&mut machine, aiming);
+        let root_layer = &mut machine.layers_mut()[0];
+        let node = root_layer.add_node(PoseNode::PlayAnimation(PlayAnimation::new(locomotion)));
+        let state = root_layer.add_state(State::new("Locomotion", node));
+        root_layer.set_entry_state(state);
+
+        let absm = AnimationBlendingStateMachineBuilder::new(BaseBuilder::new())
+            .with_machine(machine)
+            .with_animation_player(animation_player)
+            .build(&mut graph);
+
+        let absm_ref = graph[absm]
+            .query_component_mut::<AnimationBlendingStateMachine>()
+            .unwrap();
+        assert!(absm_ref.set_layer_weight(1, 0.5));
+        assert!(!absm_ref.set_layer_weight(2, 0.5));
+        assert_eq!(absm_ref.layer_weight(1), Some(0.5));
+        assert!(!absm_ref.machine().is_modified());
+
+        graph.update(
+            Vector2::new(100.0, 100.0),
+            0.0,
+            GraphUpdateSwitches {
+                physics2d: false,
+                physics: false,
+                sound: false,
+                ..Default::default()
+            },
+        );
+
+        // The locomotion layer contributes with full weight, aiming layer - with half of its weight.
+        assert_eq!(
+            **graph[animated_node].local_transform().position(),
+            Vector3::new(1.0, 1.0, 0.0)
+        );
+    }
+}
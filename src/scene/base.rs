@@ -14,7 +14,7 @@ use crate::{
     },
     engine::SerializationContext,
     resource::model::Model,
-    scene::{node::Node, transform::Transform},
+    scene::{graph::SubGraph, node::Node, transform::Transform},
     script::{Script, ScriptTrait},
     utils::log::Log,
 };
@@ -271,6 +271,16 @@ pub enum NodeScriptMessage {
         /// Node handle.
         handle: Handle<Node>,
     },
+    /// A node with initialized scripts was removed from the graph. Handles of the removed
+    /// hierarchy stay reserved until every script is deinitialized.
+    DestroyNode {
+        /// Handle of the removed node.
+        handle: Handle<Node>,
+        /// The removed hierarchy.
+        sub_graph: SubGraph,
+        /// Scripts of the node and its descendants with handles of their nodes.
+        scripts: Vec<(Handle<Node>, Script)>,
+    },
 }
 
 /// Unique id of the node. It can be shared across multiple resources (read - prefabs), to preserve parent-child
@@ -375,6 +385,11 @@ pub struct Base {
     #[reflect(hidden)]
     pub(crate) global_transform: Cell<Matrix4<f32>>,
 
+    // Global transform from the previous update tick. It is `None` until the node was processed
+    // by the graph at least once. Non-serializable.
+    #[reflect(hidden)]
+    pub(crate) prev_global_transform: Cell<Option<Matrix4<f32>>>,
+
     // Bone-specific matrix. Non-serializable.
     #[reflect(hidden)]
     pub(crate) inv_bind_pose_transform: Matrix4<f32>,
@@ -612,6 +627,34 @@ impl Base {
         self.global_transform.get().position()
     }
 
+    /// Returns global transform matrix of the node from the previous update tick of the graph. If the
+    /// node was created on current tick, then this method returns current global transform.
+    #[inline]
+    pub fn prev_global_transform(&self) -> Matrix4<f32> {
+        self.prev_global_transform
+            .get()
+            .unwrap_or_else(|| self.global_transform.get())
+    }
+
+    /// Returns position of the node in absolute coordinates from the previous update tick of the
+    /// graph. See [`Self::prev_global_transform`] for more info.
+    #[inline]
+    pub fn prev_global_position(&self) -> Vector3<f32> {
+        self.prev_global_transform().position()
+    }
+
+    /// Calculates velocity of the node using its current and previous global positions, divided by
+    /// the given time step. It could be used to get velocity of nodes that are not driven by physics
+    /// (kinematic platforms, animated objects, etc.). Returns zero vector if `dt` is zero.
+    #[inline]
+    pub fn global_velocity(&self, dt: f32) -> Vector3<f32> {
+        if dt > 0.0 {
+            (self.global_position() - self.prev_global_position()).scale(1.0 / dt)
+        } else {
+            Vector3::default()
+        }
+    }
+
     /// Returns "look" vector of global transform basis, in most cases return vector will be non-normalized.
     #[inline]
     pub fn look_vector(&self) -> Vector3<f32> {
@@ -1109,6 +1152,7 @@ impl BaseBuilder {
             global_visibility: Cell::new(true),
             parent: Handle::NONE,
             global_transform: Cell::new(Matrix4::identity()),
+            prev_global_transform: Cell::new(None),
             inv_bind_pose_transform: self.inv_bind_pose_transform,
             resource: None,
             original_handle_in_resource: Handle::NONE,
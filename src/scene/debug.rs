@@ -21,6 +21,22 @@ pub struct Line {
     pub color: Color,
 }
 
+/// Creates a line from a listener to a sound source, colored by the occlusion amount of the source.
+/// Fully audible sources (`occlusion == 0.0`) produce green lines, fully occluded sources
+/// (`occlusion == 1.0`) - red lines, every value in between is interpolated. It is used to
+/// visualize occlusion raycasts when tuning sound occlusion parameters.
+pub fn sound_occlusion_line(
+    listener_position: Vector3<f32>,
+    source_position: Vector3<f32>,
+    occlusion: f32,
+) -> Line {
+    Line {
+        begin: listener_position,
+        end: source_position,
+        color: Color::GREEN.lerp(Color::RED, occlusion.clamp(0.0, 1.0)),
+    }
+}
+
 /// Drawing context for simple graphics, it allows you to draw simple figures using a set of lines. Most
 /// common use of the context is to draw some debug geometry in your game, draw physics info (contacts,
 /// meshes, shapes, etc.), draw temporary geometry in editor and so on.
@@ -1039,6 +1055,21 @@ impl SceneDrawingContext {
         }
     }
 
+    /// Draws a line from a listener to a sound source, colored by the occlusion amount of the source.
+    /// See [`sound_occlusion_line`] for more info.
+    pub fn draw_sound_occlusion(
+        &mut self,
+        listener_position: Vector3<f32>,
+        source_position: Vector3<f32>,
+        occlusion: f32,
+    ) {
+        self.add_line(sound_occlusion_line(
+            listener_position,
+            source_position,
+            occlusion,
+        ));
+    }
+
     /// Adds single line into internal buffer.
     pub fn add_line(&mut self, line: Line) {
         self.lines.push(line);
@@ -1050,3 +1081,34 @@ impl SceneDrawingContext {
         self.lines.clear()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, color::Color},
+        scene::debug::{sound_occlusion_line, SceneDrawingContext},
+    };
+
+    #[test]
+    fn test_sound_occlusion_line() {
+        let listener = Vector3::new(0.0, 1.0, 0.0);
+        let source = Vector3::new(5.0, 1.0, 2.0);
+
+        let line = sound_occlusion_line(listener, source, 0.0);
+        assert_eq!(line.begin, listener);
+        assert_eq!(line.end, source);
+        assert_eq!(line.color, Color::GREEN);
+
+        let line = sound_occlusion_line(listener, source, 1.0);
+        assert_eq!(line.color, Color::RED);
+
+        // Out-of-range values are clamped.
+        let line = sound_occlusion_line(listener, source, 2.0);
+        assert_eq!(line.color, Color::RED);
+
+        let mut ctx = SceneDrawingContext::default();
+        ctx.draw_sound_occlusion(listener, source, 0.5);
+        assert_eq!(ctx.lines.len(), 1);
+        assert_eq!(ctx.lines[0].color, Color::GREEN.lerp(Color::RED, 0.5));
+    }
+}
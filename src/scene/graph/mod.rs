@@ -51,7 +51,7 @@ use crate::{
         sound::context::SoundContext,
         transform::TransformBuilder,
     },
-    script::ScriptTrait,
+    script::{Script, ScriptTrait},
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashSet;
@@ -137,6 +137,16 @@ pub struct Graph {
     pub(crate) script_message_sender: Sender<NodeScriptMessage>,
     #[reflect(hidden)]
     pub(crate) script_message_receiver: Receiver<NodeScriptMessage>,
+
+    // Time step of the last physics update, that wasn't yet passed to the scripts. See
+    // `ScriptTrait::on_physics_update` for more info.
+    #[reflect(hidden)]
+    pub(crate) pending_physics_step: Option<f32>,
+
+    // Time step between previous-tick and current global transforms of the nodes, it is used to
+    // calculate velocities of the nodes.
+    #[reflect(hidden)]
+    pub(crate) prev_transform_dt: f32,
 }
 
 impl Default for Graph {
@@ -154,6 +164,8 @@ impl Default for Graph {
             event_broadcaster: Default::default(),
             script_message_receiver: rx,
             script_message_sender: tx,
+            pending_physics_step: None,
+            prev_transform_dt: 0.0,
         }
     }
 }
@@ -263,6 +275,8 @@ impl Graph {
             event_broadcaster: Default::default(),
             script_message_receiver: rx,
             script_message_sender: tx,
+            pending_physics_step: None,
+            prev_transform_dt: 0.0,
         }
     }
 
@@ -364,8 +378,44 @@ impl Graph {
 
     /// Destroys the node and its children recursively. Scripts of the destroyed nodes will be removed in the next
     /// update tick.
+    ///
+    /// # Initialized scripts
+    ///
+    /// If the node or any of its descendants has an initialized script, the hierarchy is removed
+    /// from the graph right away as well, but its handles stay reserved until the scripts are
+    /// deinitialized. If any of the scripts requests to be kept alive in
+    /// [`crate::script::ScriptTrait::on_deinit`], the hierarchy is put back to the graph (see
+    /// `ScriptDeinitContext::request_keep_alive` for more info).
     #[inline]
     pub fn remove_node(&mut self, node_handle: Handle<Node>) {
+        let scripts = self.take_initialized_scripts(node_handle);
+        if !scripts.is_empty() {
+            self.stack.clear();
+            self.stack.push(node_handle);
+            let mut removed = Vec::new();
+            while let Some(handle) = self.stack.pop() {
+                self.stack.extend_from_slice(self.pool[handle].children());
+                removed.push(handle);
+            }
+
+            let sub_graph = self.take_reserve_sub_graph(node_handle);
+
+            for handle in removed {
+                self.event_broadcaster
+                    .broadcast(GraphEvent::Removed(handle));
+            }
+
+            Log::verify(
+                self.script_message_sender
+                    .send(NodeScriptMessage::DestroyNode {
+                        handle: node_handle,
+                        sub_graph,
+                        scripts,
+                    }),
+            );
+            return;
+        }
+
         self.unlink_internal(node_handle);
 
         self.stack.clear();
@@ -384,6 +434,47 @@ impl Graph {
         }
     }
 
+    // Takes initialized scripts from the node and its descendants.
+    fn take_initialized_scripts(
+        &mut self,
+        node_handle: Handle<Node>,
+    ) -> Vec<(Handle<Node>, Script)> {
+        let mut scripts = Vec::new();
+        self.stack.clear();
+        self.stack.push(node_handle);
+        while let Some(handle) = self.stack.pop() {
+            let node = &mut self.pool[handle];
+            self.stack.extend_from_slice(node.children());
+            if node
+                .script
+                .as_ref()
+                .map_or(false, |script| script.initialized)
+            {
+                scripts.push((handle, node.script.take().unwrap()));
+            }
+        }
+        scripts
+    }
+
+    // Puts a removed hierarchy back, because some of its scripts requested to be kept alive. The
+    // hierarchy is detached from its former parent and it is invisible and disabled.
+    pub(crate) fn put_kept_alive_sub_graph_back(&mut self, sub_graph: SubGraph) -> Handle<Node> {
+        let handle = self.put_sub_graph_back(sub_graph);
+        let node = &mut self.pool[handle];
+        node.set_visibility(false);
+        node.set_enabled(false);
+        handle
+    }
+
+    // Destroys a hierarchy that was put back by `put_kept_alive_sub_graph_back`. Removal events
+    // were sent already, so they're not sent again.
+    pub(crate) fn destroy_kept_alive_node(&mut self, node_handle: Handle<Node>) {
+        if self.is_valid_handle(node_handle) {
+            let sub_graph = self.take_reserve_sub_graph(node_handle);
+            self.forget_sub_graph(sub_graph);
+        }
+    }
+
     fn unlink_internal(&mut self, node_handle: Handle<Node>) {
         // Replace parent handle of child
         let parent_handle = std::mem::replace(&mut self.pool[node_handle].parent, Handle::NONE);
@@ -1096,6 +1187,15 @@ impl Graph {
         }
     }
 
+    /// Returns time step between previous-tick and current global transforms of the nodes (see
+    /// [`crate::scene::base::Base::prev_global_transform`]), it is the time step of the last
+    /// [`Self::update`] call. Use it to calculate velocities of the nodes with
+    /// [`crate::scene::base::Base::global_velocity`].
+    #[inline]
+    pub fn prev_transform_dt(&self) -> f32 {
+        self.prev_transform_dt
+    }
+
     /// Updates nodes in the graph using given delta time.
     ///
     /// # Update Switches
@@ -1104,7 +1204,23 @@ impl Graph {
     /// where you need to have preview mode to update only specific set of nodes, etc.
     pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
         let last_time = instant::Instant::now();
+        // Remember global transforms from the previous tick first, they're used to calculate
+        // velocities of the nodes. Nodes that weren't updated yet will get their current
+        // transforms right after hierarchical data update.
+        for node in self.pool.iter() {
+            if node.prev_global_transform.get().is_some() {
+                node.prev_global_transform
+                    .set(Some(node.global_transform.get()));
+            }
+        }
+        self.prev_transform_dt = dt;
         self.update_hierarchical_data();
+        for node in self.pool.iter() {
+            if node.prev_global_transform.get().is_none() {
+                node.prev_global_transform
+                    .set(Some(node.global_transform.get()));
+            }
+        }
         self.performance_statistics.hierarchical_properties_time =
             instant::Instant::now() - last_time;
 
@@ -1112,20 +1228,29 @@ impl Graph {
         self.sync_native(&switches);
         self.performance_statistics.sync_time = instant::Instant::now() - last_time;
 
+        self.pending_physics_step = None;
+
         if switches.physics {
             self.physics.performance_statistics.reset();
             self.physics.update(dt);
             self.performance_statistics.physics = self.physics.performance_statistics.clone();
+            self.pending_physics_step = Some(self.physics.integration_parameters.dt.unwrap_or(dt));
         }
 
         if switches.physics2d {
             self.physics2d.performance_statistics.reset();
             self.physics2d.update(dt);
             self.performance_statistics.physics2d = self.physics2d.performance_statistics.clone();
+            if self.pending_physics_step.is_none() {
+                self.pending_physics_step =
+                    Some(self.physics2d.integration_parameters.dt.unwrap_or(dt));
+            }
         }
 
         if switches.sound {
+            self.sound_context.sync_velocities(&self.pool, dt);
             self.sound_context.update();
+            self.sound_context.update_effect_zones(&self.pool);
             self.performance_statistics.sound_update_time =
                 self.sound_context.full_render_duration();
         }
@@ -1500,10 +1625,39 @@ mod test {
     use crate::scene::base::BaseBuilder;
     use crate::scene::pivot::PivotBuilder;
     use crate::{
-        core::pool::Handle,
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
         scene::{graph::Graph, node::Node, pivot::Pivot},
     };
 
+    #[test]
+    fn test_node_velocity() {
+        let mut graph = Graph::new();
+        let pivot = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let dt = 0.5;
+
+        graph.update(Vector2::new(100.0, 100.0), dt, Default::default());
+        assert_eq!(graph.prev_transform_dt(), dt);
+        // Newly created node has no velocity.
+        assert_eq!(graph[pivot].global_velocity(dt), Vector3::default());
+
+        graph[pivot]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 2.0, 3.0));
+        graph.update(Vector2::new(100.0, 100.0), dt, Default::default());
+        assert_eq!(
+            graph[pivot].global_velocity(dt),
+            Vector3::new(2.0, 4.0, 6.0)
+        );
+
+        // The node stopped.
+        graph.update(Vector2::new(100.0, 100.0), dt, Default::default());
+        assert_eq!(graph[pivot].global_velocity(dt), Vector3::default());
+    }
+
     #[test]
     fn graph_init_test() {
         let graph = Graph::new();
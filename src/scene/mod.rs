@@ -44,6 +44,7 @@ use crate::{
     },
     resource::{curve::CurveResource, model::Model, texture::Texture},
     scene::{
+        base::NodeScriptMessage,
         camera::Camera,
         debug::SceneDrawingContext,
         graph::{map::NodeHandleMap, Graph, GraphPerformanceStatistics, GraphUpdateSwitches},
@@ -55,6 +56,7 @@ use crate::{
         node::Node,
         sound::SoundEngine,
     },
+    script::{Script, ScriptTrait},
     utils::{lightmap::Lightmap, log::Log, log::MessageKind, navmesh::Navmesh},
 };
 use fxhash::{FxHashMap, FxHashSet};
@@ -678,6 +680,93 @@ impl Scene {
         self.performance_statistics.graph = self.graph.performance_statistics.clone();
     }
 
+    /// Returns an iterator over all nodes with a script of given type. Each item of the iterator
+    /// is a pair of a node handle and a reference to the script of the node.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use fyrox::{
+    /// #     core::{reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
+    /// #     impl_component_provider,
+    /// #     scene::Scene,
+    /// #     script::ScriptTrait,
+    /// # };
+    /// #[derive(Reflect, Visit, Debug, Clone, Default)]
+    /// struct Enemy {
+    ///     health: f32,
+    /// }
+    ///
+    /// # impl_component_provider!(Enemy);
+    /// impl ScriptTrait for Enemy {
+    ///     // ...
+    /// #     fn id(&self) -> Uuid {
+    /// #         Uuid::default()
+    /// #     }
+    /// }
+    ///
+    /// fn total_enemies_health(scene: &Scene) -> f32 {
+    ///     scene.iter_scripts::<Enemy>().map(|(_, enemy)| enemy.health).sum()
+    /// }
+    /// ```
+    pub fn iter_scripts<T: ScriptTrait>(&self) -> impl Iterator<Item = (Handle<Node>, &T)> {
+        self.graph.pair_iter().filter_map(|(handle, node)| {
+            node.script()
+                .and_then(|s| s.cast::<T>())
+                .map(|s| (handle, s))
+        })
+    }
+
+    /// Same as [`Self::iter_scripts`], but returns mutable references to the scripts. The graph
+    /// is borrowed mutably for the whole lifetime of the iterator, so it is impossible to access
+    /// other nodes while iterating. If you need to do so, collect the handles first and then
+    /// borrow the nodes one-by-one.
+    pub fn iter_scripts_mut<T: ScriptTrait>(
+        &mut self,
+    ) -> impl Iterator<Item = (Handle<Node>, &mut T)> {
+        self.graph.pair_iter_mut().filter_map(|(handle, node)| {
+            node.script_mut()
+                .and_then(|s| s.cast_mut::<T>())
+                .map(|s| (handle, s))
+        })
+    }
+
+    /// Calls the given closure for every node that has a script, regardless of the type of the
+    /// script. Unlike [`Self::iter_scripts_mut`] it gives access to the [`Script`] wrapper itself,
+    /// so it could be used for bulk operations over all scripts (for example, to reinitialize
+    /// scripts after hot-reload).
+    pub fn visit_scripts<F>(&mut self, mut func: F)
+    where
+        F: FnMut(Handle<Node>, &mut Script),
+    {
+        for (handle, node) in self.graph.pair_iter_mut() {
+            if let Some(script) = node.script_mut() {
+                func(handle, script);
+            }
+        }
+    }
+
+    /// Forces every script of the scene to run [`ScriptTrait::on_start`] again on the next update
+    /// pass of the engine, without reloading the scene. Already initialized scripts won't receive
+    /// [`ScriptTrait::on_init`] again, only the start of the lifecycle is repeated. It is useful for
+    /// rapid iteration with hot-reload.
+    pub fn restart_scripts(&mut self) {
+        let mut restarted = Vec::new();
+        self.visit_scripts(|handle, script| {
+            script.started = false;
+            restarted.push(handle);
+        });
+
+        // The engine starts scripts only in response to these messages.
+        for handle in restarted {
+            Log::verify(
+                self.graph
+                    .script_message_sender
+                    .send(NodeScriptMessage::InitializeScript { handle }),
+            );
+        }
+    }
+
     /// Creates deep copy of a scene, filter predicate allows you to filter out nodes
     /// by your criteria.
     pub fn clone<F>(&self, filter: &mut F) -> (Self, NodeHandleMap)
@@ -2,21 +2,27 @@
 
 use crate::{
     core::{
+        algebra::{Matrix3, Matrix4, Vector3},
+        math::Matrix4Ext,
         pool::{Handle, Pool, Ticket},
         reflect::prelude::*,
         visitor::prelude::*,
     },
     resource::model::Model,
     scene::{
+        graph::{Graph, NodePool},
         node::Node,
-        sound::{effect::Effect, Sound},
+        sound::{effect::Effect, listener::Listener, Sound},
     },
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashSet;
 use fyrox_sound::{
-    context::DistanceModel,
-    effects::{reverb::Reverb, BaseEffect, EffectInput},
+    buffer::SoundBufferResource,
+    context::{AudioBudget, DistanceModel},
+    dsp::limiter::LimiterParams,
+    effects::EffectInput,
+    error::SoundError,
     renderer::Renderer,
     source::{SoundSource, SoundSourceBuilder, Status},
 };
@@ -37,6 +43,10 @@ pub struct SoundContext {
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: fyrox_sound::context::SoundContext,
+    // Sources that were playing at the moment of the last `stop_all_sounds` call.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    previously_playing: Vec<Handle<SoundSource>>,
 }
 
 impl Default for SoundContext {
@@ -49,6 +59,7 @@ impl Default for SoundContext {
             effects: Default::default(),
             resource: None,
             native: fyrox_sound::context::SoundContext::new(),
+            previously_playing: Default::default(),
         }
     }
 }
@@ -68,6 +79,17 @@ impl SoundContext {
         self.effects.free(effect)
     }
 
+    /// Replaces an effect at the given handle with the new one and returns the old effect. It could
+    /// be used to change the variant of an effect (see [`Effect::with_variant`]), sound sources that
+    /// were sent to the old effect will be sent to the new one.
+    pub fn replace_effect(&mut self, handle: Handle<Effect>, effect: Effect) -> Effect {
+        let old = std::mem::replace(&mut self.effects[handle], effect);
+        // Native effect will be re-created on update if its variant does not match.
+        self.effects[handle].native.set(old.native.get());
+        old.native.set(Default::default());
+        old
+    }
+
     /// Borrows an effect.
     pub fn effect(&self, handle: Handle<Effect>) -> &Effect {
         &self.effects[handle]
@@ -113,6 +135,18 @@ impl SoundContext {
         self.effects.alive_count()
     }
 
+    /// Returns amount of native sound sources that are currently playing. It also takes into account
+    /// sources that have no corresponding nodes (for example sounds played via [`Self::play_ui`]).
+    /// Could be used to profile audio load.
+    pub fn active_voice_count(&self) -> u32 {
+        self.native
+            .state()
+            .sources()
+            .iter()
+            .filter(|source| source.status() == Status::Playing)
+            .count() as u32
+    }
+
     /// Pause/unpause the sound context. Paused context won't play any sounds.
     pub fn pause(&mut self, pause: bool) {
         self.paused = pause;
@@ -168,43 +202,408 @@ impl SoundContext {
         self.master_gain
     }
 
+    /// Enables or disables master limiter of the context. See
+    /// [`fyrox_sound::context::State::set_limiter`] for more info. The limiter is a runtime-only
+    /// setting, it is not saved with the scene.
+    pub fn set_limiter(&mut self, params: Option<LimiterParams>) {
+        self.native.state().set_limiter(params)
+    }
+
+    /// Returns parameters of the master limiter, if any.
+    pub fn limiter(&self) -> Option<LimiterParams> {
+        self.native.state().limiter()
+    }
+
+    /// Sets audio budget of the context. See [`AudioBudget`] for more info. The budget is a
+    /// runtime-only setting, it is not saved with the scene.
+    pub fn set_audio_budget(&mut self, budget: AudioBudget) {
+        self.native.state().set_audio_budget(budget)
+    }
+
+    /// Returns current audio budget of the context.
+    pub fn audio_budget(&self) -> AudioBudget {
+        self.native.state().audio_budget()
+    }
+
+    /// Limits the amount of simultaneously playing instances of the given buffer, the oldest
+    /// instances are stopped when the limit is exceeded. See
+    /// [`fyrox_sound::context::State::set_max_instances_per_buffer`] for more info. The limit is a
+    /// runtime-only setting, it is not saved with the scene.
+    pub fn set_max_instances_per_buffer(
+        &mut self,
+        buffer: SoundBufferResource,
+        max_instances: Option<usize>,
+    ) {
+        self.native
+            .state()
+            .set_max_instances_per_buffer(buffer, max_instances)
+    }
+
+    /// Returns the maximum amount of simultaneously playing instances of the given buffer, if any.
+    pub fn max_instances_per_buffer(&self, buffer: &SoundBufferResource) -> Option<usize> {
+        self.native.state().max_instances_per_buffer(buffer)
+    }
+
+    /// Sets new position of the listener in world coordinates.
+    ///
+    /// # Notes
+    ///
+    /// If there is a [`Listener`](super::listener::Listener) node in the scene, it will override
+    /// position of the listener on every frame.
+    pub fn set_listener_position(&mut self, position: Vector3<f32>) {
+        self.native.state().listener_mut().set_position(position);
+    }
+
+    /// Returns current position of the listener in world coordinates.
+    pub fn listener_position(&self) -> Vector3<f32> {
+        self.native.state().listener().position()
+    }
+
+    /// Sets new orientation of the listener using given forward (look) and up vectors in world
+    /// coordinates. Orientation of the listener defines which channel will be louder for sound
+    /// sources that are located at a side of the listener. Vectors will be normalized, they
+    /// must not be collinear.
+    ///
+    /// # Notes
+    ///
+    /// If there is a [`Listener`](super::listener::Listener) node in the scene, it will override
+    /// orientation of the listener on every frame.
+    pub fn set_listener_orientation(&mut self, forward: Vector3<f32>, up: Vector3<f32>) {
+        let forward = forward
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+        let up = up.try_normalize(f32::EPSILON).unwrap_or_else(Vector3::y);
+        self.native
+            .state()
+            .listener_mut()
+            .set_orientation_lh(forward, up);
+    }
+
+    /// Returns current basis of the listener. Its columns are side (ear), up and forward (look)
+    /// axes respectively.
+    pub fn listener_basis(&self) -> Matrix3<f32> {
+        *self.native.state().listener().basis()
+    }
+
+    /// Sets position and orientation of the listener from given world transform. It is useful to
+    /// keep the listener in sync with some node (usually a camera), for example:
+    ///
+    /// ```rust
+    /// # use fyrox::scene::{node::Node, sound::context::SoundContext};
+    /// fn sync_listener(sound_context: &mut SoundContext, camera: &Node) {
+    ///     sound_context.set_listener_transform(&camera.global_transform());
+    /// }
+    /// ```
+    pub fn set_listener_transform(&mut self, transform: &Matrix4<f32>) {
+        self.apply_listener_transform(transform)
+    }
+
+    /// Syncs position and orientation of the listener with world transform of a node with given
+    /// handle (usually it is an active camera). This method should be called every frame to keep
+    /// the listener attached to the node. Does nothing if the handle is invalid.
+    ///
+    /// ```rust
+    /// # use fyrox::{core::pool::Handle, scene::{node::Node, Scene}};
+    /// fn update_listener(scene: &Scene, camera: Handle<Node>) {
+    ///     scene
+    ///         .graph
+    ///         .sound_context
+    ///         .sync_listener_to_node(&scene.graph, camera);
+    /// }
+    /// ```
+    pub fn sync_listener_to_node(&self, graph: &Graph, node: Handle<Node>) {
+        if let Some(node) = graph.try_get(node) {
+            self.apply_listener_transform(&node.global_transform());
+        }
+    }
+
+    fn apply_listener_transform(&self, transform: &Matrix4<f32>) {
+        let forward = transform
+            .look()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::z);
+        let up = transform
+            .up()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        let mut state = self.native.state();
+        let listener = state.listener_mut();
+        listener.set_position(transform.position());
+        listener.set_orientation_lh(forward, up);
+    }
+
+    /// Mutes or unmutes a sound source with given handle. Muted source is silenced at the mix
+    /// stage, but its gain is preserved, so unmuting restores the actual gain of the source (even if
+    /// it was changed while the source was muted). Does nothing if the handle is invalid.
+    pub fn set_sound_muted(&self, sound: Handle<SoundSource>, muted: bool) {
+        if let Some(source) = self.native.state().try_get_source_mut(sound) {
+            source.set_muted(muted);
+        }
+    }
+
+    /// Returns true if a sound source with given handle is muted, false - otherwise (including the
+    /// case when the handle is invalid).
+    pub fn is_sound_muted(&self, sound: Handle<SoundSource>) -> bool {
+        let state = self.native.state();
+        state.is_valid_handle(sound) && state.is_sound_muted(sound)
+    }
+
+    /// Plays given buffer as a "UI" sound - a fully non-spatial (2D) one-shot sound, that ignores
+    /// positions of the listener and the source as well as distance model. The sound will be
+    /// automatically removed when it finishes playing. It is useful for sounds of user interface,
+    /// such as button clicks, notifications, etc.
+    pub fn play_ui(
+        &mut self,
+        buffer: SoundBufferResource,
+        gain: f32,
+    ) -> Result<Handle<SoundSource>, SoundError> {
+        let source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_gain(gain)
+            .with_spatial_blend_factor(0.0)
+            .with_play_once(true)
+            .with_status(Status::Playing)
+            .build()?;
+        Ok(self.native.state().add_source(source))
+    }
+
+    /// Plays given buffer as a transient one-shot sound at the position of the given node. If the
+    /// node (or any of its descendants) is a [`Sound`], the one-shot inherits its audio bus, distance
+    /// attenuation parameters and effect send, so it sounds consistent with persistent sounds of the
+    /// same object. The sound will be automatically removed when it finishes playing. It is useful
+    /// for gameplay sound effects, such as footsteps, impacts, shots, etc.
+    ///
+    /// ```rust
+    /// # use fyrox::{
+    /// #     core::pool::Handle,
+    /// #     scene::{node::Node, sound::SoundBufferResource, Scene},
+    /// # };
+    /// fn play_impact(scene: &Scene, object: Handle<Node>, buffer: SoundBufferResource) {
+    ///     let _ = scene
+    ///         .graph
+    ///         .sound_context
+    ///         .play_one_shot_at_node(&scene.graph, object, buffer, 1.0);
+    /// }
+    /// ```
+    pub fn play_one_shot_at_node(
+        &self,
+        graph: &Graph,
+        node: Handle<Node>,
+        buffer: SoundBufferResource,
+        gain: f32,
+    ) -> Result<Handle<SoundSource>, SoundError> {
+        let mut builder = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_gain(gain)
+            .with_play_once(true)
+            .with_status(Status::Playing);
+
+        if let Some(node_ref) = graph.try_get(node) {
+            builder = builder.with_position(node_ref.global_position());
+        }
+
+        let sound = graph
+            .find(node, &mut |n| n.query_component_ref::<Sound>().is_some())
+            .and_then(|(_, n)| n.query_component_ref::<Sound>());
+
+        if let Some(sound) = sound {
+            builder = builder
+                .with_bus(sound.bus())
+                .with_radius(sound.radius())
+                .with_max_distance(sound.max_distance())
+                .with_rolloff_factor(sound.rolloff_factor())
+                .with_spatial_blend_factor(sound.spatial_blend())
+                .with_direct_output(sound.is_direct_output())
+                .with_distance_model_override(sound.distance_model_override())
+                .with_direction(sound.look_vector())
+                .with_cone_inner_angle(sound.cone_inner_angle())
+                .with_cone_outer_angle(sound.cone_outer_angle())
+                .with_cone_outer_gain(sound.cone_outer_gain());
+        }
+
+        let source = builder.build()?;
+
+        let mut state = self.native.state();
+        let handle = state.add_source(source);
+
+        if let Some(sound) = sound {
+            for effect in self
+                .effects
+                .iter()
+                .filter(|e| sound.is_attached_to_effect(e.name()) && e.native.get().is_some())
+            {
+                // Dangling input will be removed by the effect when the source is removed.
+                state
+                    .effect_mut(effect.native.get())
+                    .add_input(EffectInput::direct(handle).with_gain(sound.effect_send_gain()));
+            }
+        }
+
+        Ok(handle)
+    }
+
     /// Destroys all backing sound entities.
     pub fn destroy_sound_sources(&mut self) {
         self.native.state().sources_mut().clear();
     }
 
+    /// Stops all sound sources right away (ignoring their fade out) and rewinds them to the
+    /// beginning. Unlike [`Self::destroy_sound_sources`], the sources stay alive, so they could be
+    /// played again. Sources that were playing could be resumed using
+    /// [`Self::resume_all_previously_playing`]. It is useful on scene transitions, pause menus,
+    /// etc. Keep in mind that one-shot sounds are removed once they're stopped.
+    pub fn stop_all_sounds(&mut self) {
+        let mut state = self.native.state();
+        self.previously_playing.clear();
+        for (handle, source) in state.sources_mut().pair_iter_mut() {
+            if source.status() == Status::Playing {
+                self.previously_playing.push(handle);
+            }
+            Log::verify(source.stop_immediately());
+        }
+    }
+
+    /// Plays all sound sources that were playing at the moment of the last
+    /// [`Self::stop_all_sounds`] call. Sources that were removed since then are ignored.
+    pub fn resume_all_previously_playing(&mut self) {
+        let mut state = self.native.state();
+        for handle in self.previously_playing.drain(..) {
+            if let Some(source) = state.try_get_source_mut(handle) {
+                source.play();
+            }
+        }
+    }
+
     pub(crate) fn update(&mut self) {
         let mut state = self.native.state();
 
         for effect in self.effects.iter() {
+            let native = effect.native.get();
+            if native.is_some()
+                && !matches!(
+                    (state.effect(native), effect),
+                    (fyrox_sound::effects::Effect::Reverb(_), Effect::Reverb(_))
+                        | (fyrox_sound::effects::Effect::Delay(_), Effect::Delay(_))
+                )
+            {
+                // Variant of the effect was changed, re-create the native effect, but keep its
+                // inputs.
+                let mut new_native = effect.make_native();
+                for input in state.effect(native).inputs_ref() {
+                    new_native.add_input(input.clone());
+                }
+                state.remove_effect(native);
+                effect.native.set(state.add_effect(new_native));
+            }
+
             if effect.native.get().is_some() {
-                let native_effect = state.effect_mut(effect.native.get());
-                if let (
-                    fyrox_sound::effects::Effect::Reverb(native_reverb),
-                    Effect::Reverb(reverb),
-                ) = (native_effect, effect)
-                {
-                    reverb.decay_time.try_sync_model(|v| {
-                        native_reverb.set_decay_time(Duration::from_secs_f32(v))
-                    });
-                    reverb.gain.try_sync_model(|v| native_reverb.set_gain(v));
-                    reverb.wet.try_sync_model(|v| native_reverb.set_wet(v));
-                    reverb.dry.try_sync_model(|v| native_reverb.set_dry(v));
-                    reverb.fc.try_sync_model(|v| native_reverb.set_fc(v));
+                match (state.effect_mut(effect.native.get()), effect) {
+                    (
+                        fyrox_sound::effects::Effect::Reverb(native_reverb),
+                        Effect::Reverb(reverb),
+                    ) => {
+                        reverb.decay_time.try_sync_model(|v| {
+                            native_reverb.set_decay_time(Duration::from_secs_f32(v))
+                        });
+                        reverb.gain.try_sync_model(|v| native_reverb.set_gain(v));
+                        reverb.wet.try_sync_model(|v| native_reverb.set_wet(v));
+                        reverb.dry.try_sync_model(|v| native_reverb.set_dry(v));
+                        reverb.fc.try_sync_model(|v| native_reverb.set_fc(v));
+                        reverb.modulation_depth.try_sync_model(|v| {
+                            let rate = native_reverb.modulation_rate();
+                            native_reverb.set_modulation(v, rate)
+                        });
+                        reverb.modulation_rate.try_sync_model(|v| {
+                            let depth = native_reverb.modulation_depth();
+                            native_reverb.set_modulation(depth, v)
+                        });
+                    }
+                    (fyrox_sound::effects::Effect::Delay(native_delay), Effect::Delay(delay)) => {
+                        delay
+                            .delay_time
+                            .try_sync_model(|v| native_delay.set_delay_time(v));
+                        delay.gain.try_sync_model(|v| native_delay.set_gain(v));
+                        delay
+                            .feedback
+                            .try_sync_model(|v| native_delay.set_feedback(v));
+                        delay.mix.try_sync_model(|v| native_delay.set_mix(v));
+                    }
+                    _ => (),
                 }
             } else {
-                match effect {
-                    Effect::Reverb(reverb) => {
-                        let mut native_reverb = Reverb::new(BaseEffect::default());
-                        native_reverb.set_gain(reverb.gain());
-                        native_reverb.set_fc(reverb.fc());
-                        native_reverb.set_decay_time(Duration::from_secs_f32(reverb.decay_time()));
-                        native_reverb.set_dry(reverb.dry());
-                        native_reverb.set_wet(reverb.wet());
-                        let native =
-                            state.add_effect(fyrox_sound::effects::Effect::Reverb(native_reverb));
-                        reverb.native.set(native);
+                let native = state.add_effect(effect.make_native());
+                effect.native.set(native);
+            }
+        }
+    }
+
+    // Feeds velocities of sound and listener nodes to their native counterparts, they're used to
+    // calculate doppler effect.
+    pub(crate) fn sync_velocities(&self, nodes: &NodePool, dt: f32) {
+        let mut state = self.native.state();
+        for node in nodes.iter() {
+            if let Some(sound) = node.cast::<Sound>() {
+                if let Some(source) = state.try_get_source_mut(sound.native.get()) {
+                    source.set_velocity(sound.global_velocity(dt));
+                }
+            } else if let Some(listener) = node.cast::<Listener>() {
+                if listener.is_globally_enabled() {
+                    state
+                        .listener_mut()
+                        .set_velocity(listener.global_velocity(dt));
+                }
+            }
+        }
+    }
+
+    // Sends sounds to effects whose zones they're inside, see [`BaseEffect::set_zone`] for more info.
+    pub(crate) fn update_effect_zones(&self, nodes: &NodePool) {
+        if self.effects.iter().all(|effect| effect.zone().is_none()) {
+            return;
+        }
+
+        let mut state = self.native.state();
+        for sound in nodes.iter().filter_map(|node| node.cast::<Sound>()) {
+            let source = sound.native.get();
+            if !state.is_valid_handle(source) {
+                continue;
+            }
+
+            let position = sound.global_position();
+            let zone_effect = self
+                .effects
+                .pair_iter()
+                .filter(|(_, effect)| {
+                    effect
+                        .zone()
+                        .map_or(false, |zone| zone.is_contains_point(position))
+                })
+                .map(|(handle, _)| handle)
+                .last()
+                .unwrap_or_default();
+
+            for (handle, effect) in self.effects.pair_iter() {
+                // Sounds are always sent to the effect they're bound to by name.
+                if effect.zone().is_none()
+                    || effect.native.get().is_none()
+                    || sound.is_attached_to_effect(effect.name())
+                {
+                    continue;
+                }
+
+                let native_effect = state.effect_mut(effect.native.get());
+                let connected = native_effect
+                    .inputs_ref()
+                    .iter()
+                    .any(|input| input.source() == source);
+                if handle == zone_effect {
+                    if !connected {
+                        let mut input = EffectInput::direct(source);
+                        input.set_gain(sound.effect_send_gain());
+                        native_effect.add_input(input);
                     }
+                } else if connected {
+                    native_effect.remove_input_by_source(source);
                 }
             }
         }
@@ -224,7 +623,9 @@ impl SoundContext {
 
     pub(crate) fn set_sound_position(&mut self, sound: &Sound) {
         if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
-            source.set_position(sound.global_position());
+            source
+                .set_position(sound.global_position())
+                .set_direction(sound.look_vector());
         }
     }
 
@@ -236,6 +637,32 @@ impl SoundContext {
         }
     }
 
+    // Connects the sound to every effect it is attached to by name and disconnects it from every
+    // other effect. Inputs that are managed by effect zones will be restored on next update.
+    fn sync_effect_inputs(&self, sound: &Sound) {
+        let source = sound.native.get();
+        let mut state = self.native.state();
+        for effect in self.effects.iter() {
+            if effect.native.get().is_none() {
+                continue;
+            }
+
+            let native_effect = state.effect_mut(effect.native.get());
+            let connected = native_effect
+                .inputs_ref()
+                .iter()
+                .any(|input| input.source() == source);
+            if sound.is_attached_to_effect(effect.name()) {
+                if !connected {
+                    native_effect
+                        .add_input(EffectInput::direct(source).with_gain(sound.effect_send_gain()));
+                }
+            } else if connected {
+                native_effect.remove_input_by_source(source);
+            }
+        }
+    }
+
     pub(crate) fn sync_to_sound(
         &mut self,
         sound_handle: Handle<Node>,
@@ -262,9 +689,41 @@ impl SoundContext {
             sound.rolloff_factor.try_sync_model(|v| {
                 source.set_rolloff_factor(v);
             });
+            sound.cone_inner_angle.try_sync_model(|v| {
+                source.set_cone_inner_angle(v);
+            });
+            sound.cone_outer_angle.try_sync_model(|v| {
+                source.set_cone_outer_angle(v);
+            });
+            sound.cone_outer_gain.try_sync_model(|v| {
+                source.set_cone_outer_gain(v);
+            });
             sound.radius.try_sync_model(|v| {
                 source.set_radius(v);
             });
+            // Must be synced before playback time, so the seek will use actual mode.
+            sound.smooth_seek.try_sync_model(|v| {
+                source.set_smooth_seek(v);
+            });
+            sound.priority.try_sync_model(|v| {
+                source.set_priority(v);
+            });
+            sound.direct_output.try_sync_model(|v| {
+                source.set_direct_output(v);
+            });
+            sound.distance_model_override.try_sync_model(|v| {
+                source.set_distance_model_override(v);
+            });
+            // Must be synced before status, so the fades will be applied.
+            sound.fade_in.try_sync_model(|v| {
+                source.set_fade_in(v);
+            });
+            sound.fade_out.try_sync_model(|v| {
+                source.set_fade_out(v);
+            });
+            sound.fade_curve.try_sync_model(|v| {
+                source.set_fade_curve(v);
+            });
             sound.playback_time.try_sync_model(|v| {
                 source.set_playback_time(v);
             });
@@ -283,6 +742,13 @@ impl SoundContext {
             sound
                 .spatial_blend
                 .try_sync_model(|v| source.set_spatial_blend(v));
+            sound.bus.try_sync_model(|v| source.set_bus(v));
+            sound.doppler_factor.try_sync_model(|v| {
+                source.set_doppler_factor(v);
+            });
+            sound.loop_region.try_sync_model(|v| {
+                source.set_loop_region(v.map(|r| (r.start as usize, r.end as usize)));
+            });
             sound.status.try_sync_model(|v| match v {
                 Status::Stopped => {
                     Log::verify(source.stop());
@@ -298,18 +764,25 @@ impl SoundContext {
             // Make sure to not deadlock.
             drop(state);
 
-            sound.effect_name.try_sync_model(|effect_name| {
-                if let Some(effect) = self.effects.iter().find(|e| e.name() == effect_name) {
-                    let mut state = self.native.state();
+            if sound.effect_names.try_sync_model(|_| {}) {
+                self.sync_effect_inputs(sound);
+            }
+
+            sound.effect_send_gain.try_sync_model(|gain| {
+                let mut state = self.native.state();
+                for effect in self
+                    .effects
+                    .iter()
+                    .filter(|e| sound.is_attached_to_effect(e.name()) && e.native.get().is_some())
+                {
                     let native_effect = state.effect_mut(effect.native.get());
-                    if let Some(prev) = native_effect
-                        .inputs_ref()
-                        .iter()
-                        .position(|input| input.source() == sound.native.get())
+                    if let Some(input) = native_effect
+                        .inputs_mut()
+                        .iter_mut()
+                        .find(|input| input.source() == sound.native.get())
                     {
-                        native_effect.remove_input(prev);
+                        input.set_gain(gain);
                     }
-                    native_effect.add_input(EffectInput::direct(sound.native.get()));
                 }
             });
         } else {
@@ -319,26 +792,40 @@ impl SoundContext {
                 .with_looping(sound.is_looping())
                 .with_panning(sound.panning())
                 .with_pitch(sound.pitch())
-                .with_status(sound.status())
+                .with_status(if sound.is_play_on_awake() {
+                    Status::Playing
+                } else {
+                    sound.status()
+                })
                 .with_playback_time(sound.playback_time())
                 .with_position(sound.global_position())
+                .with_direction(sound.look_vector())
                 .with_radius(sound.radius())
                 .with_max_distance(sound.max_distance())
                 .with_rolloff_factor(sound.rolloff_factor())
+                .with_cone_inner_angle(sound.cone_inner_angle())
+                .with_cone_outer_angle(sound.cone_outer_angle())
+                .with_cone_outer_gain(sound.cone_outer_gain())
+                .with_bus(sound.bus())
+                .with_loop_region(
+                    sound
+                        .loop_region()
+                        .map(|r| (r.start as usize, r.end as usize)),
+                )
+                .with_doppler_factor(sound.doppler_factor())
+                .with_smooth_seek(sound.is_smooth_seek())
+                .with_priority(sound.priority())
+                .with_direct_output(sound.is_direct_output())
+                .with_distance_model_override(sound.distance_model_override())
+                .with_fade_in(sound.fade_in())
+                .with_fade_out(sound.fade_out())
+                .with_fade_curve(sound.fade_curve())
                 .build()
             {
                 Ok(source) => {
                     sound.native.set(self.native.state().add_source(source));
 
-                    if let Some(effect) = self
-                        .effects
-                        .iter()
-                        .find(|e| e.name() == sound.effect_name())
-                    {
-                        let mut state = self.native.state();
-                        let native_effect = state.effect_mut(effect.native.get());
-                        native_effect.add_input(EffectInput::direct(sound.native.get()));
-                    }
+                    self.sync_effect_inputs(sound);
 
                     Log::writeln(
                         MessageKind::Information,
@@ -359,3 +846,525 @@ impl SoundContext {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector2, Vector3},
+            math::aabb::AxisAlignedBoundingBox,
+            pool::Handle,
+            reflect::ResolvePath,
+        },
+        scene::{
+            base::BaseBuilder,
+            graph::{Graph, GraphUpdateSwitches},
+            pivot::PivotBuilder,
+            sound::{
+                context::SoundContext,
+                effect::{BaseEffectBuilder, DelayEffectBuilder, Effect, ReverbEffectBuilder},
+                listener::ListenerBuilder,
+                Sound, SoundBuilder,
+            },
+            transform::TransformBuilder,
+        },
+    };
+    use fyrox_sound::{
+        buffer::{DataSource, SoundBufferResource},
+        bus::AudioBus,
+        effects::EffectInput,
+        engine::SoundEngine,
+        source::{SoundSource, SoundSourceBuilder, Status},
+    };
+    use std::time::Duration;
+
+    fn constant_buffer() -> SoundBufferResource {
+        SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![1.0; 44100],
+        })
+        .unwrap()
+    }
+
+    // Looping source that plays the constant buffer.
+    fn playing_source() -> SoundSourceBuilder {
+        SoundSourceBuilder::new()
+            .with_buffer(constant_buffer())
+            .with_looping(true)
+            .with_status(Status::Playing)
+    }
+
+    fn update_graph(graph: &mut Graph) {
+        graph.update(
+            Vector2::new(100.0, 100.0),
+            0.1,
+            GraphUpdateSwitches {
+                physics2d: false,
+                physics: false,
+                ..Default::default()
+            },
+        )
+    }
+
+    // Returns sources and gains of the inputs of the native effect of the given effect.
+    fn effect_inputs(graph: &Graph, effect: Handle<Effect>) -> Vec<(Handle<SoundSource>, f32)> {
+        let context = &graph.sound_context;
+        let native_effect = context.effect(effect).native.get();
+        context
+            .native
+            .state()
+            .effect(native_effect)
+            .inputs_ref()
+            .iter()
+            .map(|input| (input.source(), input.gain()))
+            .collect()
+    }
+
+    fn render_loudness(engine: &mut SoundEngine) -> (f32, f32) {
+        let mut buf = vec![(0.0, 0.0); SoundEngine::render_buffer_len()];
+        // Render twice to skip gain interpolation between frames.
+        engine.render(&mut buf);
+        engine.render(&mut buf);
+        buf.iter()
+            .fold((0.0, 0.0), |(l, r), (sl, sr)| (l + sl.abs(), r + sr.abs()))
+    }
+
+    #[test]
+    fn test_listener_orientation_changes_panning() {
+        let mut context = SoundContext::new();
+
+        // Put a sound source at the side of the listener.
+        let source = playing_source()
+            .with_position(Vector3::new(5.0, 0.0, 0.0))
+            .build()
+            .unwrap();
+        context.native.state().add_source(source);
+
+        let engine = SoundEngine::without_device();
+        let mut engine = engine.lock().unwrap();
+        engine.add_context(context.native.clone());
+
+        context.set_listener_position(Vector3::default());
+        context.set_listener_orientation(Vector3::z(), Vector3::y());
+        assert_eq!(
+            context.listener_basis().column(2).into_owned(),
+            Vector3::z()
+        );
+        let (left, right) = render_loudness(&mut engine);
+        let left_is_louder = left > right;
+        assert_ne!(left, right);
+
+        // Turn around, the same source must be louder in other channel.
+        context.set_listener_orientation(-Vector3::z(), Vector3::y());
+        let (left, right) = render_loudness(&mut engine);
+        assert_ne!(left, right);
+        assert_ne!(left > right, left_is_louder);
+    }
+
+    #[test]
+    fn test_ui_sound_ignores_listener_distance() {
+        let mut context = SoundContext::new();
+
+        context.play_ui(constant_buffer(), 0.5).unwrap();
+
+        let engine = SoundEngine::without_device();
+        let mut engine = engine.lock().unwrap();
+        engine.add_context(context.native.clone());
+
+        context.set_listener_position(Vector3::default());
+        let (near_left, near_right) = render_loudness(&mut engine);
+
+        context.set_listener_position(Vector3::new(1000.0, 0.0, 1000.0));
+        let (far_left, far_right) = render_loudness(&mut engine);
+
+        let expected = 0.5 * SoundEngine::render_buffer_len() as f32;
+        for loudness in [near_left, near_right, far_left, far_right] {
+            assert!((loudness - expected).abs() < 1.0e-3 * expected);
+        }
+    }
+
+    #[test]
+    fn test_sound_mute_preserves_gain() {
+        let context = SoundContext::new();
+
+        let source = context.native.state().add_source(
+            playing_source()
+                .with_spatial_blend_factor(0.0)
+                .build()
+                .unwrap(),
+        );
+
+        let engine = SoundEngine::without_device();
+        let mut engine = engine.lock().unwrap();
+        engine.add_context(context.native.clone());
+
+        let expected = SoundEngine::render_buffer_len() as f32;
+        let (left, _) = render_loudness(&mut engine);
+        assert!((left - expected).abs() < 1.0e-3 * expected);
+
+        context.set_sound_muted(source, true);
+        assert!(context.is_sound_muted(source));
+        assert_eq!(render_loudness(&mut engine), (0.0, 0.0));
+
+        // Change the gain while muted, it must be applied after unmuting.
+        context.native.state().source_mut(source).set_gain(0.5);
+        assert_eq!(render_loudness(&mut engine), (0.0, 0.0));
+
+        context.set_sound_muted(source, false);
+        assert!(!context.is_sound_muted(source));
+        assert_eq!(context.native.state().source(source).gain(), 0.5);
+        let (left, right) = render_loudness(&mut engine);
+        for loudness in [left, right] {
+            assert!((loudness - 0.5 * expected).abs() < 1.0e-3 * expected);
+        }
+    }
+
+    #[test]
+    fn test_active_voice_count() {
+        let mut context = SoundContext::new();
+        assert_eq!(context.active_voice_count(), 0);
+
+        for status in [
+            Status::Playing,
+            Status::Stopped,
+            Status::Playing,
+            Status::Paused,
+            Status::Playing,
+        ] {
+            let source = SoundSourceBuilder::new()
+                .with_buffer(constant_buffer())
+                .with_status(status)
+                .build()
+                .unwrap();
+            context.native.state().add_source(source);
+        }
+        context.play_ui(constant_buffer(), 1.0).unwrap();
+
+        assert_eq!(context.active_voice_count(), 4);
+    }
+
+    #[test]
+    fn test_stop_all_sounds() {
+        let mut context = SoundContext::new();
+
+        let mut sources = Vec::new();
+        for status in [Status::Playing, Status::Paused, Status::Playing] {
+            let source = SoundSourceBuilder::new()
+                .with_buffer(constant_buffer())
+                .with_status(status)
+                .with_fade_out(Duration::from_secs(1))
+                .build()
+                .unwrap();
+            sources.push(context.native.state().add_source(source));
+        }
+
+        context.stop_all_sounds();
+        for &source in sources.iter() {
+            let state = context.native.state();
+            let source = state.source(source);
+            assert_eq!(source.status(), Status::Stopped);
+            assert_eq!(source.playback_time(), Duration::default());
+        }
+
+        // Sources are still alive and only the playing ones are resumed.
+        context.resume_all_previously_playing();
+        let state = context.native.state();
+        assert_eq!(state.source(sources[0]).status(), Status::Playing);
+        assert_eq!(state.source(sources[1]).status(), Status::Stopped);
+        assert_eq!(state.source(sources[2]).status(), Status::Playing);
+    }
+
+    #[test]
+    fn test_sync_listener_to_node() {
+        let mut graph = Graph::new();
+
+        let camera = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 2.0, 3.0))
+                    .with_local_rotation(UnitQuaternion::from_axis_angle(
+                        &Vector3::y_axis(),
+                        std::f32::consts::FRAC_PI_2,
+                    ))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        graph.sound_context.sync_listener_to_node(&graph, camera);
+
+        let context = &graph.sound_context;
+        assert_eq!(context.listener_position(), Vector3::new(1.0, 2.0, 3.0));
+        let basis = context.listener_basis();
+        assert!(basis.column(2).metric_distance(&Vector3::x()) < 1.0e-5);
+        assert!(basis.column(1).metric_distance(&Vector3::y()) < 1.0e-5);
+    }
+
+    #[test]
+    fn test_one_shot_at_node_inherits_sound_settings() {
+        let mut graph = Graph::new();
+
+        let effect =
+            ReverbEffectBuilder::new(BaseEffectBuilder::new().with_name("Reverb".to_string()))
+                .build(&mut graph.sound_context);
+        // Create native effect.
+        graph.sound_context.update();
+
+        graph
+            .sound_context
+            .native
+            .state()
+            .bus_graph_mut()
+            .add_bus(AudioBus::new("SFX").with_gain(0.5));
+
+        let object = SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 2.0, 3.0))
+                    .build(),
+            ),
+        )
+        .with_bus("SFX".to_string())
+        .with_effect_name("Reverb".to_string())
+        .with_effect_send_gain(0.3)
+        .with_radius(4.0)
+        .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let one_shot = graph
+            .sound_context
+            .play_one_shot_at_node(&graph, object, constant_buffer(), 0.75)
+            .unwrap();
+
+        let context = &graph.sound_context;
+        let native_effect = context.effect(effect).native.get();
+        let state = context.native.state();
+
+        let source = state.source(one_shot);
+        assert!(source.is_play_once());
+        assert_eq!(source.status(), Status::Playing);
+        assert_eq!(source.gain(), 0.75);
+        assert_eq!(source.radius(), 4.0);
+        assert_eq!(source.position(), Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(source.bus(), "SFX");
+        assert_eq!(
+            state.bus_graph_ref().effective_gain_by_name(source.bus()),
+            0.5
+        );
+
+        let inputs = state.effect(native_effect).inputs_ref();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].source(), one_shot);
+        assert_eq!(inputs[0].gain(), 0.3);
+    }
+
+    #[test]
+    fn test_effect_zones() {
+        let mut graph = Graph::new();
+
+        // The listener is outside of both zones.
+        ListenerBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let zone_a = ReverbEffectBuilder::new(
+            BaseEffectBuilder::new()
+                .with_name("CaveA".to_string())
+                .with_zone(Some(AxisAlignedBoundingBox::from_min_max(
+                    Vector3::new(5.0, -5.0, -5.0),
+                    Vector3::new(15.0, 5.0, 5.0),
+                ))),
+        )
+        .build(&mut graph.sound_context);
+        let zone_b = ReverbEffectBuilder::new(
+            BaseEffectBuilder::new()
+                .with_name("CaveB".to_string())
+                .with_zone(Some(AxisAlignedBoundingBox::from_min_max(
+                    Vector3::new(-15.0, -5.0, -5.0),
+                    Vector3::new(-5.0, 5.0, 5.0),
+                ))),
+        )
+        .build(&mut graph.sound_context);
+
+        let sound = SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_buffer(Some(constant_buffer()))
+        .with_effect_send_gain(0.5)
+        .build(&mut graph);
+
+        // The first update creates native sound source.
+        update_graph(&mut graph);
+        update_graph(&mut graph);
+
+        let source = graph[sound].cast::<Sound>().unwrap().native.get();
+        assert_eq!(effect_inputs(&graph, zone_a), [(source, 0.5)]);
+        assert!(effect_inputs(&graph, zone_b).is_empty());
+
+        // Move the sound to the other zone.
+        graph[sound]
+            .local_transform_mut()
+            .set_position(Vector3::new(-10.0, 0.0, 0.0));
+        update_graph(&mut graph);
+        assert!(effect_inputs(&graph, zone_a).is_empty());
+        assert_eq!(effect_inputs(&graph, zone_b), [(source, 0.5)]);
+
+        // And outside of any zone.
+        graph[sound]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 20.0, 0.0));
+        update_graph(&mut graph);
+        assert!(effect_inputs(&graph, zone_a).is_empty());
+        assert!(effect_inputs(&graph, zone_b).is_empty());
+    }
+
+    #[test]
+    fn test_sound_with_multiple_effects() {
+        let mut graph = Graph::new();
+
+        let reverb =
+            ReverbEffectBuilder::new(BaseEffectBuilder::new().with_name("Reverb".to_string()))
+                .build(&mut graph.sound_context);
+        let delay =
+            DelayEffectBuilder::new(BaseEffectBuilder::new().with_name("Delay".to_string()))
+                .build(&mut graph.sound_context);
+        // Create native effects.
+        graph.sound_context.update();
+
+        let sound = SoundBuilder::new(BaseBuilder::new())
+            .with_buffer(Some(constant_buffer()))
+            .with_effect_names(vec!["Reverb".to_string(), "Delay".to_string()])
+            .with_effect_send_gain(0.5)
+            .build(&mut graph);
+
+        // The first update creates native sound source.
+        update_graph(&mut graph);
+        update_graph(&mut graph);
+
+        let source = graph[sound].cast::<Sound>().unwrap().native.get();
+        assert_eq!(effect_inputs(&graph, reverb), [(source, 0.5)]);
+        assert_eq!(effect_inputs(&graph, delay), [(source, 0.5)]);
+
+        // Send gain is applied to every effect.
+        graph[sound]
+            .cast_mut::<Sound>()
+            .unwrap()
+            .set_effect_send_gain(0.25);
+        update_graph(&mut graph);
+        assert_eq!(effect_inputs(&graph, reverb), [(source, 0.25)]);
+        assert_eq!(effect_inputs(&graph, delay), [(source, 0.25)]);
+
+        // Detach the sound from the reverb.
+        graph[sound]
+            .cast_mut::<Sound>()
+            .unwrap()
+            .set_effect_names(vec!["Delay".to_string()]);
+        update_graph(&mut graph);
+        assert!(effect_inputs(&graph, reverb).is_empty());
+        assert_eq!(effect_inputs(&graph, delay), [(source, 0.25)]);
+    }
+
+    #[test]
+    fn test_replace_effect_variant() {
+        let mut context = SoundContext::new();
+        let effect = context.add_effect(
+            ReverbEffectBuilder::new(BaseEffectBuilder::new().with_name("Cave".to_string()))
+                .build_effect(),
+        );
+        let source = context.native.state().add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(constant_buffer())
+                .build()
+                .unwrap(),
+        );
+        context.update();
+        let native = context.effect(effect).native.get();
+        context
+            .native
+            .state()
+            .effect_mut(native)
+            .add_input(EffectInput::direct(source));
+
+        let delay = context.effect(effect).with_variant("Delay").unwrap();
+        assert!(context.effect(effect).with_variant("Foobar").is_none());
+        let reverb = context.replace_effect(effect, delay);
+        assert!(reverb.is_reverb());
+
+        // The new variant is active and its parameters are exposed.
+        let effect_ref = context.effect(effect);
+        assert!(effect_ref.is_delay());
+        assert_eq!(effect_ref.name(), "Cave");
+        let has_property = |path: &str| {
+            let mut found = false;
+            effect_ref.resolve_path(path, &mut |result| found = result.is_ok());
+            found
+        };
+        assert!(has_property("Delay@0.delay_time"));
+        assert!(!has_property("Reverb@0.decay_time"));
+
+        // Native effect is re-created with the inputs of the old one.
+        context.update();
+        let native = context.effect(effect).native.get();
+        let state = context.native.state();
+        assert!(matches!(
+            state.effect(native),
+            fyrox_sound::effects::Effect::Delay(_)
+        ));
+        assert_eq!(state.effect(native).inputs_ref().len(), 1);
+        assert_eq!(state.effect(native).inputs_ref()[0].source(), source);
+        drop(state);
+
+        // Switch back.
+        context.replace_effect(effect, reverb);
+        context.update();
+        let native = context.effect(effect).native.get();
+        assert!(matches!(
+            context.native.state().effect(native),
+            fyrox_sound::effects::Effect::Reverb(_)
+        ));
+        assert!(context.effect(effect).is_reverb());
+    }
+
+    #[test]
+    fn test_doppler_from_node_velocities() {
+        let mut graph = Graph::new();
+
+        ListenerBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let sound = SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_buffer(Some(constant_buffer()))
+        .with_looping(true)
+        .with_status(Status::Playing)
+        .with_doppler_factor(1.0)
+        .build(&mut graph);
+
+        update_graph(&mut graph);
+
+        // Move the sound towards the listener.
+        graph[sound]
+            .local_transform_mut()
+            .set_position(Vector3::new(9.0, 0.0, 0.0));
+        update_graph(&mut graph);
+
+        let native = graph[sound].cast::<Sound>().unwrap().native.get();
+        let state = graph.sound_context.native.state();
+        let source = state.source(native);
+        assert!(
+            source
+                .velocity()
+                .metric_distance(&Vector3::new(-10.0, 0.0, 0.0))
+                < 1.0e-3
+        );
+        assert!(source.calculate_doppler_pitch(state.listener()) > 1.0);
+    }
+}
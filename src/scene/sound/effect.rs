@@ -2,19 +2,33 @@
 
 use crate::{
     core::{
-        define_is_as, pool::Handle, reflect::prelude::*, variable::InheritableVariable,
-        visitor::prelude::*,
+        define_is_as, math::aabb::AxisAlignedBoundingBox, pool::Handle, reflect::prelude::*,
+        variable::InheritableVariable, visitor::prelude::*,
     },
     define_with,
     scene::sound::context::SoundContext,
 };
+use fyrox_sound::{
+    buffer::SoundBufferResource,
+    effects::{
+        delay::Delay,
+        reverb::{Reverb, ReverbPreset},
+        EffectInput,
+    },
+    engine::SoundEngine,
+    error::SoundError,
+    source::{SoundSourceBuilder, Status},
+};
 use std::{
     cell::Cell,
     ops::{Deref, DerefMut},
+    str::FromStr,
+    time::Duration,
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 const DEFAULT_FC: f32 = 0.25615; // 11296 Hz at 44100 Hz sample rate
+const DEFAULT_MODULATION_RATE: f32 = 0.5;
 
 /// Base effect contains common properties for every effect (gain, inputs, etc.)
 #[derive(Visit, Reflect, Debug, Clone)]
@@ -29,6 +43,14 @@ pub struct BaseEffect {
     #[reflect(setter = "set_gain")]
     pub(crate) gain: InheritableVariable<f32>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_zone",
+        description = "World-space volume of the effect. Every sound inside the volume is sent \
+        to the effect, regardless of the listener position."
+    )]
+    pub(crate) zone: InheritableVariable<Option<AxisAlignedBoundingBox>>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<Handle<fyrox_sound::effects::Effect>>,
@@ -63,6 +85,23 @@ impl BaseEffect {
     fn set_name_internal(&mut self, name: String) -> String {
         self.name.set_value_and_mark_modified(name)
     }
+
+    /// Sets world-space volume (zone) of the effect. Every sound, that is inside the zone, is
+    /// automatically sent to the effect in addition to the effect it is bound to by name. Zones
+    /// are tested against positions of sounds, not the listener, so a sound inside a cave will
+    /// be reverberant even if the listener is outside. If a sound is inside multiple zones, then
+    /// the last effect will be used. `None` (default) disables the zone.
+    pub fn set_zone(
+        &mut self,
+        zone: Option<AxisAlignedBoundingBox>,
+    ) -> Option<AxisAlignedBoundingBox> {
+        self.zone.set_value_and_mark_modified(zone)
+    }
+
+    /// Returns world-space volume (zone) of the effect.
+    pub fn zone(&self) -> Option<AxisAlignedBoundingBox> {
+        *self.zone
+    }
 }
 
 impl Default for BaseEffect {
@@ -70,6 +109,7 @@ impl Default for BaseEffect {
         Self {
             name: InheritableVariable::new("Primary".to_string()),
             gain: InheritableVariable::new(1.0),
+            zone: InheritableVariable::new(None),
             native: Default::default(),
         }
     }
@@ -80,6 +120,8 @@ impl Default for BaseEffect {
 pub enum Effect {
     /// See [`ReverbEffect`] docs.
     Reverb(ReverbEffect),
+    /// See [`DelayEffect`] docs.
+    Delay(DelayEffect),
 }
 
 impl Deref for Effect {
@@ -88,6 +130,7 @@ impl Deref for Effect {
     fn deref(&self) -> &Self::Target {
         match self {
             Effect::Reverb(v) => v,
+            Effect::Delay(v) => v,
         }
     }
 }
@@ -96,6 +139,7 @@ impl DerefMut for Effect {
     fn deref_mut(&mut self) -> &mut Self::Target {
         match self {
             Effect::Reverb(v) => v,
+            Effect::Delay(v) => v,
         }
     }
 }
@@ -108,12 +152,89 @@ impl Default for Effect {
 
 impl Effect {
     define_is_as!(Effect : Reverb -> ref ReverbEffect => fn is_reverb, fn as_reverb, fn as_reverb_mut);
+    define_is_as!(Effect : Delay -> ref DelayEffect => fn is_delay, fn as_delay, fn as_delay_mut);
+
+    /// Creates a new effect of the given variant (`Reverb`, `Delay`, etc.) with default parameters,
+    /// but with the base properties (name, gain, zone) of this effect.
+    /// Returns `None` if there's no variant with such name. Use [`SoundContext::replace_effect`] to
+    /// put the new effect in place of this one.
+    pub fn with_variant(&self, variant: &str) -> Option<Effect> {
+        let mut effect = Effect::from_str(variant).ok()?;
+        *effect = (**self).clone();
+        Some(effect)
+    }
+
+    /// Renders given buffer in a loop through a copy of the effect and returns `sample_count` stereo
+    /// samples of the output. The buffer is played in an isolated sound context, which is not bound
+    /// to any scene or output device, the buffer is mixed to the output directly as well, just like
+    /// sounds in a scene do. It could be used to preview parameters of the effect without running
+    /// the game.
+    pub fn render_preview(
+        &self,
+        buffer: SoundBufferResource,
+        sample_count: usize,
+    ) -> Result<Vec<(f32, f32)>, SoundError> {
+        let context = fyrox_sound::context::SoundContext::new();
+
+        {
+            let mut state = context.state();
+            let effect = state.add_effect(self.make_native());
+            let source = state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer)
+                    .with_looping(true)
+                    .with_spatial_blend_factor(0.0)
+                    .with_status(Status::Playing)
+                    .build()?,
+            );
+            state
+                .effect_mut(effect)
+                .add_input(EffectInput::direct(source));
+        }
+
+        let engine = SoundEngine::without_device();
+        let mut engine = engine.lock().unwrap();
+        engine.add_context(context);
+
+        let mut samples = Vec::with_capacity(sample_count);
+        let mut buf = vec![(0.0, 0.0); SoundEngine::render_buffer_len()];
+        while samples.len() < sample_count {
+            engine.render(&mut buf);
+            samples.extend_from_slice(&buf[..buf.len().min(sample_count - samples.len())]);
+        }
+
+        Ok(samples)
+    }
+
+    pub(crate) fn make_native(&self) -> fyrox_sound::effects::Effect {
+        match self {
+            Effect::Reverb(reverb) => {
+                let mut native_reverb = Reverb::new(Default::default());
+                native_reverb.set_gain(reverb.gain());
+                native_reverb.set_fc(reverb.fc());
+                native_reverb.set_decay_time(Duration::from_secs_f32(reverb.decay_time()));
+                native_reverb.set_dry(reverb.dry());
+                native_reverb.set_wet(reverb.wet());
+                native_reverb.set_modulation(reverb.modulation_depth(), reverb.modulation_rate());
+                fyrox_sound::effects::Effect::Reverb(native_reverb)
+            }
+            Effect::Delay(delay) => {
+                let mut native_delay = Delay::new(Default::default());
+                native_delay.set_gain(delay.gain());
+                native_delay.set_delay_time(delay.delay_time());
+                native_delay.set_feedback(delay.feedback());
+                native_delay.set_mix(delay.mix());
+                fyrox_sound::effects::Effect::Delay(native_delay)
+            }
+        }
+    }
 }
 
 /// Base effect builder allows you to build an effect.
 pub struct BaseEffectBuilder {
     gain: f32,
     name: String,
+    zone: Option<AxisAlignedBoundingBox>,
 }
 
 impl Default for BaseEffectBuilder {
@@ -128,6 +249,7 @@ impl BaseEffectBuilder {
         Self {
             gain: 1.0,
             name: "".to_owned(),
+            zone: None,
         }
     }
 
@@ -141,11 +263,17 @@ impl BaseEffectBuilder {
         fn with_gain(gain: f32)
     );
 
+    define_with!(
+        /// Sets desired zone of the effect. See [`BaseEffect::set_zone`] for more info.
+        fn with_zone(zone: Option<AxisAlignedBoundingBox>)
+    );
+
     /// Creates new base effect.
     pub fn build(self) -> BaseEffect {
         BaseEffect {
             name: self.name.into(),
             gain: self.gain.into(),
+            zone: self.zone.into(),
             native: Default::default(),
         }
     }
@@ -167,6 +295,24 @@ pub struct ReverbEffect {
 
     #[reflect(setter = "set_decay_time")]
     pub(crate) decay_time: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_modulation_depth",
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Modulation depth of the internal delay lines, it reduces metallic tone of \
+        the reverb. 0.0 - no modulation."
+    )]
+    pub(crate) modulation_depth: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_modulation_rate",
+        min_value = 0.0,
+        description = "Modulation rate (in Hz) of the internal delay lines."
+    )]
+    pub(crate) modulation_rate: InheritableVariable<f32>,
 }
 
 impl Default for ReverbEffect {
@@ -177,6 +323,8 @@ impl Default for ReverbEffect {
             wet: InheritableVariable::new(1.0),
             fc: InheritableVariable::new(DEFAULT_FC),
             decay_time: InheritableVariable::new(3.0),
+            modulation_depth: InheritableVariable::new(0.0),
+            modulation_rate: InheritableVariable::new(DEFAULT_MODULATION_RATE),
         }
     }
 }
@@ -196,6 +344,38 @@ impl DerefMut for ReverbEffect {
 }
 
 impl ReverbEffect {
+    /// Creates new reverb effect with the parameters of the given preset. See [`ReverbPreset`]
+    /// for the list of available presets.
+    pub fn from_preset(preset: ReverbPreset) -> Self {
+        Self {
+            dry: preset.dry.into(),
+            wet: preset.wet.into(),
+            fc: preset.fc().into(),
+            decay_time: preset.decay_time.as_secs_f32().into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates new reverb of a small room, RT60 is ~0.4 seconds. See [`ReverbPreset::SMALL_ROOM`].
+    pub fn small_room() -> Self {
+        Self::from_preset(ReverbPreset::SMALL_ROOM)
+    }
+
+    /// Creates new reverb of a large hall, RT60 is ~2.5 seconds. See [`ReverbPreset::LARGE_HALL`].
+    pub fn large_hall() -> Self {
+        Self::from_preset(ReverbPreset::LARGE_HALL)
+    }
+
+    /// Creates new reverb of a cave, RT60 is ~4 seconds. See [`ReverbPreset::CAVE`].
+    pub fn cave() -> Self {
+        Self::from_preset(ReverbPreset::CAVE)
+    }
+
+    /// Creates new reverb of a bathroom, RT60 is ~1.2 seconds. See [`ReverbPreset::BATHROOM`].
+    pub fn bathroom() -> Self {
+        Self::from_preset(ReverbPreset::BATHROOM)
+    }
+
     /// Sets how much of input signal should be passed to output without any processing.
     /// Default value is 1.0.
     pub fn set_dry(&mut self, dry: f32) -> f32 {
@@ -251,6 +431,38 @@ impl ReverbEffect {
     pub fn fc(&self) -> f32 {
         *self.fc
     }
+
+    /// Sets modulation of the internal delay lines. See [`Self::set_modulation_depth`] and
+    /// [`Self::set_modulation_rate`] for more info.
+    pub fn set_modulation(&mut self, depth: f32, rate: f32) {
+        self.set_modulation_depth(depth);
+        self.set_modulation_rate(rate);
+    }
+
+    /// Sets modulation depth of the internal delay lines, slow modulation breaks up resonances of
+    /// the reverb and reduces its "metallic" tone. The depth is clamped to `[0; 1]` range, 0.0
+    /// means no modulation (default).
+    pub fn set_modulation_depth(&mut self, depth: f32) -> f32 {
+        self.modulation_depth
+            .set_value_and_mark_modified(depth.clamp(0.0, 1.0))
+    }
+
+    /// Returns modulation depth of the internal delay lines.
+    pub fn modulation_depth(&self) -> f32 {
+        *self.modulation_depth
+    }
+
+    /// Sets modulation rate (in Hz) of the internal delay lines. It should be low (0.1 - 2 Hz) to
+    /// not produce audible pitch changes.
+    pub fn set_modulation_rate(&mut self, rate: f32) -> f32 {
+        self.modulation_rate
+            .set_value_and_mark_modified(rate.max(0.0))
+    }
+
+    /// Returns modulation rate (in Hz) of the internal delay lines.
+    pub fn modulation_rate(&self) -> f32 {
+        *self.modulation_rate
+    }
 }
 
 /// Allows you to create a new reverb effect.
@@ -260,6 +472,8 @@ pub struct ReverbEffectBuilder {
     wet: f32,
     fc: f32,
     decay_time: f32,
+    modulation_depth: f32,
+    modulation_rate: f32,
 }
 
 impl ReverbEffectBuilder {
@@ -271,6 +485,8 @@ impl ReverbEffectBuilder {
             wet: 1.0,
             fc: DEFAULT_FC,
             decay_time: 3.0,
+            modulation_depth: 0.0,
+            modulation_rate: DEFAULT_MODULATION_RATE,
         }
     }
 
@@ -294,6 +510,16 @@ impl ReverbEffectBuilder {
         fn with_decay_time(decay_time: f32)
     );
 
+    define_with!(
+        /// Sets desired modulation depth of the internal delay lines.
+        fn with_modulation_depth(modulation_depth: f32)
+    );
+
+    define_with!(
+        /// Sets desired modulation rate (in Hz) of the internal delay lines.
+        fn with_modulation_rate(modulation_rate: f32)
+    );
+
     /// Creates new reverb effect.
     pub fn build_effect(self) -> Effect {
         Effect::Reverb(ReverbEffect {
@@ -302,6 +528,8 @@ impl ReverbEffectBuilder {
             wet: self.wet.into(),
             fc: self.fc.into(),
             decay_time: self.decay_time.into(),
+            modulation_depth: self.modulation_depth.into(),
+            modulation_rate: self.modulation_rate.into(),
         })
     }
 
@@ -310,3 +538,129 @@ impl ReverbEffectBuilder {
         context.add_effect(self.build_effect())
     }
 }
+
+/// Delay effect repeats input signal after some time with decreasing amplitude (echo).
+#[derive(Visit, Reflect, Debug, Clone)]
+pub struct DelayEffect {
+    pub(crate) base: BaseEffect,
+
+    #[reflect(setter = "set_delay_time")]
+    pub(crate) delay_time: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_feedback")]
+    pub(crate) feedback: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_mix")]
+    pub(crate) mix: InheritableVariable<f32>,
+}
+
+impl Default for DelayEffect {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            delay_time: InheritableVariable::new(0.5),
+            feedback: InheritableVariable::new(0.5),
+            mix: InheritableVariable::new(0.5),
+        }
+    }
+}
+
+impl Deref for DelayEffect {
+    type Target = BaseEffect;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for DelayEffect {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl DelayEffect {
+    /// Sets time (in seconds) between echoes.
+    pub fn set_delay_time(&mut self, delay_time: f32) -> f32 {
+        self.delay_time
+            .set_value_and_mark_modified(delay_time.max(0.0))
+    }
+
+    /// Returns time (in seconds) between echoes.
+    pub fn delay_time(&self) -> f32 {
+        *self.delay_time
+    }
+
+    /// Sets how much of the delayed signal is fed back into the delay, it defines how fast echoes
+    /// will decay. 0.0 - single echo, 1.0 - infinite echoes.
+    pub fn set_feedback(&mut self, feedback: f32) -> f32 {
+        self.feedback
+            .set_value_and_mark_modified(feedback.clamp(0.0, 1.0))
+    }
+
+    /// Returns feedback coefficient.
+    pub fn feedback(&self) -> f32 {
+        *self.feedback
+    }
+
+    /// Sets mixing of dry (input) and wet (delayed) signals. 0.0 - dry signal only, 1.0 - wet
+    /// signal only.
+    pub fn set_mix(&mut self, mix: f32) -> f32 {
+        self.mix.set_value_and_mark_modified(mix.clamp(0.0, 1.0))
+    }
+
+    /// Returns mixing coefficient of dry and wet signals.
+    pub fn mix(&self) -> f32 {
+        *self.mix
+    }
+}
+
+/// Allows you to create a new delay effect.
+pub struct DelayEffectBuilder {
+    base_builder: BaseEffectBuilder,
+    delay_time: f32,
+    feedback: f32,
+    mix: f32,
+}
+
+impl DelayEffectBuilder {
+    /// Creates new delay effect builder.
+    pub fn new(base_builder: BaseEffectBuilder) -> Self {
+        Self {
+            base_builder,
+            delay_time: 0.5,
+            feedback: 0.5,
+            mix: 0.5,
+        }
+    }
+
+    define_with!(
+        /// Sets desired delay time (in seconds).
+        fn with_delay_time(delay_time: f32)
+    );
+
+    define_with!(
+        /// Sets desired feedback coefficient.
+        fn with_feedback(feedback: f32)
+    );
+
+    define_with!(
+        /// Sets desired mixing coefficient of dry and wet signals.
+        fn with_mix(mix: f32)
+    );
+
+    /// Creates new delay effect.
+    pub fn build_effect(self) -> Effect {
+        Effect::Delay(DelayEffect {
+            base: self.base_builder.build(),
+            delay_time: self.delay_time.into(),
+            feedback: self.feedback.into(),
+            mix: self.mix.into(),
+        })
+    }
+
+    /// Creates new delay effect and adds it to the context.
+    pub fn build(self, context: &mut SoundContext) -> Handle<Effect> {
+        context.add_effect(self.build_effect())
+    }
+}
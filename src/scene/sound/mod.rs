@@ -22,7 +22,7 @@ use crate::{
 pub use fyrox_sound::{
     buffer::{DataSource, SoundBufferResource, SoundBufferResourceLoadError, SoundBufferState},
     context::{DistanceModel, SAMPLE_RATE},
-    dsp::{filters::*, DelayLine},
+    dsp::{fade::FadeCurve, filters::*, limiter::LimiterParams, DelayLine},
     engine::SoundEngine,
     error::SoundError,
     hrtf::HrirSphere,
@@ -32,10 +32,10 @@ pub use fyrox_sound::{
 
 use crate::scene::Scene;
 use fyrox_resource::ResourceState;
-use fyrox_sound::source::SoundSource;
+use fyrox_sound::{bus::BusGraph, source::SoundSource};
 use std::{
     cell::Cell,
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Range},
     time::Duration,
 };
 
@@ -44,7 +44,7 @@ pub mod effect;
 pub mod listener;
 
 /// Sound source.
-#[derive(Visit, Reflect, Debug)]
+#[derive(Reflect, Debug)]
 pub struct Sound {
     base: Base,
 
@@ -84,20 +84,118 @@ pub struct Sound {
     #[reflect(setter = "set_rolloff_factor")]
     rolloff_factor: InheritableVariable<f32>,
 
+    #[reflect(min_value = 0.0, max_value = 6.28318, step = 0.05)]
+    #[reflect(setter = "set_cone_inner_angle")]
+    #[reflect(
+        description = "Full angle (in radians) of the inner emission cone, the cone is oriented \
+        along the look vector of the node. 2π - omnidirectional sound."
+    )]
+    cone_inner_angle: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 6.28318, step = 0.05)]
+    #[reflect(setter = "set_cone_outer_angle")]
+    #[reflect(
+        description = "Full angle (in radians) of the outer emission cone. Between the inner and \
+        the outer cones the gain fades to the outer cone gain."
+    )]
+    cone_outer_angle: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[reflect(setter = "set_cone_outer_gain")]
+    #[reflect(description = "Gain of the sound outside of the outer emission cone.")]
+    cone_outer_gain: InheritableVariable<f32>,
+
     #[reflect(setter = "set_playback_time")]
     playback_time: InheritableVariable<Duration>,
 
     #[reflect(setter = "set_spatial_blend")]
     spatial_blend: InheritableVariable<f32>,
 
-    #[visit(optional)]
+    #[reflect(setter = "set_effect_names")]
+    #[reflect(
+        description = "Names of sound effects to which the sound will attach to when instantiated."
+    )]
+    effect_names: InheritableVariable<Vec<String>>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[reflect(setter = "set_effect_send_gain")]
+    #[reflect(
+        description = "Defines how much of the sound's signal will be passed to the sound effect. \
+        Can be animated to keyframe effect sends."
+    )]
+    effect_send_gain: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_bus")]
+    #[reflect(
+        description = "A name of an audio bus (sound category) to which the sound will be routed."
+    )]
+    bus: InheritableVariable<String>,
+
+    #[reflect(setter = "set_loop_region")]
+    #[reflect(
+        description = "A region (in samples per channel) of the buffer that will be repeated \
+        while the sound is looping. Overrides the loop region of the buffer."
+    )]
+    loop_region: InheritableVariable<Option<Range<u64>>>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_doppler_factor")]
+    #[reflect(
+        description = "Defines how much relative velocity of the sound and the listener changes \
+        pitch of the sound. 0.0 - disabled, 1.0 - physically correct."
+    )]
+    doppler_factor: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_play_on_awake")]
+    #[reflect(
+        description = "If set, the sound will start playing as soon as it enters the scene \
+        (or becomes enabled)."
+    )]
+    play_on_awake: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_smooth_seek")]
     #[reflect(
-        description = "A name of a sound effect to which the sound will attach to when instantiated."
+        description = "If set, changes of playback time will cross-fade old and new playback \
+        positions to prevent clicks."
     )]
-    effect_name: InheritableVariable<String>,
+    smooth_seek: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_priority")]
+    #[reflect(
+        description = "Priority of the sound for the audio budget of the sound context. Sounds \
+        with higher priority are less likely to be virtualized."
+    )]
+    priority: InheritableVariable<u8>,
+
+    #[reflect(setter = "set_fade_in")]
+    #[reflect(
+        description = "Duration of the volume ramp from silence when the sound starts playing."
+    )]
+    fade_in: InheritableVariable<Duration>,
+
+    #[reflect(setter = "set_fade_out")]
+    #[reflect(description = "Duration of the volume ramp to silence when the sound is stopped.")]
+    fade_out: InheritableVariable<Duration>,
+
+    #[reflect(setter = "set_fade_curve")]
+    #[reflect(description = "Shape of the fade in and fade out volume ramps.")]
+    fade_curve: InheritableVariable<FadeCurve>,
+
+    #[reflect(setter = "set_direct_output")]
+    #[reflect(
+        description = "Whether the sound is mixed to the output directly. When disabled, the sound \
+        is heard only through the effects it is attached to."
+    )]
+    direct_output: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_distance_model_override")]
+    #[reflect(
+        description = "Distance model of the sound, that is used instead of the distance model of \
+        the sound context. None - use the distance model of the sound context."
+    )]
+    distance_model_override: InheritableVariable<Option<DistanceModel>>,
 
     #[reflect(hidden)]
-    #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
 }
 
@@ -129,9 +227,24 @@ impl Default for Sound {
             radius: InheritableVariable::new(10.0),
             max_distance: InheritableVariable::new(f32::MAX),
             rolloff_factor: InheritableVariable::new(1.0),
+            cone_inner_angle: InheritableVariable::new(std::f32::consts::TAU),
+            cone_outer_angle: InheritableVariable::new(std::f32::consts::TAU),
+            cone_outer_gain: InheritableVariable::new(0.0),
             playback_time: Default::default(),
             spatial_blend: InheritableVariable::new(1.0),
-            effect_name: InheritableVariable::new("Primary".to_string()),
+            effect_names: InheritableVariable::new(vec!["Primary".to_string()]),
+            effect_send_gain: InheritableVariable::new(1.0),
+            bus: InheritableVariable::new(BusGraph::PRIMARY_BUS.to_string()),
+            loop_region: InheritableVariable::new(None),
+            doppler_factor: InheritableVariable::new(0.0),
+            play_on_awake: InheritableVariable::new(false),
+            smooth_seek: InheritableVariable::new(false),
+            priority: InheritableVariable::new(0),
+            fade_in: Default::default(),
+            fade_out: Default::default(),
+            fade_curve: Default::default(),
+            direct_output: InheritableVariable::new(true),
+            distance_model_override: Default::default(),
             native: Default::default(),
         }
     }
@@ -151,15 +264,83 @@ impl Clone for Sound {
             radius: self.radius.clone(),
             max_distance: self.max_distance.clone(),
             rolloff_factor: self.rolloff_factor.clone(),
+            cone_inner_angle: self.cone_inner_angle.clone(),
+            cone_outer_angle: self.cone_outer_angle.clone(),
+            cone_outer_gain: self.cone_outer_gain.clone(),
             playback_time: self.playback_time.clone(),
             spatial_blend: self.spatial_blend.clone(),
-            effect_name: self.effect_name.clone(),
+            effect_names: self.effect_names.clone(),
+            effect_send_gain: self.effect_send_gain.clone(),
+            bus: self.bus.clone(),
+            loop_region: self.loop_region.clone(),
+            doppler_factor: self.doppler_factor.clone(),
+            play_on_awake: self.play_on_awake.clone(),
+            smooth_seek: self.smooth_seek.clone(),
+            priority: self.priority.clone(),
+            fade_in: self.fade_in.clone(),
+            fade_out: self.fade_out.clone(),
+            fade_curve: self.fade_curve.clone(),
+            direct_output: self.direct_output.clone(),
+            distance_model_override: self.distance_model_override.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
         }
     }
 }
 
+impl Visit for Sound {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.base.visit("Base", &mut region)?;
+        self.buffer.visit("Buffer", &mut region)?;
+        self.play_once.visit("PlayOnce", &mut region)?;
+        self.gain.visit("Gain", &mut region)?;
+        self.panning.visit("Panning", &mut region)?;
+        self.status.visit("Status", &mut region)?;
+        self.looping.visit("Looping", &mut region)?;
+        self.pitch.visit("Pitch", &mut region)?;
+        self.radius.visit("Radius", &mut region)?;
+        self.max_distance.visit("MaxDistance", &mut region)?;
+        self.rolloff_factor.visit("RolloffFactor", &mut region)?;
+        self.playback_time.visit("PlaybackTime", &mut region)?;
+        self.spatial_blend.visit("SpatialBlend", &mut region)?;
+
+        if self.effect_names.visit("EffectNames", &mut region).is_err() && region.is_reading() {
+            // Backward compatibility - sounds could be attached to a single effect only.
+            let mut effect_name = InheritableVariable::<String>::default();
+            if effect_name.visit("EffectName", &mut region).is_ok() && !effect_name.is_empty() {
+                let effect_names = vec![effect_name.clone_inner()];
+                if effect_name.is_modified() {
+                    self.effect_names.set_value_and_mark_modified(effect_names);
+                } else {
+                    self.effect_names.set_value_silent(effect_names);
+                }
+            }
+        }
+
+        let _ = self.effect_send_gain.visit("EffectSendGain", &mut region);
+        let _ = self.bus.visit("Bus", &mut region);
+        let _ = self.loop_region.visit("LoopRegion", &mut region);
+        let _ = self.doppler_factor.visit("DopplerFactor", &mut region);
+        let _ = self.play_on_awake.visit("PlayOnAwake", &mut region);
+        let _ = self.smooth_seek.visit("SmoothSeek", &mut region);
+        let _ = self.priority.visit("Priority", &mut region);
+        let _ = self.fade_in.visit("FadeIn", &mut region);
+        let _ = self.fade_out.visit("FadeOut", &mut region);
+        let _ = self.fade_curve.visit("FadeCurve", &mut region);
+        let _ = self.direct_output.visit("DirectOutput", &mut region);
+        let _ = self
+            .distance_model_override
+            .visit("DistanceModelOverride", &mut region);
+        let _ = self.cone_inner_angle.visit("ConeInnerAngle", &mut region);
+        let _ = self.cone_outer_angle.visit("ConeOuterAngle", &mut region);
+        let _ = self.cone_outer_gain.visit("ConeOuterGain", &mut region);
+
+        Ok(())
+    }
+}
+
 impl TypeUuidProvider for Sound {
     fn type_uuid() -> Uuid {
         uuid!("28621735-8cd1-4fad-8faf-ecd24bf8aa99")
@@ -323,6 +504,43 @@ impl Sound {
         *self.rolloff_factor
     }
 
+    /// Sets full angle (in radians) of the inner emission cone. The cone is oriented along the look
+    /// vector of the node, so rotate the node to change emission direction. See
+    /// [`SoundSource::set_cone_inner_angle`] for more info.
+    pub fn set_cone_inner_angle(&mut self, angle: f32) -> f32 {
+        self.cone_inner_angle
+            .set_value_and_mark_modified(angle.clamp(0.0, std::f32::consts::TAU))
+    }
+
+    /// Returns full angle (in radians) of the inner emission cone.
+    pub fn cone_inner_angle(&self) -> f32 {
+        *self.cone_inner_angle
+    }
+
+    /// Sets full angle (in radians) of the outer emission cone. See
+    /// [`SoundSource::set_cone_outer_angle`] for more info.
+    pub fn set_cone_outer_angle(&mut self, angle: f32) -> f32 {
+        self.cone_outer_angle
+            .set_value_and_mark_modified(angle.clamp(0.0, std::f32::consts::TAU))
+    }
+
+    /// Returns full angle (in radians) of the outer emission cone.
+    pub fn cone_outer_angle(&self) -> f32 {
+        *self.cone_outer_angle
+    }
+
+    /// Sets gain of the sound outside of the outer emission cone. See
+    /// [`SoundSource::set_cone_outer_gain`] for more info.
+    pub fn set_cone_outer_gain(&mut self, gain: f32) -> f32 {
+        self.cone_outer_gain
+            .set_value_and_mark_modified(gain.clamp(0.0, 1.0))
+    }
+
+    /// Returns gain of the sound outside of the outer emission cone.
+    pub fn cone_outer_gain(&self) -> f32 {
+        *self.cone_outer_gain
+    }
+
     /// Sets maximum distance until which distance gain will be applicable. Basically it doing this
     /// min(max(distance, radius), max_distance) which clamps distance in radius..max_distance range.
     /// From listener's perspective this will sound like source has stopped decreasing its volume even
@@ -336,14 +554,182 @@ impl Sound {
         *self.max_distance
     }
 
-    /// Sets new effect to which the sound will be attached.
+    /// Sets new set of effects to which the sound will be attached. The sound will be sent to every
+    /// effect with a matching name, effects that are no longer listed will stop receiving it.
+    pub fn set_effect_names(&mut self, names: Vec<String>) -> Vec<String> {
+        self.effect_names.set_value_and_mark_modified(names)
+    }
+
+    /// Attaches the sound to a single effect, it is a shortcut for [`Self::set_effect_names`] with
+    /// a single name.
     pub fn set_effect_name(&mut self, name: String) {
-        self.effect_name.set_value_and_mark_modified(name);
+        self.set_effect_names(vec![name]);
     }
 
-    /// Returns the name of an effect to which the sound is attached.
+    /// Returns the name of the first effect to which the sound is attached, or an empty string if
+    /// the sound is not attached to any effect.
+    #[deprecated(
+        note = "a sound could be attached to multiple effects, use `effect_names` instead"
+    )]
     pub fn effect_name(&self) -> &str {
-        &self.effect_name
+        self.effect_names
+            .first()
+            .map(|n| n.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of effects to which the sound is attached.
+    pub fn effect_names(&self) -> &[String] {
+        &self.effect_names
+    }
+
+    /// Returns true if the sound is attached to an effect with the given name.
+    pub fn is_attached_to_effect(&self, name: &str) -> bool {
+        self.effect_names.iter().any(|n| n == name)
+    }
+
+    /// Sets new send gain of the sound. It defines how much of the sound's signal will be passed
+    /// to the effect, to which the sound is attached. 0.0 - nothing, 1.0 - full signal. The value
+    /// is a numeric property, so it could be animated (for example, to keyframe reverb sends in
+    /// cutscenes).
+    pub fn set_effect_send_gain(&mut self, gain: f32) -> f32 {
+        self.effect_send_gain
+            .set_value_and_mark_modified(gain.max(0.0))
+    }
+
+    /// Returns current send gain of the sound.
+    pub fn effect_send_gain(&self) -> f32 {
+        *self.effect_send_gain
+    }
+
+    /// Sets the name of an audio bus (sound category) to which the sound will be routed. The bus
+    /// must exist in the bus graph of the sound context, otherwise the sound will be routed to the
+    /// primary bus.
+    pub fn set_bus(&mut self, bus: String) -> String {
+        self.bus.set_value_and_mark_modified(bus)
+    }
+
+    /// Returns the name of an audio bus to which the sound is routed.
+    pub fn bus(&self) -> &str {
+        &self.bus
+    }
+
+    /// Sets a region `[start_sample, end_sample)` of the buffer that will be repeated while the
+    /// sound is looping. See [`SoundSource::set_loop_region`] for more info.
+    pub fn set_loop_region(&mut self, region: Option<Range<u64>>) -> Option<Range<u64>> {
+        self.loop_region.set_value_and_mark_modified(region)
+    }
+
+    /// Returns the loop region of the sound (if any).
+    pub fn loop_region(&self) -> Option<Range<u64>> {
+        (*self.loop_region).clone()
+    }
+
+    /// Sets doppler factor of the sound. Velocity of the sound is calculated automatically using
+    /// its global position. See [`SoundSource::set_doppler_factor`] for more info.
+    pub fn set_doppler_factor(&mut self, factor: f32) -> f32 {
+        self.doppler_factor
+            .set_value_and_mark_modified(factor.max(0.0))
+    }
+
+    /// Returns doppler factor of the sound.
+    pub fn doppler_factor(&self) -> f32 {
+        *self.doppler_factor
+    }
+
+    /// Enables or disables "play on awake" mode. In this mode the sound will start playing as soon
+    /// as it enters the scene or becomes enabled, so there is no need to start it manually. It is
+    /// useful for ambience, music, etc.
+    pub fn set_play_on_awake(&mut self, play_on_awake: bool) -> bool {
+        self.play_on_awake
+            .set_value_and_mark_modified(play_on_awake)
+    }
+
+    /// Returns true if the sound is in "play on awake" mode, false - otherwise.
+    pub fn is_play_on_awake(&self) -> bool {
+        *self.play_on_awake
+    }
+
+    /// Enables or disables smooth seek. When enabled, changes of playback time of a playing sound
+    /// do not cause clicks. See [`SoundSource::set_smooth_seek`] for more info.
+    pub fn set_smooth_seek(&mut self, smooth_seek: bool) -> bool {
+        self.smooth_seek.set_value_and_mark_modified(smooth_seek)
+    }
+
+    /// Returns true if smooth seek is enabled, false - otherwise.
+    pub fn is_smooth_seek(&self) -> bool {
+        *self.smooth_seek
+    }
+
+    /// Sets priority of the sound for the audio budget of the sound context. See
+    /// [`SoundSource::set_priority`] for more info.
+    pub fn set_priority(&mut self, priority: u8) -> u8 {
+        self.priority.set_value_and_mark_modified(priority)
+    }
+
+    /// Returns priority of the sound.
+    pub fn priority(&self) -> u8 {
+        *self.priority
+    }
+
+    /// Sets duration of the volume ramp from silence when the sound starts playing. See
+    /// [`SoundSource::set_fade_in`] for more info.
+    pub fn set_fade_in(&mut self, fade_in: Duration) -> Duration {
+        self.fade_in.set_value_and_mark_modified(fade_in)
+    }
+
+    /// Returns duration of fade in.
+    pub fn fade_in(&self) -> Duration {
+        *self.fade_in
+    }
+
+    /// Sets duration of the volume ramp to silence when the sound is stopped. See
+    /// [`SoundSource::set_fade_out`] for more info.
+    pub fn set_fade_out(&mut self, fade_out: Duration) -> Duration {
+        self.fade_out.set_value_and_mark_modified(fade_out)
+    }
+
+    /// Returns duration of fade out.
+    pub fn fade_out(&self) -> Duration {
+        *self.fade_out
+    }
+
+    /// Sets the shape of the fade in and fade out volume ramps. See
+    /// [`SoundSource::set_fade_curve`] for more info.
+    pub fn set_fade_curve(&mut self, fade_curve: FadeCurve) -> FadeCurve {
+        self.fade_curve.set_value_and_mark_modified(fade_curve)
+    }
+
+    /// Returns the shape of the fade in and fade out volume ramps.
+    pub fn fade_curve(&self) -> FadeCurve {
+        *self.fade_curve
+    }
+
+    /// Enables or disables direct output of the sound. When disabled, the sound is heard only
+    /// through the effects it is attached to. See [`SoundSource::set_direct_output`] for more info.
+    pub fn set_direct_output(&mut self, direct_output: bool) -> bool {
+        self.direct_output
+            .set_value_and_mark_modified(direct_output)
+    }
+
+    /// Returns true if the sound is mixed to the output directly.
+    pub fn is_direct_output(&self) -> bool {
+        *self.direct_output
+    }
+
+    /// Sets distance model of the sound, that is used instead of the distance model of the sound
+    /// context. See [`SoundSource::set_distance_model_override`] for more info.
+    pub fn set_distance_model_override(
+        &mut self,
+        distance_model: Option<DistanceModel>,
+    ) -> Option<DistanceModel> {
+        self.distance_model_override
+            .set_value_and_mark_modified(distance_model)
+    }
+
+    /// Returns distance model of the sound, that overrides the distance model of the sound context.
+    pub fn distance_model_override(&self) -> Option<DistanceModel> {
+        *self.distance_model_override
     }
 }
 
@@ -419,6 +805,55 @@ impl NodeTrait for Sound {
     }
 }
 
+/// Calculates the distance (from the sound's position) at which the gain of the sound drops below
+/// the given threshold using specified distance model. Own gain of the sound is taken into account
+/// as well. It could be used to visualize audible area of a sound, for example in the editor.
+///
+/// The result is always in `[0.0; max_distance]` range: `0.0` means that the sound is inaudible
+/// even at its radius, `max_distance` means that the gain never drops below the threshold (the
+/// attenuation stops at `max_distance`).
+pub fn sound_audible_radius(sound: &Sound, distance_model: DistanceModel, threshold: f32) -> f32 {
+    let gain = sound.gain();
+    let radius = sound.radius();
+    let max_distance = sound.max_distance();
+    let rolloff_factor = sound.rolloff_factor();
+
+    if threshold <= 0.0 {
+        return max_distance;
+    } else if gain < threshold {
+        return 0.0;
+    }
+
+    // Solve `gain * attenuation(distance) == threshold` for the distance.
+    let ratio = gain / threshold;
+    let distance = match distance_model {
+        DistanceModel::None => max_distance,
+        DistanceModel::InverseDistance => {
+            if rolloff_factor > 0.0 {
+                radius + radius * (ratio - 1.0) / rolloff_factor
+            } else {
+                max_distance
+            }
+        }
+        DistanceModel::LinearDistance => {
+            if radius > 0.0 {
+                radius + (1.0 - 1.0 / ratio) * (max_distance - radius) / radius
+            } else {
+                max_distance
+            }
+        }
+        DistanceModel::ExponentDistance => {
+            if rolloff_factor > 0.0 {
+                radius * ratio.powf(1.0 / rolloff_factor)
+            } else {
+                max_distance
+            }
+        }
+    };
+
+    distance.clamp(radius.min(max_distance), max_distance)
+}
+
 /// Sound builder, allows you to create a new [`Sound`] instance.
 pub struct SoundBuilder {
     base_builder: BaseBuilder,
@@ -432,9 +867,24 @@ pub struct SoundBuilder {
     radius: f32,
     max_distance: f32,
     rolloff_factor: f32,
+    cone_inner_angle: f32,
+    cone_outer_angle: f32,
+    cone_outer_gain: f32,
     playback_time: Duration,
     spatial_blend: f32,
-    effect_name: String,
+    effect_names: Vec<String>,
+    effect_send_gain: f32,
+    bus: String,
+    loop_region: Option<Range<u64>>,
+    doppler_factor: f32,
+    play_on_awake: bool,
+    smooth_seek: bool,
+    priority: u8,
+    fade_in: Duration,
+    fade_out: Duration,
+    fade_curve: FadeCurve,
+    direct_output: bool,
+    distance_model_override: Option<DistanceModel>,
 }
 
 impl SoundBuilder {
@@ -452,9 +902,24 @@ impl SoundBuilder {
             radius: 10.0,
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            cone_inner_angle: std::f32::consts::TAU,
+            cone_outer_angle: std::f32::consts::TAU,
+            cone_outer_gain: 0.0,
             spatial_blend: 1.0,
             playback_time: Default::default(),
-            effect_name: "".to_string(),
+            effect_names: Default::default(),
+            effect_send_gain: 1.0,
+            bus: BusGraph::PRIMARY_BUS.to_string(),
+            loop_region: None,
+            doppler_factor: 0.0,
+            play_on_awake: false,
+            smooth_seek: false,
+            priority: 0,
+            fade_in: Default::default(),
+            fade_out: Default::default(),
+            fade_curve: Default::default(),
+            direct_output: true,
+            distance_model_override: None,
         }
     }
 
@@ -508,6 +973,24 @@ impl SoundBuilder {
         fn with_rolloff_factor(rolloff_factor: f32)
     );
 
+    define_with!(
+        /// Sets full angle of the inner emission cone. See [`Sound::set_cone_inner_angle`] for more
+        /// info.
+        fn with_cone_inner_angle(cone_inner_angle: f32)
+    );
+
+    define_with!(
+        /// Sets full angle of the outer emission cone. See [`Sound::set_cone_outer_angle`] for more
+        /// info.
+        fn with_cone_outer_angle(cone_outer_angle: f32)
+    );
+
+    define_with!(
+        /// Sets gain outside of the outer emission cone. See [`Sound::set_cone_outer_gain`] for more
+        /// info.
+        fn with_cone_outer_gain(cone_outer_gain: f32)
+    );
+
     define_with!(
         /// Sets desired spatial blend factor. See [`Sound::set_spatial_blend`] for more info.
         fn with_spatial_blend_factor(spatial_blend: f32)
@@ -519,8 +1002,76 @@ impl SoundBuilder {
     );
 
     define_with!(
-        /// Sets desired playback time. See [`Sound::set_effect_name`] for more info.
-        fn with_effect_name(effect_name: String)
+        /// Sets desired effects. See [`Sound::set_effect_names`] for more info.
+        fn with_effect_names(effect_names: Vec<String>)
+    );
+
+    /// Sets desired effect, it replaces all effects set before. See [`Sound::set_effect_name`] for
+    /// more info.
+    pub fn with_effect_name(mut self, effect_name: String) -> Self {
+        self.effect_names = vec![effect_name];
+        self
+    }
+
+    define_with!(
+        /// Sets desired effect send gain. See [`Sound::set_effect_send_gain`] for more info.
+        fn with_effect_send_gain(effect_send_gain: f32)
+    );
+
+    define_with!(
+        /// Sets desired audio bus. See [`Sound::set_bus`] for more info.
+        fn with_bus(bus: String)
+    );
+
+    define_with!(
+        /// Sets desired loop region. See [`Sound::set_loop_region`] for more info.
+        fn with_loop_region(loop_region: Option<Range<u64>>)
+    );
+
+    define_with!(
+        /// Sets desired doppler factor. See [`Sound::set_doppler_factor`] for more info.
+        fn with_doppler_factor(doppler_factor: f32)
+    );
+
+    define_with!(
+        /// Sets "play on awake" mode. See [`Sound::set_play_on_awake`] for more info.
+        fn with_play_on_awake(play_on_awake: bool)
+    );
+
+    define_with!(
+        /// Sets smooth seek mode. See [`Sound::set_smooth_seek`] for more info.
+        fn with_smooth_seek(smooth_seek: bool)
+    );
+
+    define_with!(
+        /// Sets priority of the sound. See [`Sound::set_priority`] for more info.
+        fn with_priority(priority: u8)
+    );
+
+    define_with!(
+        /// Sets fade in duration. See [`Sound::set_fade_in`] for more info.
+        fn with_fade_in(fade_in: Duration)
+    );
+
+    define_with!(
+        /// Sets fade out duration. See [`Sound::set_fade_out`] for more info.
+        fn with_fade_out(fade_out: Duration)
+    );
+
+    define_with!(
+        /// Sets the shape of fade ramps. See [`Sound::set_fade_curve`] for more info.
+        fn with_fade_curve(fade_curve: FadeCurve)
+    );
+
+    define_with!(
+        /// Sets whether the sound is mixed to the output directly. See [`Sound::set_direct_output`]
+        /// for more info.
+        fn with_direct_output(direct_output: bool)
+    );
+
+    define_with!(
+        /// Sets distance model override. See [`Sound::set_distance_model_override`] for more info.
+        fn with_distance_model_override(distance_model_override: Option<DistanceModel>)
     );
 
     /// Creates a new [`Sound`] node.
@@ -538,9 +1089,24 @@ impl SoundBuilder {
             radius: self.radius.into(),
             max_distance: self.max_distance.into(),
             rolloff_factor: self.rolloff_factor.into(),
+            cone_inner_angle: self.cone_inner_angle.into(),
+            cone_outer_angle: self.cone_outer_angle.into(),
+            cone_outer_gain: self.cone_outer_gain.into(),
             playback_time: self.playback_time.into(),
             spatial_blend: self.spatial_blend.into(),
-            effect_name: self.effect_name.into(),
+            effect_names: self.effect_names.into(),
+            effect_send_gain: self.effect_send_gain.into(),
+            bus: self.bus.into(),
+            loop_region: self.loop_region.into(),
+            doppler_factor: self.doppler_factor.into(),
+            play_on_awake: self.play_on_awake.into(),
+            smooth_seek: self.smooth_seek.into(),
+            priority: self.priority.into(),
+            fade_in: self.fade_in.into(),
+            fade_out: self.fade_out.into(),
+            fade_curve: self.fade_curve.into(),
+            direct_output: self.direct_output.into(),
+            distance_model_override: self.distance_model_override.into(),
             native: Default::default(),
         }
     }
@@ -562,9 +1128,29 @@ mod test {
     use crate::scene::base::test::inherit_node_properties;
     use crate::scene::{
         base::{test::check_inheritable_properties_equality, BaseBuilder},
-        sound::{Sound, SoundBuilder},
+        sound::{
+            context::SoundContext,
+            effect::{BaseEffectBuilder, ReverbEffectBuilder},
+            sound_audible_radius, DistanceModel, Sound, SoundBuilder,
+        },
+    };
+    use crate::{
+        animation::{
+            container::{TrackDataContainer, TrackValueKind},
+            track::Track,
+            value::{BoundValueCollection, ValueBinding, ValueType},
+        },
+        core::{
+            curve::{CurveKey, CurveKeyKind},
+            pool::Handle,
+            variable::InheritableVariable,
+            visitor::prelude::*,
+        },
+    };
+    use fyrox_sound::{
+        buffer::{DataSource, SoundBufferResource},
+        source::Status,
     };
-    use fyrox_sound::source::Status;
     use std::time::Duration;
 
     #[test]
@@ -589,4 +1175,211 @@ mod test {
         check_inheritable_properties_equality(&child.base, &parent.base);
         check_inheritable_properties_equality(&child, parent);
     }
+
+    #[test]
+    fn test_animated_effect_send_gain() {
+        let mut context = SoundContext::new();
+        let effect =
+            ReverbEffectBuilder::new(BaseEffectBuilder::new().with_name("Reverb".to_string()))
+                .build(&mut context);
+        // Create native effect.
+        context.update();
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.0; 44100],
+        })
+        .unwrap();
+
+        let mut node = SoundBuilder::new(BaseBuilder::new())
+            .with_buffer(Some(buffer))
+            .with_effect_name("Reverb".to_string())
+            .build_node();
+
+        context.sync_to_sound(Handle::NONE, node.cast::<Sound>().unwrap(), None);
+
+        let mut track = Track::new(
+            TrackDataContainer::new(TrackValueKind::Real),
+            ValueBinding::Property {
+                name: "effect_send_gain".to_string(),
+                value_type: ValueType::F32,
+            },
+        );
+        let curve = track.data_container_mut().curve_mut(0).unwrap();
+        curve.add_key(CurveKey::new(0.0, 1.0, CurveKeyKind::Linear));
+        curve.add_key(CurveKey::new(1.0, 0.0, CurveKeyKind::Linear));
+
+        // Sample the track and apply the value to the sound.
+        BoundValueCollection {
+            values: vec![track.fetch(0.75).unwrap()],
+        }
+        .apply(&mut node);
+
+        let sound = node.cast::<Sound>().unwrap();
+        assert_eq!(sound.effect_send_gain(), 0.25);
+
+        context.sync_to_sound(Handle::NONE, sound, None);
+
+        let native_effect = context.effect(effect).native.get();
+        let state = context.native.state();
+        let inputs = state.effect(native_effect).inputs_ref();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].source(), sound.native.get());
+        assert_eq!(inputs[0].gain(), 0.25);
+    }
+
+    #[test]
+    fn test_play_on_awake() {
+        let mut context = SoundContext::new();
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.0; 44100],
+        })
+        .unwrap();
+
+        let mut sound = SoundBuilder::new(BaseBuilder::new())
+            .with_buffer(Some(buffer))
+            .with_play_on_awake(true)
+            .build_sound();
+        assert_eq!(sound.status(), Status::Stopped);
+
+        // First sync must start the sound.
+        context.sync_to_sound(Handle::NONE, &sound, None);
+        context.sync_with_sound(&mut sound);
+        assert_eq!(sound.status(), Status::Playing);
+        assert_eq!(
+            context.native.state().source(sound.native.get()).status(),
+            Status::Playing
+        );
+
+        sound.stop();
+        context.sync_to_sound(Handle::NONE, &sound, None);
+
+        // Subsequent syncs must not restart the sound.
+        context.sync_to_sound(Handle::NONE, &sound, None);
+        context.sync_with_sound(&mut sound);
+        assert_eq!(sound.status(), Status::Stopped);
+        assert_eq!(
+            context.native.state().source(sound.native.get()).status(),
+            Status::Stopped
+        );
+    }
+
+    #[test]
+    fn test_sound_audible_radius() {
+        let sound = SoundBuilder::new(BaseBuilder::new())
+            .with_gain(2.0)
+            .with_radius(2.0)
+            .with_rolloff_factor(1.0)
+            .with_max_distance(100.0)
+            .build_sound();
+
+        let threshold = 0.25;
+
+        for distance_model in [
+            DistanceModel::InverseDistance,
+            DistanceModel::LinearDistance,
+            DistanceModel::ExponentDistance,
+        ] {
+            let radius = sound_audible_radius(&sound, distance_model, threshold);
+            assert!(radius > sound.radius() && radius < sound.max_distance());
+
+            let gain = sound.gain()
+                * distance_model.attenuation(
+                    radius,
+                    sound.radius(),
+                    sound.rolloff_factor(),
+                    sound.max_distance(),
+                );
+            assert!((gain - threshold).abs() < 1e-4, "{:?}", distance_model);
+        }
+
+        // Inverse distance: 2 * 2 / (2 + 1 * (d - 2)) = 0.25 => d = 16
+        assert_eq!(
+            sound_audible_radius(&sound, DistanceModel::InverseDistance, threshold),
+            16.0
+        );
+
+        // No attenuation - always audible.
+        assert_eq!(
+            sound_audible_radius(&sound, DistanceModel::None, threshold),
+            sound.max_distance()
+        );
+
+        // Too quiet to be heard at all.
+        assert_eq!(
+            sound_audible_radius(&sound, DistanceModel::InverseDistance, 3.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_legacy_effect_name_migration() {
+        let mut visitor = Visitor::new();
+        {
+            // Write the sound in the same way as the older versions did.
+            let mut sound = Sound::default();
+            let mut region = visitor.enter_region("Sound").unwrap();
+            sound.base.visit("Base", &mut region).unwrap();
+            sound.buffer.visit("Buffer", &mut region).unwrap();
+            sound.play_once.visit("PlayOnce", &mut region).unwrap();
+            sound.gain.visit("Gain", &mut region).unwrap();
+            sound.panning.visit("Panning", &mut region).unwrap();
+            sound.status.visit("Status", &mut region).unwrap();
+            sound.looping.visit("Looping", &mut region).unwrap();
+            sound.pitch.visit("Pitch", &mut region).unwrap();
+            sound.radius.visit("Radius", &mut region).unwrap();
+            sound
+                .max_distance
+                .visit("MaxDistance", &mut region)
+                .unwrap();
+            sound
+                .rolloff_factor
+                .visit("RolloffFactor", &mut region)
+                .unwrap();
+            sound
+                .playback_time
+                .visit("PlaybackTime", &mut region)
+                .unwrap();
+            sound
+                .spatial_blend
+                .visit("SpatialBlend", &mut region)
+                .unwrap();
+            InheritableVariable::new_modified("Reverb".to_string())
+                .visit("EffectName", &mut region)
+                .unwrap();
+        }
+
+        let mut visitor = Visitor::load_from_memory(visitor.save_binary_to_vec().unwrap()).unwrap();
+        let mut sound = Sound::default();
+        sound.visit("Sound", &mut visitor).unwrap();
+
+        assert_eq!(sound.effect_names(), &["Reverb".to_string()]);
+        assert!(sound.effect_names.is_modified());
+        assert!(sound.is_attached_to_effect("Reverb"));
+    }
+
+    #[test]
+    fn test_default_sound_is_attached_to_primary_effect() {
+        // Default name of effects is "Primary", so default sounds are heard through them.
+        let sound = Sound::default();
+        assert_eq!(sound.effect_names(), &["Primary".to_string()]);
+        assert!(sound.is_attached_to_effect("Primary"));
+    }
+
+    #[test]
+    fn test_with_effect_name_replaces_effects() {
+        let sound = SoundBuilder::new(BaseBuilder::new())
+            .with_effect_names(vec!["Reverb".to_string(), "Delay".to_string()])
+            .with_effect_name("Echo".to_string())
+            .build_sound();
+        assert_eq!(sound.effect_names(), &["Echo".to_string()]);
+        #[allow(deprecated)]
+        {
+            assert_eq!(sound.effect_name(), "Echo");
+        }
+    }
 }
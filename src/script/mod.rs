@@ -3,16 +3,22 @@
 //! Script is used to add custom logic to scene nodes. See [ScriptTrait] for more info.
 
 use crate::{
+    animation::Animation,
     core::{
+        algebra::Vector3,
         pool::Handle,
         reflect::{FieldInfo, Reflect, ReflectArray, ReflectList},
         uuid::Uuid,
-        visitor::{Visit, VisitResult, Visitor},
+        visitor::{Visit, VisitError, VisitResult, Visitor},
     },
     engine::{resource_manager::ResourceManager, ScriptMessageDispatcher},
     event::Event,
     plugin::Plugin,
-    scene::{node::Node, Scene},
+    scene::{
+        animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
+        node::Node,
+        Scene,
+    },
     utils::{component::ComponentProvider, log::Log},
 };
 use std::{
@@ -64,6 +70,20 @@ pub enum RoutingStrategy {
     Up,
     /// An message will be passed to every node down the tree in the hierarchy.
     Down,
+    /// Same as [`RoutingStrategy::Up`], but the message will be passed to at most `max_depth`
+    /// ancestors of the root node. `max_depth == 0` means that only the root node will receive
+    /// the message.
+    UpLimited {
+        /// Maximum amount of levels up from the root node.
+        max_depth: usize,
+    },
+    /// Same as [`RoutingStrategy::Down`], but the message will be passed only to descendants that
+    /// are at most `max_depth` levels below the root node. `max_depth == 0` means that only the
+    /// root node will receive the message.
+    DownLimited {
+        /// Maximum amount of levels down from the root node.
+        max_depth: usize,
+    },
 }
 
 /// A script message of a particular kind.
@@ -74,6 +94,23 @@ pub struct ScriptMessage {
     pub kind: ScriptMessageKind,
 }
 
+impl ScriptMessage {
+    /// Tries to cast the payload of the message to a particular type.
+    pub fn payload_as<T: 'static>(&self) -> Option<&T> {
+        self.payload.downcast_ref::<T>()
+    }
+
+    /// Tries to cast the payload of the message to a particular type.
+    pub fn payload_as_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.payload.downcast_mut::<T>()
+    }
+
+    /// Returns type id of the actual payload of the message.
+    pub fn payload_type_id(&self) -> TypeId {
+        self.payload.as_any_ref().type_id()
+    }
+}
+
 /// An message for a node with a script.
 pub enum ScriptMessageKind {
     /// An message for a specific scene node. It will be delivered only if the node is subscribed to receive
@@ -93,12 +130,25 @@ pub enum ScriptMessageKind {
     /// An message that will be delivered for **every** scene node that is subscribed to receive messages
     /// of a particular type.
     Global,
+
+    /// An message that will be delivered for every scene node with a script of a particular type
+    /// (defined by its type id). Unlike other kinds, such messages are delivered regardless of
+    /// subscriptions.
+    ScriptType(TypeId),
+}
+
+/// A script message with a delay before its delivery.
+pub(crate) struct ScheduledScriptMessage {
+    pub(crate) message: ScriptMessage,
+    /// Amount of time (in seconds) after which the message will be delivered. Zero means that the
+    /// message will be delivered as soon as possible.
+    pub(crate) delay: f32,
 }
 
 /// A script message sender.
 #[derive(Clone)]
 pub struct ScriptMessageSender {
-    pub(crate) sender: Sender<ScriptMessage>,
+    pub(crate) sender: Sender<ScheduledScriptMessage>,
 }
 
 impl Debug for ScriptMessageSender {
@@ -110,7 +160,21 @@ impl Debug for ScriptMessageSender {
 impl ScriptMessageSender {
     /// Send a generic script message.
     pub fn send(&self, message: ScriptMessage) {
-        if self.sender.send(message).is_err() {
+        self.send_delayed(message, 0.0)
+    }
+
+    /// Send a generic script message, that will be delivered after the given amount of time (in
+    /// seconds). Messages, whose delay elapses on the same frame, are delivered in the order they
+    /// were sent.
+    pub fn send_delayed(&self, message: ScriptMessage, delay: f32) {
+        if self
+            .sender
+            .send(ScheduledScriptMessage {
+                message,
+                delay: delay.max(0.0),
+            })
+            .is_err()
+        {
             Log::err("Failed to send script message, it means the scene is already deleted!");
         }
     }
@@ -120,10 +184,22 @@ impl ScriptMessageSender {
     where
         T: 'static + Send,
     {
-        self.send(ScriptMessage {
-            payload: Box::new(payload),
-            kind: ScriptMessageKind::Targeted(target),
-        })
+        self.send_to_target_delayed(target, payload, 0.0)
+    }
+
+    /// Sends a targeted script message with the given payload, that will be delivered after the
+    /// given amount of time (in seconds).
+    pub fn send_to_target_delayed<T>(&self, target: Handle<Node>, payload: T, delay: f32)
+    where
+        T: 'static + Send,
+    {
+        self.send_delayed(
+            ScriptMessage {
+                payload: Box::new(payload),
+                kind: ScriptMessageKind::Targeted(target),
+            },
+            delay,
+        )
     }
 
     /// Sends a global script message with the given payload.
@@ -131,10 +207,22 @@ impl ScriptMessageSender {
     where
         T: 'static + Send,
     {
-        self.send(ScriptMessage {
-            payload: Box::new(payload),
-            kind: ScriptMessageKind::Global,
-        })
+        self.send_global_delayed(payload, 0.0)
+    }
+
+    /// Sends a global script message with the given payload, that will be delivered after the
+    /// given amount of time (in seconds).
+    pub fn send_global_delayed<T>(&self, payload: T, delay: f32)
+    where
+        T: 'static + Send,
+    {
+        self.send_delayed(
+            ScriptMessage {
+                payload: Box::new(payload),
+                kind: ScriptMessageKind::Global,
+            },
+            delay,
+        )
     }
 
     /// Sends a hierarchical script message with the given payload.
@@ -142,10 +230,53 @@ impl ScriptMessageSender {
     where
         T: 'static + Send,
     {
-        self.send(ScriptMessage {
-            payload: Box::new(payload),
-            kind: ScriptMessageKind::Hierarchical { root, routing },
-        })
+        self.send_hierarchical_delayed(root, routing, payload, 0.0)
+    }
+
+    /// Sends a hierarchical script message with the given payload, that will be delivered after
+    /// the given amount of time (in seconds).
+    pub fn send_hierarchical_delayed<T>(
+        &self,
+        root: Handle<Node>,
+        routing: RoutingStrategy,
+        payload: T,
+        delay: f32,
+    ) where
+        T: 'static + Send,
+    {
+        self.send_delayed(
+            ScriptMessage {
+                payload: Box::new(payload),
+                kind: ScriptMessageKind::Hierarchical { root, routing },
+            },
+            delay,
+        )
+    }
+
+    /// Sends a script message with the given payload to every node with a script of type `S`.
+    /// Subscriptions are ignored for such messages, see [`ScriptMessageKind::ScriptType`].
+    pub fn send_to_script_type<S, T>(&self, payload: T)
+    where
+        S: ScriptTrait,
+        T: 'static + Send,
+    {
+        self.send_to_script_type_delayed::<S, T>(payload, 0.0)
+    }
+
+    /// Sends a script message with the given payload to every node with a script of type `S`,
+    /// that will be delivered after the given amount of time (in seconds).
+    pub fn send_to_script_type_delayed<S, T>(&self, payload: T, delay: f32)
+    where
+        S: ScriptTrait,
+        T: 'static + Send,
+    {
+        self.send_delayed(
+            ScriptMessage {
+                payload: Box::new(payload),
+                kind: ScriptMessageKind::ScriptType(TypeId::of::<S>()),
+            },
+            delay,
+        )
     }
 }
 
@@ -180,7 +311,8 @@ where
 
 /// A set of data, that provides contextual information for script methods.
 pub struct ScriptContext<'a, 'b, 'c> {
-    /// Amount of time that passed from last call. It has valid values only when called from `on_update`.
+    /// Amount of time that passed from last call. It has valid values only when called from `on_update`
+    /// (time step of the script update) or `on_physics_update` (time step of the physics update).
     pub dt: f32,
 
     /// Amount of time (in seconds) that passed from creation of the engine. Keep in mind, that
@@ -188,6 +320,15 @@ pub struct ScriptContext<'a, 'b, 'c> {
     /// which the engine "ticks" and this delta time affects elapsed time.
     pub elapsed_time: f32,
 
+    /// Index of the current frame. It is incremented once per engine update, so it is the same for
+    /// every script method called during the frame. It could be used to tag events by frame (for
+    /// example in replay systems).
+    pub frame_index: u64,
+
+    /// Time step of the previous frame. Could be used to detect hitches or to calculate derivatives
+    /// (for example acceleration) without storing any state in the script.
+    pub previous_dt: f32,
+
     /// A reference to the plugin which the script instance belongs to. You can use it to access plugin data
     /// inside script methods. For example you can store some "global" data in the plugin - for example a
     /// controls configuration, some entity managers and so on.
@@ -219,6 +360,107 @@ pub struct ScriptContext<'a, 'b, 'c> {
     pub message_dispatcher: &'c mut ScriptMessageDispatcher,
 }
 
+impl<'a, 'b, 'c> ScriptContext<'a, 'b, 'c> {
+    /// Returns velocity of the node to which the script instance belongs to. The velocity is calculated
+    /// using current and previous-tick global positions of the node, divided by the time step of the
+    /// graph update that recorded them (see [`crate::scene::graph::Graph::prev_transform_dt`]), so it
+    /// is correct in [`ScriptTrait::on_physics_update`] as well, where `dt` is the physics step. See
+    /// [`crate::scene::base::Base::global_velocity`] for more info.
+    pub fn velocity(&self) -> Vector3<f32> {
+        let graph = &self.scene.graph;
+        graph
+            .try_get(self.handle)
+            .map(|node| node.global_velocity(graph.prev_transform_dt()))
+            .unwrap_or_default()
+    }
+
+    /// Returns a handle of an animation player associated with the node to which the script instance
+    /// belongs to. If the node is an animation blending state machine, its animation player is used,
+    /// otherwise the node itself or its first descendant that is an animation player is used. Returns
+    /// [`Handle::NONE`] if there is no such animation player.
+    pub fn animation_player(&self) -> Handle<Node> {
+        let graph = &self.scene.graph;
+
+        if let Some(absm) = graph
+            .try_get(self.handle)
+            .and_then(|n| n.query_component_ref::<AnimationBlendingStateMachine>())
+        {
+            return absm.animation_player();
+        }
+
+        graph
+            .find(self.handle, &mut |n| {
+                n.query_component_ref::<AnimationPlayer>().is_some()
+            })
+            .map(|(handle, _)| handle)
+            .unwrap_or_default()
+    }
+
+    fn with_animation<F>(&mut self, name: &str, func: F) -> bool
+    where
+        F: FnOnce(&mut Animation),
+    {
+        let animation_player = self.animation_player();
+        if let Some((_, animation)) = self
+            .scene
+            .graph
+            .try_get_mut(animation_player)
+            .and_then(|n| n.query_component_mut::<AnimationPlayer>())
+            .and_then(|p| {
+                p.animations_mut()
+                    .get_value_mut_silent()
+                    .find_by_name_mut(name)
+            })
+        {
+            func(animation);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Starts playing an animation with the given name on the animation player associated with the node
+    /// (see [`Self::animation_player`]). Returns `false` if there is no such animation.
+    pub fn play_animation(&mut self, name: &str) -> bool {
+        self.with_animation(name, |animation| {
+            animation.set_enabled(true);
+        })
+    }
+
+    /// Stops an animation with the given name on the animation player associated with the node (see
+    /// [`Self::animation_player`]) and rewinds it to the beginning. Returns `false` if there is no such
+    /// animation.
+    pub fn stop_animation(&mut self, name: &str) -> bool {
+        self.with_animation(name, |animation| {
+            animation.set_enabled(false).rewind();
+        })
+    }
+
+    /// Sets playback speed of an animation with the given name on the animation player associated with the
+    /// node (see [`Self::animation_player`]). Returns `false` if there is no such animation.
+    pub fn set_animation_speed(&mut self, name: &str, speed: f32) -> bool {
+        self.with_animation(name, |animation| {
+            animation.set_speed(speed);
+        })
+    }
+
+    /// Sets weight of a layer with the given index of the animation blending state machine with the given
+    /// handle. It could be used to blend layers at runtime, see [`AnimationBlendingStateMachine::set_layer_weight`]
+    /// for more info. Returns `false` if there is no such machine or layer.
+    pub fn set_layer_weight(
+        &mut self,
+        absm: Handle<Node>,
+        layer_index: usize,
+        weight: f32,
+    ) -> bool {
+        self.scene
+            .graph
+            .try_get_mut(absm)
+            .and_then(|n| n.query_component_mut::<AnimationBlendingStateMachine>())
+            .map_or(false, |absm| absm.set_layer_weight(layer_index, weight))
+    }
+}
+
 /// A set of data, that provides contextual information for script methods.
 pub struct ScriptMessageContext<'a, 'b, 'c> {
     /// Amount of time that passed from last call. It has valid values only when called from `on_update`.
@@ -282,6 +524,33 @@ pub struct ScriptDeinitContext<'a, 'b, 'c> {
     /// An message sender. Every message sent via this sender will be then passed to every [`ScriptTrait::on_message`]
     /// method of every script.
     pub message_sender: &'c ScriptMessageSender,
+
+    pub(crate) keep_alive: bool,
+}
+
+impl<'a, 'b, 'c> ScriptDeinitContext<'a, 'b, 'c> {
+    /// Asks the engine to keep the script instance alive for one more frame. The script won't be
+    /// destroyed, instead [`ScriptTrait::on_deinit`] will be called once again on next frame, and
+    /// the script will be destroyed only if it won't request keep alive again. It could be used to
+    /// finish some effect (for example, a death animation) without spawning a separate node for it.
+    ///
+    /// # Important
+    ///
+    /// The script is destroyed when its node is removed from the graph, so the node is not in the
+    /// graph when this method is called for the first time (see [`Self::node_handle`]). If the
+    /// script requests keep alive, the node (with its descendants) is put back to the graph with
+    /// the same handle, but it is detached from its former parent, invisible and disabled. It is
+    /// destroyed once its scripts stop requesting keep alive. The request is ignored when the
+    /// whole scene is destroyed. Keep in mind, that it is your responsibility to not block the
+    /// destruction indefinitely.
+    pub fn request_keep_alive(&mut self) {
+        self.keep_alive = true;
+    }
+
+    /// Returns true if the script requested to be kept alive for one more frame.
+    pub fn is_keep_alive_requested(&self) -> bool {
+        self.keep_alive
+    }
 }
 
 /// Script is a set predefined methods that are called on various stages by the engine. It is used to add
@@ -322,6 +591,25 @@ pub trait ScriptTrait: BaseScript + ComponentProvider {
     /// 60 times per second (this may change in future releases).
     fn on_update(&mut self, #[allow(unused_variables)] ctx: &mut ScriptContext) {}
 
+    /// Performs a single physics tick of the script. The method is called once per physics update
+    /// with the time step of the physics in `ctx.dt`, it should be used for physics-coupled logic,
+    /// such as applying forces or reading contact results.
+    ///
+    /// # Ordering
+    ///
+    /// The method is called after the physics solver has produced the state for the step, but before
+    /// [`ScriptTrait::on_update`] of any script in the scene. The method won't be called if the
+    /// physics wasn't updated.
+    fn on_physics_update(&mut self, #[allow(unused_variables)] ctx: &mut ScriptContext) {}
+
+    /// Defines the order in which the script is updated relative to other scripts in the same scene.
+    /// Scripts with lower values are updated first, scripts with equal values are updated in the order
+    /// of their node handles. The order applies to both [`ScriptTrait::on_physics_update`] and
+    /// [`ScriptTrait::on_update`]. Default value is `0`.
+    fn execution_order(&self) -> i32 {
+        0
+    }
+
     /// Allows you to react to certain script messages. It could be used for communication between scripts; to
     /// bypass borrowing issues. If you need to receive messages of a particular type, you must subscribe to a type
     /// explicitly. Usually it is done in [`ScriptTrait::on_start`] method:
@@ -577,6 +865,12 @@ impl Script {
             .downcast_mut::<T>()
     }
 
+    /// Returns type id of the actual script instance.
+    #[inline]
+    pub fn instance_type_id(&self) -> TypeId {
+        self.instance.deref().as_any_ref().type_id()
+    }
+
     /// Tries to borrow a component of given type.
     #[inline]
     pub fn query_component_ref<T: Any>(&self) -> Option<&T> {
@@ -592,19 +886,44 @@ impl Script {
             .query_component_mut(TypeId::of::<T>())
             .and_then(|c| c.downcast_mut())
     }
+
+    /// Serializes the data of the script instance into a compact binary blob. Internal flags
+    /// (whether the script was initialized or started) are not included, so the blob could be used
+    /// to send the state of an entity over the network, for example.
+    pub fn serialize_state(&self) -> Result<Vec<u8>, VisitError> {
+        let mut visitor = Visitor::new();
+        // Visit requires mutable access, so serialize a temporary copy of the instance.
+        self.instance.clone_box().visit("Data", &mut visitor)?;
+        visitor.save_binary_to_vec()
+    }
+
+    /// Restores the data of the script instance from a binary blob produced by
+    /// [`Self::serialize_state`]. Internal flags of the script are left untouched.
+    pub fn deserialize_state(&mut self, bytes: &[u8]) -> VisitResult {
+        let mut visitor = Visitor::load_from_memory(bytes.to_vec())?;
+        self.instance.visit("Data", &mut visitor)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
         core::{
-            reflect::prelude::*, uuid::Uuid, variable::try_inherit_properties,
-            variable::InheritableVariable, visitor::prelude::*,
+            reflect::prelude::*,
+            uuid::{uuid, Uuid},
+            variable::try_inherit_properties,
+            variable::InheritableVariable,
+            visitor::prelude::*,
         },
         impl_component_provider,
-        scene::base::Base,
-        script::{Script, ScriptTrait},
+        scene::{
+            base::{Base, BaseBuilder, NodeScriptMessage},
+            pivot::PivotBuilder,
+            Scene,
+        },
+        script::{Script, ScriptMessage, ScriptMessageKind, ScriptTrait},
     };
+    use std::any::TypeId;
 
     #[derive(Reflect, Visit, Debug, Clone, Default)]
     struct MyScript {
@@ -619,6 +938,129 @@ mod test {
         }
     }
 
+    #[derive(Reflect, Visit, Debug, Clone, Default)]
+    struct MyOtherScript {
+        field: f32,
+    }
+
+    impl_component_provider!(MyOtherScript);
+
+    impl ScriptTrait for MyOtherScript {
+        fn id(&self) -> Uuid {
+            uuid!("abaa7c4a-deb3-43cb-9d38-3f724d429528")
+        }
+    }
+
+    #[test]
+    fn test_iter_scripts() {
+        let mut scene = Scene::new();
+
+        let a = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(MyScript {
+            field: InheritableVariable::new(1.0),
+        })))
+        .build(&mut scene.graph);
+        PivotBuilder::new(BaseBuilder::new().with_script(Script::new(MyOtherScript::default())))
+            .build(&mut scene.graph);
+        let c = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(MyScript {
+            field: InheritableVariable::new(2.0),
+        })))
+        .build(&mut scene.graph);
+
+        let found = scene
+            .iter_scripts::<MyScript>()
+            .map(|(handle, script)| (handle, *script.field))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(a, 1.0), (c, 2.0)]);
+
+        for (_, script) in scene.iter_scripts_mut::<MyScript>() {
+            script.field.set_value_and_mark_modified(3.0);
+        }
+        assert!(scene
+            .iter_scripts::<MyScript>()
+            .all(|(_, script)| *script.field == 3.0));
+        assert_eq!(scene.iter_scripts::<MyOtherScript>().count(), 1);
+    }
+
+    #[test]
+    fn test_visit_scripts() {
+        let mut scene = Scene::new();
+
+        let a = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(MyScript::default())))
+            .build(&mut scene.graph);
+        PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let c = PivotBuilder::new(
+            BaseBuilder::new().with_script(Script::new(MyOtherScript::default())),
+        )
+        .build(&mut scene.graph);
+
+        let mut visited = Vec::new();
+        scene.visit_scripts(|handle, script| {
+            script.initialized = true;
+            visited.push(handle);
+        });
+        assert_eq!(visited, vec![a, c]);
+        assert!(scene.graph[a].script().unwrap().initialized);
+        assert!(scene.graph[c].script().unwrap().initialized);
+    }
+
+    #[test]
+    fn test_restart_scripts() {
+        let mut scene = Scene::new();
+
+        let a = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(MyScript::default())))
+            .build(&mut scene.graph);
+        PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let c = PivotBuilder::new(
+            BaseBuilder::new().with_script(Script::new(MyOtherScript::default())),
+        )
+        .build(&mut scene.graph);
+
+        // Simulate the first update pass of the engine.
+        while scene.graph.script_message_receiver.try_recv().is_ok() {}
+        scene.visit_scripts(|_, script| {
+            script.initialized = true;
+            script.started = true;
+        });
+
+        scene.restart_scripts();
+
+        for handle in [a, c] {
+            let script = scene.graph[handle].script().unwrap();
+            assert!(script.initialized);
+            assert!(!script.started);
+        }
+
+        // The next update pass of the engine must start the scripts again.
+        let mut queued = Vec::new();
+        while let Ok(message) = scene.graph.script_message_receiver.try_recv() {
+            if let NodeScriptMessage::InitializeScript { handle } = message {
+                queued.push(handle);
+            }
+        }
+        assert_eq!(queued, vec![a, c]);
+    }
+
+    #[test]
+    fn test_script_state_serialization() {
+        let source = Script::new(MyScript {
+            field: InheritableVariable::new(1.23),
+        });
+
+        let bytes = source.serialize_state().unwrap();
+
+        let mut dest = Script::new(MyScript::default());
+        dest.initialized = true;
+        dest.deserialize_state(&bytes).unwrap();
+
+        assert_eq!(
+            dest.cast::<MyScript>().unwrap().field,
+            source.cast::<MyScript>().unwrap().field
+        );
+        // Internal flags are not part of the state.
+        assert!(dest.initialized);
+        assert!(!dest.started);
+    }
+
     #[test]
     fn test_script_property_inheritance_on_nodes() {
         let mut child = Base::default();
@@ -685,4 +1127,21 @@ mod test {
             3.21
         );
     }
+
+    #[test]
+    fn test_script_message_payload() {
+        struct MyMessage(u32);
+
+        let mut message = ScriptMessage {
+            payload: Box::new(MyMessage(1)),
+            kind: ScriptMessageKind::Global,
+        };
+
+        assert_eq!(message.payload_type_id(), TypeId::of::<MyMessage>());
+        assert!(message.payload_as::<u32>().is_none());
+        assert_eq!(message.payload_as::<MyMessage>().unwrap().0, 1);
+
+        message.payload_as_mut::<MyMessage>().unwrap().0 = 2;
+        assert_eq!(message.payload.downcast_ref::<MyMessage>().unwrap().0, 2);
+    }
 }
@@ -34,7 +34,7 @@ use crate::{
     utils::{uvgen, uvgen::SurfaceDataPatch},
 };
 use fxhash::FxHashMap;
-use rayon::prelude::*;
+use rayon::{prelude::*, ThreadPoolBuilder};
 use std::fmt::{Display, Formatter};
 use std::{
     ops::Deref,
@@ -45,6 +45,10 @@ use std::{
     },
 };
 
+/// Maximum size (in pixels) of a side of lightmap texture. Size of a texture is clamped to this
+/// value regardless of the requested texel density, otherwise huge meshes could exhaust memory.
+pub const MAX_LIGHTMAP_SIZE: u32 = 4096;
+
 ///
 #[derive(Default, Clone, Debug, Visit)]
 pub struct LightmapEntry {
@@ -225,13 +229,89 @@ impl From<VertexFetchError> for LightmapGenerationError {
     }
 }
 
+/// Pixel format of generated lightmap textures.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LightmapFormat {
+    /// 8-bit per channel RGB texture. Accumulated lighting is clamped to [0; 1] range, which
+    /// makes it lossy for bright areas.
+    Rgb8,
+    /// 32-bit floating point RGBA texture. Accumulated lighting is stored in linear space as is,
+    /// without any clamping or tone mapping, so it can contain values larger than 1.0 (HDR).
+    Rgba32F,
+}
+
+impl Default for LightmapFormat {
+    fn default() -> Self {
+        Self::Rgb8
+    }
+}
+
+impl LightmapFormat {
+    /// Returns pixel kind of textures in this format.
+    pub fn pixel_kind(self) -> TexturePixelKind {
+        match self {
+            LightmapFormat::Rgb8 => TexturePixelKind::RGB8,
+            LightmapFormat::Rgba32F => TexturePixelKind::RGBA32F,
+        }
+    }
+
+    /// Returns extension of files that is used to save textures in this format.
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            LightmapFormat::Rgb8 => "png",
+            LightmapFormat::Rgba32F => "tiff",
+        }
+    }
+}
+
+/// A set of options for lightmap generation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LightmapOptions {
+    /// Pixel format of generated lightmap textures. Default is [`LightmapFormat::Rgb8`].
+    pub output: LightmapFormat,
+    /// If set, lightmaps of surfaces are generated in parallel (in addition to per-pixel
+    /// parallelism within each surface). It speeds up generation of scenes with lots of small
+    /// surfaces. Default is `true`.
+    pub parallel_surfaces: bool,
+    /// Maximum amount of threads that will be used to calculate light. `None` means that all
+    /// available CPU cores will be used. Default is `None`.
+    pub max_threads: Option<usize>,
+    /// If set, every lit pixel casts rays towards lights and it is not lit by a light if there's
+    /// an occluder between them. Default is `true`.
+    pub cast_shadows: bool,
+    /// Amount of shadow rays per light for each pixel. If it is more than one, rays are cast towards
+    /// points evenly spread over a sphere of [`Self::shadow_radius`] around a light, and the light
+    /// is attenuated by the fraction of occluded rays. It makes shadows soft, but the time needed
+    /// to calculate shadows grows linearly with the amount of samples. Default is `1` (hard shadows).
+    pub shadow_samples: u32,
+    /// Radius (in world units) of lights for soft shadows, the larger the radius, the softer the
+    /// shadows. It is used only if [`Self::shadow_samples`] is more than one. Default is `0.25`.
+    pub shadow_radius: f32,
+}
+
+impl Default for LightmapOptions {
+    fn default() -> Self {
+        Self {
+            output: Default::default(),
+            parallel_surfaces: true,
+            max_threads: None,
+            cast_shadows: true,
+            shadow_samples: 1,
+            shadow_radius: 0.25,
+        }
+    }
+}
+
 impl Lightmap {
     /// Generates lightmap for given scene. This method **automatically** generates secondary
     /// texture coordinates! This method is blocking, however internally it uses massive parallelism
     /// to use all available CPU power efficiently.
     ///
-    /// `texels_per_unit` defines resolution of lightmap, the higher value is, the more quality
-    /// lightmap will be generated, but also it will be slow to generate.
+    /// `texels_per_unit` defines resolution of lightmap (amount of texels per world unit), the
+    /// higher value is, the more quality lightmap will be generated, but also it will take more
+    /// memory and time to generate: size of a texture grows linearly with the density (but it is
+    /// clamped to [`MAX_LIGHTMAP_SIZE`]), amount of texels and so generation time grows with
+    /// the square of the density.
     /// `progress_indicator` allows you to get info about current progress.
     /// `cancellation_token` allows you to stop generation in any time.
     pub fn new(
@@ -239,6 +319,24 @@ impl Lightmap {
         texels_per_unit: u32,
         cancellation_token: CancellationToken,
         progress_indicator: ProgressIndicator,
+    ) -> Result<Self, LightmapGenerationError> {
+        Self::new_with_options(
+            scene,
+            texels_per_unit,
+            Default::default(),
+            cancellation_token,
+            progress_indicator,
+        )
+    }
+
+    /// Does the same as [`Self::new`], but allows you to specify additional generation options,
+    /// such as output format of lightmap textures. See [`LightmapOptions`] for more info.
+    pub fn new_with_options(
+        scene: &mut Scene,
+        texels_per_unit: u32,
+        options: LightmapOptions,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
     ) -> Result<Self, LightmapGenerationError> {
         scene.graph.update_hierarchical_data();
 
@@ -423,19 +521,50 @@ impl Lightmap {
 
         progress_indicator.set_stage(ProgressStage::CalculatingLight, instances.len() as u32);
 
-        let mut map: FxHashMap<Handle<Node>, Vec<LightmapEntry>> = FxHashMap::default();
-        for instance in instances.iter() {
+        let bake_instance = |instance: &Instance| {
             if cancellation_token.is_cancelled() {
-                return Err(LightmapGenerationError::Cancelled);
+                Err(LightmapGenerationError::Cancelled)
+            } else {
+                let lightmap =
+                    generate_lightmap(instance, &instances, &lights, texels_per_unit, &options);
+
+                progress_indicator.advance_progress();
+
+                Ok((instance.owner, lightmap))
             }
+        };
+
+        // Every surface is baked independently, so they could be processed in parallel. Results
+        // are collected in the same order as instances, so the output does not depend on the
+        // order in which surfaces were baked.
+        let bake = || {
+            if options.parallel_surfaces {
+                instances
+                    .par_iter()
+                    .map(bake_instance)
+                    .collect::<Result<Vec<_>, LightmapGenerationError>>()
+            } else {
+                instances
+                    .iter()
+                    .map(bake_instance)
+                    .collect::<Result<Vec<_>, LightmapGenerationError>>()
+            }
+        };
+
+        let lightmaps = match options
+            .max_threads
+            .and_then(|count| ThreadPoolBuilder::new().num_threads(count).build().ok())
+        {
+            Some(thread_pool) => thread_pool.install(bake),
+            None => bake(),
+        }?;
 
-            let lightmap = generate_lightmap(instance, &instances, &lights, texels_per_unit);
-            map.entry(instance.owner).or_default().push(LightmapEntry {
+        let mut map: FxHashMap<Handle<Node>, Vec<LightmapEntry>> = FxHashMap::default();
+        for (owner, lightmap) in lightmaps {
+            map.entry(owner).or_default().push(LightmapEntry {
                 texture: Some(Texture(Resource::new(TextureState::Ok(lightmap)))),
                 lights: lights.iter().map(|light| light.handle()).collect(),
             });
-
-            progress_indicator.advance_progress();
         }
 
         Ok(Self { map, patches })
@@ -454,8 +583,13 @@ impl Lightmap {
         for (handle, entries) in self.map.iter() {
             let handle_path = handle.index().to_string();
             for (i, entry) in entries.iter().enumerate() {
-                let file_path = handle_path.clone() + "_" + i.to_string().as_str() + ".png";
                 let texture = entry.texture.clone().unwrap();
+                let extension = if texture.data_ref().pixel_kind() == TexturePixelKind::RGBA32F {
+                    LightmapFormat::Rgba32F.file_extension()
+                } else {
+                    LightmapFormat::Rgb8.file_extension()
+                };
+                let file_path = format!("{}_{}.{}", handle_path, i, extension);
                 resource_manager.register_texture(texture, base_path.as_ref().join(file_path))?;
             }
         }
@@ -534,7 +668,7 @@ impl LightDefinition {
 }
 
 /// Computes total area of triangles in surface data and returns size of square
-/// in which triangles can fit.
+/// in which triangles can fit, the size is clamped to [`MAX_LIGHTMAP_SIZE`].
 fn estimate_size(data: &InstanceData, texels_per_unit: u32) -> u32 {
     let mut area = 0.0;
     for triangle in data.triangles.iter() {
@@ -543,7 +677,9 @@ fn estimate_size(data: &InstanceData, texels_per_unit: u32) -> u32 {
         let c = data.vertices[triangle[2] as usize].world_position;
         area += math::triangle_area(a, b, c);
     }
-    area.sqrt().ceil() as u32 * texels_per_unit
+    (area.sqrt().ceil() as u32)
+        .saturating_mul(texels_per_unit)
+        .clamp(1, MAX_LIGHTMAP_SIZE)
 }
 
 /// Calculates distance attenuation for a point using given distance to the point and
@@ -589,10 +725,13 @@ fn pick(
                     let nb = data.vertices[ib].world_normal;
                     let nc = data.vertices[ic].world_normal;
 
-                    return Some((
-                        math::barycentric_to_world(barycentric, a, b, c),
-                        math::barycentric_to_world(barycentric, na, nb, nc),
-                    ));
+                    // Interpolated normal must be re-normalized, otherwise lighting will be
+                    // darker inside of triangles with different vertex normals.
+                    let normal = math::barycentric_to_world(barycentric, na, nb, nc)
+                        .try_normalize(std::f32::EPSILON)
+                        .unwrap_or(na);
+
+                    return Some((math::barycentric_to_world(barycentric, a, b, c), normal));
                 }
 
                 // Offset uv to center for conservative rasterization.
@@ -669,18 +808,70 @@ fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
     k * k * (3.0 - 2.0 * k)
 }
 
+/// Returns an offset of i-th shadow sample on a unit sphere, samples are evenly distributed over the
+/// sphere (Fibonacci sphere). The sequence is deterministic, so the output of the lightmapper does
+/// not depend on the order in which pixels are processed.
+fn shadow_sample_offset(i: u32, sample_count: u32) -> Vector3<f32> {
+    if sample_count <= 1 {
+        return Vector3::default();
+    }
+
+    // Golden angle in radians.
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    let y = 1.0 - 2.0 * (i as f32 + 0.5) / sample_count as f32;
+    let r = (1.0 - y * y).max(0.0).sqrt();
+    let phi = i as f32 * golden_angle;
+    Vector3::new(phi.cos() * r, y, phi.sin() * r)
+}
+
+/// Checks whether there is any triangle between the light and the point.
+fn is_occluded(
+    light_position: Vector3<f32>,
+    world_position: Vector3<f32>,
+    instances: &[Instance],
+) -> bool {
+    let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 64>::new();
+    let shadow_bias = 0.01;
+    let ray = Ray::from_two_points(light_position, world_position);
+    for instance in instances {
+        let data = instance.data();
+        data.octree.ray_query_static(&ray, &mut query_buffer);
+        for &node in query_buffer.iter() {
+            match data.octree.node(node) {
+                OctreeNode::Leaf { indices, .. } => {
+                    for &triangle_index in indices {
+                        let triangle = &data.triangles[triangle_index as usize];
+                        let va = data.vertices[triangle[0] as usize].world_position;
+                        let vb = data.vertices[triangle[1] as usize].world_position;
+                        let vc = data.vertices[triangle[2] as usize].world_position;
+                        if let Some(pt) = ray.triangle_intersection_point(&[va, vb, vc]) {
+                            if ray.origin.metric_distance(&pt) + shadow_bias < ray.dir.norm() {
+                                return true;
+                            }
+                        }
+                    }
+                }
+                OctreeNode::Branch { .. } => unreachable!(),
+            }
+        }
+    }
+    false
+}
+
 /// Generates lightmap for given surface data with specified transform.
 ///
 /// # Performance
 ///
 /// This method is has linear complexity - the more complex mesh you pass, the more
-/// time it will take. Required time increases drastically if you enable shadows and
-/// global illumination (TODO), because in this case your data will be raytraced.
+/// time it will take. Required time increases drastically if you enable shadows (especially
+/// soft shadows) and global illumination (TODO), because in this case your data will be
+/// raytraced.
 fn generate_lightmap(
     instance: &Instance,
     other_instances: &[Instance],
     lights: &[LightDefinition],
     texels_per_unit: u32,
+    options: &LightmapOptions,
 ) -> TextureData {
     // We have to re-generate new set of world-space vertices because UV generator
     // may add new vertices on seams.
@@ -688,14 +879,14 @@ fn generate_lightmap(
     let scale = 1.0 / atlas_size as f32;
     let grid = Grid::new(instance.data(), (atlas_size / 32).max(4) as usize);
 
-    let mut pixels: Vec<Vector4<u8>> =
-        vec![Vector4::new(0, 0, 0, 0); (atlas_size * atlas_size) as usize];
+    let mut pixels: Vec<Vector4<f32>> =
+        vec![Vector4::new(0.0, 0.0, 0.0, 0.0); (atlas_size * atlas_size) as usize];
 
     let half_pixel = scale * 0.5;
     pixels
         .par_iter_mut()
         .enumerate()
-        .for_each(|(i, pixel): (usize, &mut Vector4<u8>)| {
+        .for_each(|(i, pixel): (usize, &mut Vector4<f32>)| {
             let x = i as u32 % atlas_size;
             let y = i as u32 / atlas_size;
 
@@ -708,7 +899,11 @@ fn generate_lightmap(
                         LightDefinition::Directional(directional) => {
                             let attenuation = directional.intensity
                                 * lambertian(directional.direction, world_normal);
-                            (directional.color, attenuation, Vector3::default())
+                            // Directional light has no position, so shadow rays are cast from
+                            // a far point along the light direction.
+                            let shadow_ray_origin =
+                                world_position + directional.direction.scale(1000.0);
+                            (directional.color, attenuation, shadow_ray_origin)
                         }
                         LightDefinition::Spot(spot) => {
                             let d = spot.position - world_position;
@@ -733,66 +928,45 @@ fn generate_lightmap(
                         }
                     };
                     // Shadows
-                    if attenuation >= 0.01 {
-                        let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 64>::new();
-                        let shadow_bias = 0.01;
-                        let ray = Ray::from_two_points(light_position, world_position);
-                        'outer_loop: for other_instance in other_instances {
-                            other_instance
-                                .data()
-                                .octree
-                                .ray_query_static(&ray, &mut query_buffer);
-                            for &node in query_buffer.iter() {
-                                match other_instance.data().octree.node(node) {
-                                    OctreeNode::Leaf { indices, .. } => {
-                                        let other_data = other_instance.data();
-                                        for &triangle_index in indices {
-                                            let triangle =
-                                                &other_data.triangles[triangle_index as usize];
-                                            let va = other_data.vertices[triangle[0] as usize]
-                                                .world_position;
-                                            let vb = other_data.vertices[triangle[1] as usize]
-                                                .world_position;
-                                            let vc = other_data.vertices[triangle[2] as usize]
-                                                .world_position;
-                                            if let Some(pt) =
-                                                ray.triangle_intersection_point(&[va, vb, vc])
-                                            {
-                                                if ray.origin.metric_distance(&pt) + shadow_bias
-                                                    < ray.dir.norm()
-                                                {
-                                                    attenuation = 0.0;
-                                                    break 'outer_loop;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    OctreeNode::Branch { .. } => unreachable!(),
-                                }
-                            }
-                        }
+                    if options.cast_shadows && attenuation >= 0.01 {
+                        let sample_count = options.shadow_samples.max(1);
+                        let lit_samples = (0..sample_count)
+                            .filter(|&i| {
+                                let sample_position = light_position
+                                    + shadow_sample_offset(i, sample_count)
+                                        .scale(options.shadow_radius);
+                                !is_occluded(sample_position, world_position, other_instances)
+                            })
+                            .count();
+                        attenuation *= lit_samples as f32 / sample_count as f32;
                     }
                     pixel_color += light_color.scale(attenuation);
                 }
 
+                // Low dynamic range formats cannot store values outside of [0; 1] range, HDR
+                // formats store accumulated linear values as is.
+                if options.output == LightmapFormat::Rgb8 {
+                    pixel_color = pixel_color.map(|c| c.clamp(0.0, 1.0));
+                }
+
                 *pixel = Vector4::new(
-                    (pixel_color.x.clamp(0.0, 1.0) * 255.0) as u8,
-                    (pixel_color.y.clamp(0.0, 1.0) * 255.0) as u8,
-                    (pixel_color.z.clamp(0.0, 1.0) * 255.0) as u8,
-                    255, // Indicates that this pixel was "filled"
+                    pixel_color.x,
+                    pixel_color.y,
+                    pixel_color.z,
+                    1.0, // Indicates that this pixel was "filled"
                 );
             }
         });
 
     // Prepare light map for bilinear filtration. This step is mandatory to prevent bleeding.
-    let mut rgb_pixels: Vec<Vector3<u8>> = Vec::with_capacity((atlas_size * atlas_size) as usize);
+    let mut rgb_pixels: Vec<Vector3<f32>> = Vec::with_capacity((atlas_size * atlas_size) as usize);
     for y in 0..(atlas_size as i32) {
         for x in 0..(atlas_size as i32) {
-            let fetch = |dx: i32, dy: i32| -> Option<Vector3<u8>> {
+            let fetch = |dx: i32, dy: i32| -> Option<Vector3<f32>> {
                 pixels
                     .get(((y + dy) * (atlas_size as i32) + x + dx) as usize)
                     .and_then(|p| {
-                        if p.w != 0 {
+                        if p.w != 0.0 {
                             Some(Vector3::new(p.x, p.y, p.z))
                         } else {
                             None
@@ -801,7 +975,7 @@ fn generate_lightmap(
             };
 
             let src_pixel = pixels[(y * (atlas_size as i32) + x) as usize];
-            if src_pixel.w == 0 {
+            if src_pixel.w == 0.0 {
                 // Check neighbour pixels marked as "filled" and use it as value.
                 if let Some(west) = fetch(-1, 0) {
                     rgb_pixels.push(west);
@@ -820,7 +994,7 @@ fn generate_lightmap(
                 } else if let Some(south_west) = fetch(-1, 1) {
                     rgb_pixels.push(south_west);
                 } else {
-                    rgb_pixels.push(Vector3::new(0, 0, 0));
+                    rgb_pixels.push(Vector3::new(0.0, 0.0, 0.0));
                 }
             } else {
                 rgb_pixels.push(Vector3::new(src_pixel.x, src_pixel.y, src_pixel.z))
@@ -829,17 +1003,14 @@ fn generate_lightmap(
     }
 
     // Blur lightmap using simplest box filter.
-    let mut bytes = Vec::with_capacity((atlas_size * atlas_size * 3) as usize);
+    let mut blurred = Vec::with_capacity((atlas_size * atlas_size) as usize);
     for y in 0..(atlas_size as i32) {
         for x in 0..(atlas_size as i32) {
             if x < 1 || y < 1 || x + 1 == atlas_size as i32 || y + 1 == atlas_size as i32 {
-                bytes.extend_from_slice(
-                    rgb_pixels[(y * (atlas_size as i32) + x) as usize].as_slice(),
-                );
+                blurred.push(rgb_pixels[(y * (atlas_size as i32) + x) as usize]);
             } else {
-                let fetch = |dx: i32, dy: i32| -> Vector3<i16> {
-                    let u8_pixel = rgb_pixels[((y + dy) * (atlas_size as i32) + x + dx) as usize];
-                    Vector3::new(u8_pixel.x as i16, u8_pixel.y as i16, u8_pixel.z as i16)
+                let fetch = |dx: i32, dy: i32| -> Vector3<f32> {
+                    rgb_pixels[((y + dy) * (atlas_size as i32) + x + dx) as usize]
                 };
 
                 let north_west = fetch(-1, -1);
@@ -862,19 +1033,38 @@ fn generate_lightmap(
                     + south
                     + south_east;
 
-                bytes.push((sum.x / 9).clamp(0, 255) as u8);
-                bytes.push((sum.y / 9).clamp(0, 255) as u8);
-                bytes.push((sum.z / 9).clamp(0, 255) as u8);
+                blurred.push(sum.scale(1.0 / 9.0));
             }
         }
     }
 
+    let bytes = match options.output {
+        LightmapFormat::Rgb8 => {
+            let mut bytes = Vec::with_capacity((atlas_size * atlas_size * 3) as usize);
+            for pixel in blurred {
+                bytes.push((pixel.x.clamp(0.0, 1.0) * 255.0) as u8);
+                bytes.push((pixel.y.clamp(0.0, 1.0) * 255.0) as u8);
+                bytes.push((pixel.z.clamp(0.0, 1.0) * 255.0) as u8);
+            }
+            bytes
+        }
+        LightmapFormat::Rgba32F => {
+            let mut bytes = Vec::with_capacity((atlas_size * atlas_size * 16) as usize);
+            for pixel in blurred {
+                for component in [pixel.x, pixel.y, pixel.z, 1.0] {
+                    bytes.extend_from_slice(&component.to_ne_bytes());
+                }
+            }
+            bytes
+        }
+    };
+
     TextureData::from_bytes(
         TextureKind::Rectangle {
             width: atlas_size,
             height: atlas_size,
         },
-        TexturePixelKind::RGB8,
+        options.output.pixel_kind(),
         bytes,
         // Do not serialize content because lightmap is saved as a series of images in
         // a common format.
@@ -885,22 +1075,61 @@ fn generate_lightmap(
 
 #[cfg(test)]
 mod test {
+    use crate::resource::texture::{
+        CompressionOptions, TextureData, TextureKind, TexturePixelKind,
+    };
     use crate::scene::mesh::surface::SurfaceSharedData;
     use crate::{
-        core::algebra::{Matrix4, Vector3},
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector3},
+            pool::Handle,
+        },
         scene::{
             base::BaseBuilder,
-            light::{point::PointLightBuilder, BaseLightBuilder},
+            light::{
+                directional::DirectionalLightBuilder, point::PointLightBuilder,
+                spot::SpotLightBuilder, BaseLightBuilder,
+            },
             mesh::{
                 surface::{SurfaceBuilder, SurfaceData},
                 MeshBuilder,
             },
+            node::Node,
             transform::TransformBuilder,
             Scene,
         },
-        utils::lightmap::Lightmap,
+        utils::lightmap::{Lightmap, LightmapFormat, LightmapOptions},
     };
 
+    fn add_quad(scene: &mut Scene, transform: &Matrix4<f32>) -> Handle<Node> {
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_quad(transform),
+            ))
+            .build()])
+            .build(&mut scene.graph)
+    }
+
+    // 4x4 units quad, most of the tests bake lightmaps for it.
+    fn add_receiver_quad(scene: &mut Scene) -> Handle<Node> {
+        add_quad(
+            scene,
+            &Matrix4::new_nonuniform_scaling(&Vector3::new(4.0, 4.0, 1.0)),
+        )
+    }
+
+    fn add_point_light(scene: &mut Scene, position: Vector3<f32>, radius: f32) -> Handle<Node> {
+        PointLightBuilder::new(BaseLightBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        ))
+        .with_radius(radius)
+        .build(&mut scene.graph)
+    }
+
     #[test]
     fn test_generate_lightmap() {
         let mut scene = Scene::new();
@@ -918,15 +1147,7 @@ mod test {
             ])
             .build(&mut scene.graph);
 
-        PointLightBuilder::new(BaseLightBuilder::new(
-            BaseBuilder::new().with_local_transform(
-                TransformBuilder::new()
-                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
-                    .build(),
-            ),
-        ))
-        .with_radius(4.0)
-        .build(&mut scene.graph);
+        add_point_light(&mut scene, Vector3::new(0.0, 2.0, 0.0), 4.0);
 
         let lightmap =
             Lightmap::new(&mut scene, 64, Default::default(), Default::default()).unwrap();
@@ -941,4 +1162,361 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_generate_hdr_lightmap() {
+        let mut scene = Scene::new();
+
+        add_receiver_quad(&mut scene);
+
+        // Multiple lights at the same spot in front of the quad, accumulated light must
+        // exceed 1.0 there.
+        for _ in 0..3 {
+            add_point_light(&mut scene, Vector3::new(0.0, 0.0, -1.0), 10.0);
+        }
+
+        let lightmap = Lightmap::new_with_options(
+            &mut scene,
+            16,
+            LightmapOptions {
+                output: LightmapFormat::Rgba32F,
+                ..Default::default()
+            },
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        let mut max = 0.0f32;
+        for entry in lightmap.map.values().flatten() {
+            let data = entry.texture.as_ref().unwrap().data_ref();
+            assert_eq!(data.pixel_kind(), TexturePixelKind::RGBA32F);
+            for component in data.data().chunks_exact(4) {
+                max = max.max(f32::from_ne_bytes(component.try_into().unwrap()));
+            }
+        }
+        assert!(max > 1.0);
+
+        // Textures must survive a save/load round-trip without losing precision.
+        for (i, entry) in lightmap.map.values().flatten().enumerate() {
+            let path = std::env::temp_dir().join(format!(
+                "fyrox_hdr_lightmap_{}.{}",
+                i,
+                LightmapFormat::Rgba32F.file_extension()
+            ));
+            let mut data = entry.texture.as_ref().unwrap().data_ref();
+            data.set_path(&path);
+            data.save().unwrap();
+
+            let loaded = TextureData::load_from_memory(
+                &std::fs::read(&path).unwrap(),
+                CompressionOptions::NoCompression,
+                false,
+            )
+            .unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded.pixel_kind(), TexturePixelKind::RGBA32F);
+            assert_eq!(loaded.data(), data.data());
+        }
+
+        // Default format is still clamped 8-bit RGB.
+        let lightmap =
+            Lightmap::new(&mut scene, 16, Default::default(), Default::default()).unwrap();
+        for entry in lightmap.map.values().flatten() {
+            let data = entry.texture.as_ref().unwrap().data_ref();
+            assert_eq!(data.pixel_kind(), TexturePixelKind::RGB8);
+        }
+    }
+
+    #[test]
+    fn test_overlapping_lights_saturate() {
+        let bake = |light_count| {
+            let mut scene = Scene::new();
+
+            add_receiver_quad(&mut scene);
+
+            for _ in 0..light_count {
+                add_point_light(&mut scene, Vector3::new(0.0, 0.0, -1.0), 10.0);
+            }
+
+            let lightmap =
+                Lightmap::new(&mut scene, 8, Default::default(), Default::default()).unwrap();
+            let entry = lightmap.map.values().flatten().next().unwrap();
+            let data = entry.texture.as_ref().unwrap().data_ref();
+            data.data().to_vec()
+        };
+
+        let single = bake(1);
+        let stacked = bake(4);
+
+        // Stacked lights must saturate, not wrap around, so no texel becomes darker.
+        assert_eq!(single.len(), stacked.len());
+        assert!(single.iter().zip(stacked.iter()).all(|(a, b)| b >= a));
+        assert_eq!(stacked.iter().max(), Some(&255));
+    }
+
+    #[test]
+    fn test_directional_and_spot_lights() {
+        fn bake(add_light: fn(&mut Scene)) -> u8 {
+            let mut scene = Scene::new();
+
+            add_receiver_quad(&mut scene);
+
+            add_light(&mut scene);
+
+            let lightmap =
+                Lightmap::new(&mut scene, 16, Default::default(), Default::default()).unwrap();
+
+            lightmap
+                .map
+                .values()
+                .flatten()
+                .flat_map(|entry| entry.texture.as_ref().unwrap().data_ref().data().to_vec())
+                .max()
+                .unwrap_or_default()
+        }
+
+        // Both lights are in front of the quad and point towards it.
+        fn facing_quad() -> UnitQuaternion<f32> {
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -std::f32::consts::FRAC_PI_2)
+        }
+
+        assert_eq!(bake(|_| ()), 0);
+
+        assert!(
+            bake(|scene| {
+                DirectionalLightBuilder::new(BaseLightBuilder::new(
+                    BaseBuilder::new().with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_rotation(facing_quad())
+                            .build(),
+                    ),
+                ))
+                .build(&mut scene.graph);
+            }) > 0
+        );
+
+        assert!(
+            bake(|scene| {
+                SpotLightBuilder::new(BaseLightBuilder::new(
+                    BaseBuilder::new().with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.0, 0.0, -1.0))
+                            .with_local_rotation(facing_quad())
+                            .build(),
+                    ),
+                ))
+                .with_distance(10.0)
+                .build(&mut scene.graph);
+            }) > 0
+        );
+
+        // Spot light that points away from the quad must not light it.
+        assert!(
+            bake(|scene| {
+                SpotLightBuilder::new(BaseLightBuilder::new(
+                    BaseBuilder::new().with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.0, 0.0, -1.0))
+                            .with_local_rotation(facing_quad().inverse())
+                            .build(),
+                    ),
+                ))
+                .with_distance(10.0)
+                .build(&mut scene.graph);
+            }) == 0
+        );
+    }
+
+    #[test]
+    fn test_shadows() {
+        let bake = |options: LightmapOptions| {
+            let mut scene = Scene::new();
+
+            let receiver = add_receiver_quad(&mut scene);
+
+            // Small quad between the light and the receiver.
+            add_quad(
+                &mut scene,
+                &(Matrix4::new_translation(&Vector3::new(0.0, 0.0, -1.0))
+                    * Matrix4::new_scaling(1.5)),
+            );
+
+            add_point_light(&mut scene, Vector3::new(0.0, 0.0, -2.0), 10.0);
+
+            let lightmap = Lightmap::new_with_options(
+                &mut scene,
+                16,
+                options,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+
+            let entry = &lightmap.map[&receiver][0];
+            let data = entry.texture.as_ref().unwrap().data_ref();
+            data.data().to_vec()
+        };
+
+        let brightness = |data: &[u8]| data.iter().map(|&c| c as u32).sum::<u32>();
+
+        let unshadowed = bake(LightmapOptions {
+            cast_shadows: false,
+            ..Default::default()
+        });
+        let hard = bake(Default::default());
+        let soft = bake(LightmapOptions {
+            shadow_samples: 16,
+            shadow_radius: 0.5,
+            ..Default::default()
+        });
+
+        // The occluder makes the receiver darker.
+        assert!(brightness(&hard) < brightness(&unshadowed));
+        assert!(brightness(&soft) < brightness(&unshadowed));
+
+        // Soft shadows have penumbra, so they differ from hard shadows.
+        assert_ne!(hard, soft);
+    }
+
+    #[test]
+    fn test_parallel_surfaces_match_serial_bake() {
+        // UV generation modifies surfaces, so each bake gets its own copy of the scene.
+        fn make_scene() -> Scene {
+            let mut scene = Scene::new();
+
+            for i in 0..4 {
+                let cone = SurfaceData::make_cone(
+                    8,
+                    1.0,
+                    1.0,
+                    &Matrix4::new_translation(&Vector3::new(i as f32 * 2.0, 0.0, 0.0)),
+                );
+                let quad = SurfaceData::make_quad(&Matrix4::new_nonuniform_scaling(&Vector3::new(
+                    2.0, 2.0, 1.0,
+                )));
+
+                MeshBuilder::new(BaseBuilder::new())
+                    .with_surfaces(vec![
+                        SurfaceBuilder::new(SurfaceSharedData::new(cone)).build(),
+                        SurfaceBuilder::new(SurfaceSharedData::new(quad)).build(),
+                    ])
+                    .build(&mut scene.graph);
+            }
+
+            add_point_light(&mut scene, Vector3::new(3.0, 2.0, -1.0), 10.0);
+
+            scene
+        }
+
+        let bake = |parallel_surfaces| {
+            Lightmap::new_with_options(
+                &mut make_scene(),
+                16,
+                LightmapOptions {
+                    parallel_surfaces,
+                    max_threads: Some(2),
+                    ..Default::default()
+                },
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap()
+        };
+
+        let serial = bake(false);
+        let parallel = bake(true);
+
+        assert_eq!(serial.map.len(), 4);
+        assert_eq!(serial.map.len(), parallel.map.len());
+        for (owner, serial_entries) in serial.map.iter() {
+            let parallel_entries = parallel.map.get(owner).unwrap();
+            assert_eq!(serial_entries.len(), 2);
+            assert_eq!(serial_entries.len(), parallel_entries.len());
+            for (a, b) in serial_entries.iter().zip(parallel_entries) {
+                let a = a.texture.as_ref().unwrap().data_ref();
+                let b = b.texture.as_ref().unwrap().data_ref();
+                assert_eq!(a.data(), b.data());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_pixels_match_single_threaded_bake() {
+        fn make_scene() -> Scene {
+            let mut scene = Scene::new();
+
+            let sphere = SurfaceData::make_sphere(16, 16, 1.0, &Matrix4::identity());
+
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![
+                    SurfaceBuilder::new(SurfaceSharedData::new(sphere)).build()
+                ])
+                .build(&mut scene.graph);
+
+            add_point_light(&mut scene, Vector3::new(1.0, 2.0, -1.5), 6.0);
+
+            scene
+        }
+
+        let bake = |options| {
+            Lightmap::new_with_options(
+                &mut make_scene(),
+                32,
+                options,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap()
+        };
+
+        // Single thread pool makes the per-pixel pass serial.
+        let serial = bake(LightmapOptions {
+            parallel_surfaces: false,
+            max_threads: Some(1),
+            ..Default::default()
+        });
+        let parallel = bake(Default::default());
+
+        let serial = serial.map.values().flatten().collect::<Vec<_>>();
+        let parallel = parallel.map.values().flatten().collect::<Vec<_>>();
+        assert_eq!(serial.len(), 1);
+        assert_eq!(serial.len(), parallel.len());
+        for (a, b) in serial.iter().zip(parallel.iter()) {
+            let a = a.texture.as_ref().unwrap().data_ref();
+            let b = b.texture.as_ref().unwrap().data_ref();
+            assert_eq!(a.data(), b.data());
+        }
+    }
+
+    #[test]
+    fn test_texel_density() {
+        let size = |texels_per_unit| {
+            let mut scene = Scene::new();
+
+            add_receiver_quad(&mut scene);
+
+            let lightmap = Lightmap::new(
+                &mut scene,
+                texels_per_unit,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+
+            let entry = lightmap.map.values().flatten().next().unwrap();
+            let kind = entry.texture.as_ref().unwrap().data_ref().kind();
+            match kind {
+                TextureKind::Rectangle { width, height } => {
+                    assert_eq!(width, height);
+                    width
+                }
+                _ => unreachable!(),
+            }
+        };
+
+        assert_eq!(size(4), 16);
+        assert_eq!(size(8), 2 * size(4));
+    }
 }